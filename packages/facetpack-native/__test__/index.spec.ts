@@ -2,10 +2,14 @@ import test from 'ava'
 
 import {
   FacetPack,
+  minify,
+  parse,
   parseSync,
+  transform,
   transformSync,
   SourceType,
   JsxRuntime,
+  Locale,
 } from '../index'
 
 test('FacetPack class instantiation', (t) => {
@@ -149,3 +153,29 @@ test('ES modules syntax', (t) => {
   t.true(result.code.includes('import'))
   t.true(result.code.includes('export'))
 })
+
+test('transform - async', async (t) => {
+  const result = await transform('test.ts', 'const x: number = 1;')
+
+  t.is(result.errors.length, 0)
+  t.false(result.code.includes(':'))
+})
+
+test('parse and minify - async', async (t) => {
+  const parseResult = await parse('test.js', 'const x = 1;')
+  t.false(parseResult.panicked)
+
+  const minifyResult = await minify('function add(a, b) { return a + b; }', 'add.js')
+  t.true(minifyResult.code.length > 0)
+})
+
+test('FacetPack async methods - instance settings', async (t) => {
+  const facetpack = new FacetPack({ locale: Locale.Fr })
+  const source = 'let a = 1;\nlet a = 2;'
+
+  const result = await facetpack.transformAsync('test.js', source)
+  t.deepEqual(result.diagnostics, facetpack.transform('test.js', source).diagnostics)
+
+  const parseResult = await facetpack.parseAsync('test.js', 'const x = 1;')
+  t.false(parseResult.panicked)
+})
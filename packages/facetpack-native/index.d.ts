@@ -1,16 +1,65 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
 export declare class FacetPack {
-  constructor()
+  constructor(options?: FacetPackOptions | undefined | null)
   parse(filename: string, sourceText: string, options?: ParseOptions | undefined | null): ParseResult
+  parseAsync(filename: string, sourceText: string, options?: ParseOptions | undefined | null, signal?: AbortSignal | undefined | null): Promise<ParseResult>
   transform(filename: string, sourceText: string, options?: TransformOptions | undefined | null): TransformResult
+  transformAsync(filename: string, sourceText: string, options?: TransformOptions | undefined | null, signal?: AbortSignal | undefined | null): Promise<TransformResult>
   resolve(directory: string, specifier: string, options?: ResolverOptions | undefined | null): ResolveResult
+  resolveAsync(directory: string, specifier: string, options?: ResolverOptions | undefined | null, signal?: AbortSignal | undefined | null): Promise<ResolveResult>
 }
 
+export declare function analyze(filename: string, sourceText: string, signal?: AbortSignal | undefined | null): Promise<ModuleAnalysis>
+
 export declare function analyzeBatchSync(modules: Array<ModuleInput>): Array<ModuleAnalysis>
 
 export declare function analyzeSync(filename: string, sourceText: string): ModuleAnalysis
 
+export declare const enum AstFormat {
+  Estree = 'estree',
+  Babel = 'babel'
+}
+
+export interface DeclarationOptions {
+  locale?: Locale
+  stripInternal?: boolean
+  sourcemap?: boolean
+}
+
+export interface DeclarationResult {
+  code: string
+  map?: string
+  errors: Array<string>
+  diagnostics: Array<Diagnostic>
+}
+
+export interface Dependency {
+  specifier: string
+  kind: DependencyKind
+  isTypeOnly: boolean
+  isAsync: boolean
+  isOptional: boolean
+  span: SourceSpan
+}
+
+export declare const enum DependencyKind {
+  Import = 'Import',
+  ReExport = 'ReExport',
+  Require = 'Require',
+  DynamicImport = 'DynamicImport',
+  RequireResolve = 'RequireResolve',
+  /**
+   * The directory of a `require.context()` call. Its file list changes when files are
+   * added or removed, so caches of the file depend on it.
+   */
+  Context = 'Context'
+}
+
+/**
+ * Lines and columns are 1-based, and columns count UTF-16 code units like JavaScript
+ * strings and editors do.
+ */
 export interface Diagnostic {
   severity: DiagnosticSeverity
   code?: string
@@ -20,6 +69,7 @@ export interface Diagnostic {
   column: number
   endLine?: number
   endColumn?: number
+  related: Array<RelatedLocation>
   snippet?: string
   label?: string
   help?: string
@@ -34,6 +84,18 @@ export declare const enum DiagnosticSeverity {
   Hint = 'Hint'
 }
 
+/** Output formats of `formatDiagnostics`. */
+export declare const enum DiagnosticFormat {
+  /** The `formatted` layout without colors. */
+  Text = 'Text',
+  /** GitHub Actions workflow commands, shown as annotations on pull requests. */
+  Github = 'Github',
+  /** SARIF 2.1.0 log, for code scanning. */
+  Sarif = 'Sarif',
+  /** JSON array of Language Server Protocol diagnostics. */
+  Lsp = 'Lsp'
+}
+
 export interface ExportInfo {
   name: string
   isDefault: boolean
@@ -41,17 +103,51 @@ export interface ExportInfo {
   source?: string
 }
 
+export interface FacetPackOptions {
+  /** Language of diagnostic explanations, from `LANG` when not set. */
+  locale?: Locale
+}
+
+/** Renders diagnostics as plain text, GitHub Actions annotations, SARIF or LSP JSON. */
+export declare function formatDiagnostics(diagnostics: Array<Diagnostic>, format: DiagnosticFormat): string
+
+export declare const enum HelperMode {
+  Runtime = 'runtime',
+  Inline = 'inline',
+  External = 'external'
+}
+
+export interface HelperOptions {
+  mode?: HelperMode
+  module?: string
+}
+
 export interface ImportInfo {
   source: string
   specifiers: Array<string>
   isSideEffect: boolean
 }
 
+export interface InlineRequiresOptions {
+  blockList?: Array<string>
+  nonInlinedRequires?: Array<string>
+}
+
+export declare function isolatedDeclaration(filename: string, sourceText: string, options?: DeclarationOptions | undefined | null, signal?: AbortSignal | undefined | null): Promise<DeclarationResult>
+
+export declare function isolatedDeclarationSync(filename: string, sourceText: string, options?: DeclarationOptions | undefined | null): DeclarationResult
+
 export declare const enum JsxRuntime {
   Automatic = 'Automatic',
   Classic = 'Classic'
 }
 
+/** The language of diagnostic explanations. Error codes are the same in every language. */
+export declare const enum Locale {
+  En = 'en',
+  Fr = 'fr'
+}
+
 export interface MinifyOptions {
   compress?: boolean
   mangle?: boolean
@@ -59,6 +155,7 @@ export interface MinifyOptions {
   dropConsole?: boolean
   dropDebugger?: boolean
   sourcemap?: boolean
+  inputSourceMap?: string
 }
 
 export interface MinifyResult {
@@ -66,6 +163,8 @@ export interface MinifyResult {
   map?: string
 }
 
+export declare function minify(code: string, filename: string, options?: MinifyOptions | undefined | null, signal?: AbortSignal | undefined | null): Promise<MinifyResult>
+
 export declare function minifySync(code: string, filename: string, options?: MinifyOptions | undefined | null): MinifyResult
 
 export interface ModuleAnalysis {
@@ -74,18 +173,49 @@ export interface ModuleAnalysis {
   hasSideEffects: boolean
 }
 
+export declare const enum ModuleFormat {
+  Esm = 'esm',
+  MetroCjs = 'metro-cjs',
+  Cjs = 'cjs'
+}
+
 export interface ModuleInput {
   path: string
   code: string
 }
 
+/**
+ * How a file is parsed with respect to ES module syntax, regardless of its extension.
+ * `Unambiguous` parses it as a module only if it has `import` or `export` statements.
+ */
+export declare const enum ModuleKind {
+  Module = 'Module',
+  Script = 'Script',
+  Unambiguous = 'Unambiguous'
+}
+
+export declare function parse(filename: string, sourceText: string, options?: ParseOptions | undefined | null, signal?: AbortSignal | undefined | null): Promise<ParseResult>
+
 export interface ParseOptions {
+  locale?: Locale
   sourceType?: SourceType
+  moduleKind?: ModuleKind
+  jsx?: boolean
   preserveParens?: boolean
+  allowReturnOutsideFunction?: boolean
+  allowV8Intrinsics?: boolean
+  comments?: boolean
+  tokens?: boolean
+  range?: boolean
+  loc?: boolean
+  /** Set to `false` to only check for errors, leaving `program` empty. Defaults to `true`. */
+  ast?: boolean
+  buffer?: boolean
 }
 
 export interface ParseResult {
   program: string
+  buffer?: Buffer
   errors: Array<string>
   diagnostics: Array<Diagnostic>
   panicked: boolean
@@ -93,6 +223,18 @@ export interface ParseResult {
 
 export declare function parseSync(filename: string, sourceText: string, options?: ParseOptions | undefined | null): ParseResult
 
+/** Another span a diagnostic refers to, such as the first declaration of a redeclared name. */
+export interface RelatedLocation {
+  message?: string
+  filename: string
+  line: number
+  column: number
+  endLine: number
+  endColumn: number
+}
+
+export declare function resolve(directory: string, specifier: string, options?: ResolverOptions | undefined | null, signal?: AbortSignal | undefined | null): Promise<ResolveResult>
+
 export declare function resolveBatchSync(directory: string, specifiers: Array<string>, options?: ResolverOptions | undefined | null): Array<ResolveResult>
 
 export interface ResolveResult {
@@ -104,10 +246,14 @@ export interface ResolverOptions {
   extensions?: Array<string>
   mainFields?: Array<string>
   conditionNames?: Array<string>
+  /** Maps a specifier, or a prefix of it followed by `/`, to another path. */
+  alias?: Record<string, string>
 }
 
 export declare function resolveSync(directory: string, specifier: string, options?: ResolverOptions | undefined | null): ResolveResult
 
+export declare function shake(filename: string, sourceText: string, usedExports: Array<string>, signal?: AbortSignal | undefined | null): Promise<ShakeResult>
+
 export interface ShakeResult {
   code: string
   map?: string
@@ -116,6 +262,11 @@ export interface ShakeResult {
 
 export declare function shakeSync(filename: string, sourceText: string, usedExports: Array<string>): ShakeResult
 
+export interface SourceSpan {
+  start: number
+  end: number
+}
+
 export declare const enum SourceType {
   Script = 'Script',
   Module = 'Module',
@@ -124,16 +275,73 @@ export declare const enum SourceType {
   Typescript = 'Typescript'
 }
 
+export declare function transform(filename: string, sourceText: string, options?: TransformOptions | undefined | null, signal?: AbortSignal | undefined | null): Promise<TransformResult>
+
+export declare function transformBatch(files: Array<TransformInput>, signal?: AbortSignal | undefined | null): Promise<Array<TransformBatchResult>>
+
+export interface TransformBatchResult {
+  filename: string
+  result?: TransformResult
+  error?: string
+}
+
+export declare function transformBatchSync(files: Array<TransformInput>): Array<TransformBatchResult>
+
+export interface TransformInput {
+  filename: string
+  source: string
+  options?: TransformOptions
+}
+
 export interface TransformOptions {
+  locale?: Locale
   sourceType?: SourceType
   jsx?: boolean
   jsxRuntime?: JsxRuntime
   jsxPragma?: string
   jsxPragmaFrag?: string
   jsxImportSource?: string
+  jsxDevelopment?: boolean
+  projectRoot?: string
   typescript?: boolean
+  onlyRemoveTypeImports?: boolean
+  allowDeclareFields?: boolean
+  allowNamespaces?: boolean
+  optimizeConstEnums?: boolean
+  experimentalDecorators?: boolean
+  emitDecoratorMetadata?: boolean
   flow?: boolean
   sourcemap?: boolean
+  target?: string
+  helpers?: HelperOptions
+  inputSourceMap?: string
+  worklets?: boolean
+  requireContext?: boolean
+  /**
+   * Extensions, without the dot, of the files `require.context()` can match. Defaults to
+   * Metro's `js`, `jsx`, `json`, `ts` and `tsx`.
+   */
+  sourceExts?: Array<string>
+  /** Aliases of the resolver, applied to `require.context()` directories. */
+  alias?: Record<string, string>
+  /**
+   * Also returns the transformed program as JSON in this format. It is parsed from the
+   * returned `code`, so its positions (`start`, `end`, `loc`) refer to the generated code,
+   * not to the source.
+   */
+  ast?: AstFormat
+  moduleFormat?: ModuleFormat
+  /**
+   * Inlines the top-level `require()` of the module where they are used. With the `esm`
+   * module format, imports are not lowered and only the `require()` calls of the source
+   * are inlined.
+   */
+  inlineRequires?: InlineRequiresOptions
+  define?: Record<string, string>
+  platform?: string
+  refresh?: boolean
+  refreshReg?: string
+  refreshSig?: string
 }
 
 export interface TransformResult {
@@ -141,6 +349,9 @@ export interface TransformResult {
   map?: string
   errors: Array<string>
   diagnostics: Array<Diagnostic>
+  helpersMode: HelperMode
+  dependencies: Array<Dependency>
+  ast?: string
 }
 
 export declare function transformSync(filename: string, sourceText: string, options?: TransformOptions | undefined | null): TransformResult
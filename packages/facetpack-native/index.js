@@ -577,14 +577,32 @@ if (!nativeBinding) {
 
 module.exports = nativeBinding
 module.exports.FacetPack = nativeBinding.FacetPack
+module.exports.analyze = nativeBinding.analyze
 module.exports.analyzeBatchSync = nativeBinding.analyzeBatchSync
 module.exports.analyzeSync = nativeBinding.analyzeSync
+module.exports.AstFormat = nativeBinding.AstFormat
+module.exports.DependencyKind = nativeBinding.DependencyKind
+module.exports.DiagnosticFormat = nativeBinding.DiagnosticFormat
 module.exports.DiagnosticSeverity = nativeBinding.DiagnosticSeverity
+module.exports.formatDiagnostics = nativeBinding.formatDiagnostics
+module.exports.HelperMode = nativeBinding.HelperMode
+module.exports.isolatedDeclaration = nativeBinding.isolatedDeclaration
+module.exports.isolatedDeclarationSync = nativeBinding.isolatedDeclarationSync
 module.exports.JsxRuntime = nativeBinding.JsxRuntime
+module.exports.Locale = nativeBinding.Locale
+module.exports.minify = nativeBinding.minify
 module.exports.minifySync = nativeBinding.minifySync
+module.exports.ModuleFormat = nativeBinding.ModuleFormat
+module.exports.ModuleKind = nativeBinding.ModuleKind
+module.exports.parse = nativeBinding.parse
 module.exports.parseSync = nativeBinding.parseSync
+module.exports.resolve = nativeBinding.resolve
 module.exports.resolveBatchSync = nativeBinding.resolveBatchSync
 module.exports.resolveSync = nativeBinding.resolveSync
+module.exports.shake = nativeBinding.shake
 module.exports.shakeSync = nativeBinding.shakeSync
 module.exports.SourceType = nativeBinding.SourceType
+module.exports.transform = nativeBinding.transform
+module.exports.transformBatch = nativeBinding.transformBatch
+module.exports.transformBatchSync = nativeBinding.transformBatchSync
 module.exports.transformSync = nativeBinding.transformSync
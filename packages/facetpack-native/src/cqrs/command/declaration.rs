@@ -0,0 +1,145 @@
+use crate::cqrs::query::DiagnosticReporter;
+use crate::cqrs::traits::Command;
+use crate::domain::error::FacetpackError;
+use crate::domain::types::{DeclarationOptions, DeclarationResult, DiagnosticSeverity};
+
+use oxc_allocator::Allocator;
+use oxc_codegen::{Codegen, CodegenOptions};
+use oxc_isolated_declarations::{IsolatedDeclarations, IsolatedDeclarationsOptions};
+use oxc_parser::Parser;
+use oxc_span::SourceType;
+
+/// Command emitting the `.d.ts` declarations of a TypeScript file without type checking,
+/// following TypeScript's `isolatedDeclarations` rules.
+pub struct DeclarationCommand {
+  pub filename: String,
+  pub source_text: String,
+  pub options: DeclarationOptions,
+}
+
+impl DeclarationCommand {
+  pub fn new(filename: String, source_text: String, options: Option<DeclarationOptions>) -> Self {
+    Self {
+      filename,
+      source_text,
+      options: options.unwrap_or_default(),
+    }
+  }
+
+  fn get_oxc_source_type(&self) -> SourceType {
+    SourceType::from_path(&self.filename)
+      .unwrap_or_default()
+      .with_typescript(true)
+  }
+}
+
+impl Command for DeclarationCommand {
+  type Result = DeclarationResult;
+
+  fn execute(&self) -> Result<Self::Result, FacetpackError> {
+    let allocator = Allocator::default();
+    let source_type = self.get_oxc_source_type();
+
+    let parser_return = Parser::new(&allocator, &self.source_text, source_type).parse();
+
+    if parser_return.panicked {
+      return Err(FacetpackError::ParseError(
+        parser_return
+          .errors
+          .iter()
+          .map(|e| e.to_string())
+          .collect::<Vec<_>>()
+          .join("\n"),
+      ));
+    }
+
+    let options = IsolatedDeclarationsOptions {
+      strip_internal: self.options.strip_internal.unwrap_or(false),
+    };
+    let declarations_return =
+      IsolatedDeclarations::new(&allocator, options).build(&parser_return.program);
+
+    let codegen_options = CodegenOptions {
+      source_map_path: if self.options.sourcemap.unwrap_or(false) {
+        Some(self.filename.clone().into())
+      } else {
+        None
+      },
+      ..Default::default()
+    };
+    let codegen_return = Codegen::new()
+      .with_options(codegen_options)
+      .build(&declarations_return.program);
+
+    let mut reporter = DiagnosticReporter::new(&self.filename, &self.source_text);
+    if let Some(locale) = self.options.locale {
+      reporter = reporter.with_locale(locale);
+    }
+    let errors = parser_return
+      .errors
+      .iter()
+      .chain(&declarations_return.errors);
+
+    Ok(DeclarationResult {
+      code: codegen_return.code,
+      map: codegen_return.map.map(|m| m.to_json_string()),
+      errors: errors.clone().map(|e| e.to_string()).collect(),
+      diagnostics: errors
+        .map(|e| reporter.report(e, DiagnosticSeverity::Error))
+        .collect(),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn declare(source: &str) -> DeclarationResult {
+    DeclarationCommand::new("button.ts".to_string(), source.to_string(), None)
+      .execute()
+      .unwrap()
+  }
+
+  #[test]
+  fn test_emit_declarations() {
+    let result = declare(
+      "export interface Props { label: string }\nexport function render(props: Props): string { return props.label; }\nexport const size = 12;\nconst internal = 1;",
+    );
+
+    assert!(result.errors.is_empty());
+    assert!(result.code.contains("export interface Props {"));
+    assert!(result
+      .code
+      .contains("export declare function render(props: Props): string;"));
+    assert!(result.code.contains("export declare const size = 12;"));
+    assert!(!result.code.contains("internal"));
+  }
+
+  #[test]
+  fn test_missing_explicit_types() {
+    let result = declare("export const x = 1;\nexport function load() { return fetch('/'); }");
+
+    assert_eq!(result.diagnostics.len(), 1);
+    let diag = &result.diagnostics[0];
+    assert_eq!(diag.code.as_deref(), Some("E0060"));
+    assert_eq!(diag.line, 2);
+  }
+
+  #[test]
+  fn test_declaration_map() {
+    let options = DeclarationOptions {
+      sourcemap: Some(true),
+      ..Default::default()
+    };
+    let result = DeclarationCommand::new(
+      "button.ts".to_string(),
+      "export const size: number = 12;".to_string(),
+      Some(options),
+    )
+    .execute()
+    .unwrap();
+
+    assert!(result.map.unwrap().contains("button.ts"));
+  }
+}
@@ -1,3 +1,4 @@
+use super::sourcemap::compose_source_maps;
 use crate::cqrs::traits::Command;
 use crate::domain::error::FacetpackError;
 use crate::domain::types::{MinifyOptions, MinifyResult};
@@ -80,7 +81,9 @@ impl Command for MinifyCommand {
 
     let codegen_options = CodegenOptions {
       minify: true,
-      source_map_path: if self.options.sourcemap.unwrap_or(false) {
+      source_map_path: if self.options.sourcemap.unwrap_or(false)
+        || self.options.input_source_map.is_some()
+      {
         Some(self.filename.clone().into())
       } else {
         None
@@ -90,7 +93,14 @@ impl Command for MinifyCommand {
 
     let codegen_return = Codegen::new().with_options(codegen_options).build(&program);
 
-    let map = codegen_return.map.map(|m| m.to_json_string());
+    let map = match (codegen_return.map, &self.options.input_source_map) {
+      (Some(map), Some(input)) => Some(
+        compose_source_maps(input, &map)
+          .map_err(FacetpackError::InvalidSourceMap)?
+          .to_json_string(),
+      ),
+      (map, _) => map.map(|m| m.to_json_string()),
+    };
 
     Ok(MinifyResult {
       code: codegen_return.code,
@@ -245,4 +255,33 @@ mod tests {
 
     assert!(result.code.len() < code.len());
   }
+
+  #[test]
+  fn test_minify_with_input_source_map() {
+    let code = "export const answer = 42;";
+    let input_map = r#"{"version":3,"sources":["answer.ts"],"names":[],"mappings":"AAAA,6BAAA"}"#;
+    let options = MinifyOptions {
+      input_source_map: Some(input_map.to_string()),
+      ..Default::default()
+    };
+    let command = MinifyCommand::new(code.to_string(), "answer.js".to_string(), Some(options));
+    let map = command.execute().unwrap().map.unwrap();
+
+    assert!(map.contains(r#""sources":["answer.ts"]"#));
+  }
+
+  #[test]
+  fn test_minify_with_invalid_input_source_map() {
+    let options = MinifyOptions {
+      input_source_map: Some("{".to_string()),
+      ..Default::default()
+    };
+    let command = MinifyCommand::new(
+      "export const a = 1;".to_string(),
+      "a.js".to_string(),
+      Some(options),
+    );
+
+    assert!(command.execute().is_err());
+  }
 }
@@ -1,7 +1,10 @@
+mod declaration;
 mod minify;
 mod shake;
+mod sourcemap;
 mod transform;
 
+pub use declaration::DeclarationCommand;
 pub use minify::MinifyCommand;
 pub use shake::ShakeCommand;
-pub use transform::TransformCommand;
+pub use transform::{TransformBatchCommand, TransformCommand};
@@ -4,11 +4,13 @@ use crate::cqrs::traits::Command;
 use crate::domain::error::FacetpackError;
 use crate::domain::types::ShakeResult;
 
-use oxc_allocator::Allocator;
+use oxc_allocator::{Allocator, Vec as ArenaVec};
 use oxc_ast::ast::*;
+use oxc_ast::AstBuilder;
 use oxc_codegen::{Codegen, CodegenOptions};
 use oxc_parser::Parser;
-use oxc_span::SourceType;
+use oxc_semantic::SemanticBuilder;
+use oxc_span::{GetSpan, SourceType, Span};
 
 pub struct ShakeCommand {
   pub filename: String,
@@ -24,6 +26,160 @@ impl ShakeCommand {
       used_exports,
     }
   }
+
+  /// Drops the export statements that are not in `used_set` and returns the names that left
+  /// the module's export surface. Exported declarations are unwrapped into plain statements so
+  /// the pruning pass can decide whether something local still needs them.
+  fn remove_unused_exports<'a>(
+    allocator: &'a Allocator,
+    program: &mut Program<'a>,
+    used_set: &HashSet<&str>,
+  ) -> Vec<String> {
+    let ast = AstBuilder::new(allocator);
+    let mut removed_exports = Vec::new();
+    let body = std::mem::replace(&mut program.body, ArenaVec::new_in(allocator));
+
+    for stmt in body {
+      match stmt {
+        Statement::ExportNamedDeclaration(mut export_decl) => {
+          if let Some(decl) = &export_decl.declaration {
+            let names = get_declaration_names(decl);
+            if names.is_empty() || names.iter().any(|n| used_set.contains(n.as_str())) {
+              program
+                .body
+                .push(Statement::ExportNamedDeclaration(export_decl));
+            } else {
+              removed_exports.extend(names);
+              let decl = export_decl.declaration.take().unwrap();
+              program.body.push(Statement::from(decl));
+            }
+          } else if export_decl.specifiers.is_empty() {
+            program
+              .body
+              .push(Statement::ExportNamedDeclaration(export_decl));
+          } else {
+            export_decl.specifiers.retain(|spec| {
+              let name = spec.exported.name().to_string();
+              let used = used_set.contains(name.as_str());
+              if !used {
+                removed_exports.push(name);
+              }
+              used
+            });
+            if !export_decl.specifiers.is_empty() {
+              program
+                .body
+                .push(Statement::ExportNamedDeclaration(export_decl));
+            }
+          }
+        }
+
+        Statement::ExportDefaultDeclaration(export_default) => {
+          if used_set.contains("default") {
+            program
+              .body
+              .push(Statement::ExportDefaultDeclaration(export_default));
+            continue;
+          }
+
+          removed_exports.push("default".to_string());
+          let export_default = export_default.unbox();
+          match export_default.declaration {
+            ExportDefaultDeclarationKind::FunctionDeclaration(func) if func.id.is_some() => {
+              program.body.push(Statement::FunctionDeclaration(func));
+            }
+            ExportDefaultDeclarationKind::ClassDeclaration(class) if class.id.is_some() => {
+              program.body.push(Statement::ClassDeclaration(class));
+            }
+            ExportDefaultDeclarationKind::FunctionDeclaration(_)
+            | ExportDefaultDeclarationKind::ClassDeclaration(_)
+            | ExportDefaultDeclarationKind::TSInterfaceDeclaration(_) => {}
+            kind => {
+              let expr = kind.into_expression();
+              if !is_pure_expression(&expr) {
+                program
+                  .body
+                  .push(ast.statement_expression(export_default.span, expr));
+              }
+            }
+          }
+        }
+
+        Statement::ExportAllDeclaration(export_all) => {
+          if let Some(exported) = &export_all.exported {
+            let name = exported.name().to_string();
+            if !used_set.contains(name.as_str()) {
+              removed_exports.push(name);
+              continue;
+            }
+          }
+          program
+            .body
+            .push(Statement::ExportAllDeclaration(export_all));
+        }
+
+        stmt => program.body.push(stmt),
+      }
+    }
+
+    removed_exports
+  }
+
+  /// Repeatedly removes top-level bindings that became unreferenced after the export removal.
+  /// Bindings that were already dead in the original source are left alone.
+  fn prune_dead_code(program: &mut Program, preserved: &HashSet<Span>) {
+    loop {
+      let dead: HashSet<Span> = find_dead_bindings(program)
+        .difference(preserved)
+        .copied()
+        .collect();
+
+      if dead.is_empty() {
+        break;
+      }
+
+      let before = count_bindings(program);
+
+      program.body.retain_mut(|stmt| match stmt {
+        Statement::VariableDeclaration(var_decl) => {
+          var_decl.declarations.retain(|declarator| {
+            let removable = match &declarator.id.kind {
+              BindingPatternKind::BindingIdentifier(id) => dead.contains(&id.span),
+              _ => false,
+            };
+            !(removable && declarator.init.as_ref().is_none_or(is_pure_expression))
+          });
+          !var_decl.declarations.is_empty()
+        }
+        Statement::FunctionDeclaration(func) => {
+          !func.id.as_ref().is_some_and(|id| dead.contains(&id.span))
+        }
+        Statement::ClassDeclaration(class) => {
+          !(class.id.as_ref().is_some_and(|id| dead.contains(&id.span)) && is_pure_class(class))
+        }
+        Statement::ImportDeclaration(import_decl) => match &mut import_decl.specifiers {
+          Some(specifiers) if !specifiers.is_empty() => {
+            specifiers.retain(|spec| !dead.contains(&spec.local().span));
+            if specifiers.is_empty() {
+              // The imported module may still have side effects (polyfills, registrations),
+              // so it stays as a bare `import './x'`. Type-only imports have none.
+              if import_decl.import_kind.is_type() {
+                return false;
+              }
+              import_decl.specifiers = None;
+            }
+            true
+          }
+          _ => true,
+        },
+        _ => true,
+      });
+
+      if count_bindings(program) == before {
+        break;
+      }
+    }
+  }
 }
 
 impl Command for ShakeCommand {
@@ -46,78 +202,19 @@ impl Command for ShakeCommand {
       ));
     }
 
-    let program = parser_return.program;
+    let mut program = parser_return.program;
     let used_set: HashSet<&str> = self.used_exports.iter().map(|s| s.as_str()).collect();
-    let use_all = used_set.contains("*");
 
-    let mut removed_exports = Vec::new();
-    let mut kept_statements = Vec::new();
-
-    for stmt in program.body.iter() {
-      let should_keep = match stmt {
-        Statement::ExportNamedDeclaration(export_decl) => {
-          if use_all {
-            true
-          } else if !export_decl.specifiers.is_empty() {
-            let mut any_used = false;
-            for spec in export_decl.specifiers.iter() {
-              let name = spec.exported.name().to_string();
-              if used_set.contains(name.as_str()) {
-                any_used = true;
-              } else {
-                removed_exports.push(name);
-              }
-            }
-            any_used
-          } else if let Some(decl) = &export_decl.declaration {
-            let name = get_declaration_name(decl);
-            if let Some(ref n) = name {
-              if used_set.contains(n.as_str()) {
-                true
-              } else {
-                removed_exports.push(n.clone());
-                false
-              }
-            } else {
-              true
-            }
-          } else {
-            true
-          }
-        }
-
-        Statement::ExportDefaultDeclaration(_) => {
-          if use_all || used_set.contains("default") {
-            true
-          } else {
-            removed_exports.push("default".to_string());
-            false
-          }
-        }
-
-        Statement::ExportAllDeclaration(export_all) => {
-          if use_all {
-            true
-          } else if let Some(exported) = &export_all.exported {
-            let name = exported.name().to_string();
-            if used_set.contains(name.as_str()) {
-              true
-            } else {
-              removed_exports.push(name);
-              false
-            }
-          } else {
-            true
-          }
-        }
-
-        _ => true,
-      };
-
-      if should_keep {
-        kept_statements.push(stmt);
+    let removed_exports = if used_set.contains("*") {
+      Vec::new()
+    } else {
+      let preserved = find_dead_bindings(&program);
+      let removed = Self::remove_unused_exports(&allocator, &mut program, &used_set);
+      if !removed.is_empty() {
+        Self::prune_dead_code(&mut program, &preserved);
       }
-    }
+      removed
+    };
 
     let codegen_options = CodegenOptions::default();
     let codegen_return = Codegen::new().with_options(codegen_options).build(&program);
@@ -130,23 +227,206 @@ impl Command for ShakeCommand {
   }
 }
 
-fn get_declaration_name(decl: &Declaration) -> Option<String> {
+fn get_declaration_names(decl: &Declaration) -> Vec<String> {
+  let mut names = Vec::new();
   match decl {
     Declaration::VariableDeclaration(var_decl) => {
-      var_decl
-        .declarations
-        .first()
-        .and_then(|d| match &d.id.kind {
-          BindingPatternKind::BindingIdentifier(id) => Some(id.name.to_string()),
-          _ => None,
-        })
+      for declarator in var_decl.declarations.iter() {
+        collect_binding_identifiers(&declarator.id, &mut |id| names.push(id.name.to_string()));
+      }
+    }
+    Declaration::FunctionDeclaration(fn_decl) => {
+      names.extend(fn_decl.id.as_ref().map(|id| id.name.to_string()));
     }
-    Declaration::FunctionDeclaration(fn_decl) => fn_decl.id.as_ref().map(|id| id.name.to_string()),
     Declaration::ClassDeclaration(class_decl) => {
-      class_decl.id.as_ref().map(|id| id.name.to_string())
+      names.extend(class_decl.id.as_ref().map(|id| id.name.to_string()));
+    }
+    _ => {}
+  }
+  names
+}
+
+fn collect_binding_identifiers<'a>(
+  pattern: &BindingPattern<'a>,
+  f: &mut impl FnMut(&BindingIdentifier<'a>),
+) {
+  match &pattern.kind {
+    BindingPatternKind::BindingIdentifier(id) => f(id),
+    BindingPatternKind::ObjectPattern(obj) => {
+      for prop in obj.properties.iter() {
+        collect_binding_identifiers(&prop.value, f);
+      }
+      if let Some(rest) = &obj.rest {
+        collect_binding_identifiers(&rest.argument, f);
+      }
+    }
+    BindingPatternKind::ArrayPattern(arr) => {
+      for element in arr.elements.iter().flatten() {
+        collect_binding_identifiers(element, f);
+      }
+      if let Some(rest) = &arr.rest {
+        collect_binding_identifiers(&rest.argument, f);
+      }
+    }
+    BindingPatternKind::AssignmentPattern(assign) => collect_binding_identifiers(&assign.left, f),
+  }
+}
+
+/// Returns the spans of the bindings declared by plain top-level statements whose every
+/// reference sits inside their own declaration (unused, or only used recursively).
+fn find_dead_bindings(program: &Program) -> HashSet<Span> {
+  let semantic = SemanticBuilder::new().build(program).semantic;
+  let scoping = semantic.scoping();
+  let nodes = semantic.nodes();
+
+  let is_dead = |id: &BindingIdentifier| {
+    let Some(symbol_id) = id.symbol_id.get() else {
+      return false;
+    };
+    let declaration_span = nodes.get_node(scoping.symbol_declaration(symbol_id)).span();
+    scoping
+      .get_resolved_reference_ids(symbol_id)
+      .iter()
+      .all(|&reference_id| {
+        let node_id = scoping.get_reference(reference_id).node_id();
+        let span = nodes.get_node(node_id).span();
+        declaration_span.start <= span.start && span.end <= declaration_span.end
+      })
+  };
+
+  let mut dead = HashSet::new();
+  for stmt in program.body.iter() {
+    match stmt {
+      Statement::VariableDeclaration(var_decl) => {
+        for declarator in var_decl.declarations.iter() {
+          if let BindingPatternKind::BindingIdentifier(id) = &declarator.id.kind {
+            if is_dead(id) {
+              dead.insert(id.span);
+            }
+          }
+        }
+      }
+      Statement::FunctionDeclaration(func) => {
+        dead.extend(func.id.as_ref().filter(|id| is_dead(id)).map(|id| id.span));
+      }
+      Statement::ClassDeclaration(class) => {
+        dead.extend(class.id.as_ref().filter(|id| is_dead(id)).map(|id| id.span));
+      }
+      Statement::ImportDeclaration(import_decl) => {
+        for spec in import_decl.specifiers.iter().flatten() {
+          if is_dead(spec.local()) {
+            dead.insert(spec.local().span);
+          }
+        }
+      }
+      _ => {}
     }
-    _ => None,
   }
+  dead
+}
+
+fn count_bindings(program: &Program) -> usize {
+  program
+    .body
+    .iter()
+    .map(|stmt| match stmt {
+      Statement::VariableDeclaration(var_decl) => var_decl.declarations.len(),
+      Statement::FunctionDeclaration(_) | Statement::ClassDeclaration(_) => 1,
+      Statement::ImportDeclaration(import_decl) => {
+        import_decl.specifiers.as_ref().map_or(0, |s| s.len())
+      }
+      _ => 0,
+    })
+    .sum()
+}
+
+/// Conservative side-effect check: only expressions that can be dropped without changing
+/// behaviour return `true`.
+fn is_pure_expression(expr: &Expression) -> bool {
+  match expr {
+    Expression::BooleanLiteral(_)
+    | Expression::NullLiteral(_)
+    | Expression::NumericLiteral(_)
+    | Expression::BigIntLiteral(_)
+    | Expression::RegExpLiteral(_)
+    | Expression::StringLiteral(_)
+    | Expression::Identifier(_)
+    | Expression::FunctionExpression(_)
+    | Expression::ArrowFunctionExpression(_) => true,
+    Expression::TemplateLiteral(tpl) => tpl.expressions.iter().all(is_pure_expression),
+    Expression::ClassExpression(class) => is_pure_class(class),
+    Expression::ArrayExpression(arr) => arr.elements.iter().all(|el| match el {
+      ArrayExpressionElement::SpreadElement(_) => false,
+      ArrayExpressionElement::Elision(_) => true,
+      el => is_pure_expression(el.to_expression()),
+    }),
+    Expression::ObjectExpression(obj) => obj.properties.iter().all(|prop| match prop {
+      ObjectPropertyKind::ObjectProperty(p) => {
+        is_pure_property_key(&p.key, p.computed) && is_pure_expression(&p.value)
+      }
+      ObjectPropertyKind::SpreadProperty(_) => false,
+    }),
+    Expression::UnaryExpression(unary) => {
+      unary.operator != UnaryOperator::Delete && is_pure_expression(&unary.argument)
+    }
+    Expression::BinaryExpression(binary) => {
+      is_pure_expression(&binary.left) && is_pure_expression(&binary.right)
+    }
+    Expression::LogicalExpression(logical) => {
+      is_pure_expression(&logical.left) && is_pure_expression(&logical.right)
+    }
+    Expression::ConditionalExpression(cond) => {
+      is_pure_expression(&cond.test)
+        && is_pure_expression(&cond.consequent)
+        && is_pure_expression(&cond.alternate)
+    }
+    Expression::ParenthesizedExpression(paren) => is_pure_expression(&paren.expression),
+    Expression::SequenceExpression(seq) => seq.expressions.iter().all(is_pure_expression),
+    Expression::CallExpression(call) => call.pure && call.arguments.iter().all(is_pure_argument),
+    Expression::NewExpression(new_expr) => {
+      new_expr.pure && new_expr.arguments.iter().all(is_pure_argument)
+    }
+    Expression::TSAsExpression(ts) => is_pure_expression(&ts.expression),
+    Expression::TSSatisfiesExpression(ts) => is_pure_expression(&ts.expression),
+    Expression::TSNonNullExpression(ts) => is_pure_expression(&ts.expression),
+    _ => false,
+  }
+}
+
+fn is_pure_argument(arg: &Argument) -> bool {
+  match arg {
+    Argument::SpreadElement(_) => false,
+    arg => is_pure_expression(arg.to_expression()),
+  }
+}
+
+fn is_pure_property_key(key: &PropertyKey, computed: bool) -> bool {
+  match key.as_expression() {
+    Some(expr) if computed => is_pure_expression(expr),
+    _ => true,
+  }
+}
+
+fn is_pure_class(class: &Class) -> bool {
+  class.decorators.is_empty()
+    && class.super_class.as_ref().is_none_or(is_pure_expression)
+    && class.body.body.iter().all(|element| match element {
+      ClassElement::StaticBlock(_) => false,
+      ClassElement::MethodDefinition(method) => {
+        method.decorators.is_empty() && is_pure_property_key(&method.key, method.computed)
+      }
+      ClassElement::PropertyDefinition(prop) => {
+        prop.decorators.is_empty()
+          && is_pure_property_key(&prop.key, prop.computed)
+          && (!prop.r#static || prop.value.as_ref().is_none_or(is_pure_expression))
+      }
+      ClassElement::AccessorProperty(prop) => {
+        prop.decorators.is_empty()
+          && is_pure_property_key(&prop.key, prop.computed)
+          && (!prop.r#static || prop.value.as_ref().is_none_or(is_pure_expression))
+      }
+      ClassElement::TSIndexSignature(_) => true,
+    })
 }
 
 #[cfg(test)]
@@ -220,4 +500,127 @@ mod tests {
 
     assert!(result.code.contains("import"));
   }
+
+  #[test]
+  fn test_shake_removes_unused_code_from_output() {
+    let code = r#"
+      export const used = 1;
+      export const unused = 2;
+      export function alsoUnused() {}
+    "#;
+    let command = ShakeCommand::new(
+      "test.js".to_string(),
+      code.to_string(),
+      vec!["used".to_string()],
+    );
+    let result = command.execute().unwrap();
+
+    assert!(result.code.contains("used = 1"));
+    assert!(!result.code.contains("unused"));
+    assert!(!result.code.contains("alsoUnused"));
+  }
+
+  #[test]
+  fn test_shake_prunes_transitive_dead_code() {
+    let code = r#"
+      import { format } from './format';
+      import { log } from './log';
+      const prefix = "> ";
+      function render(value) { return prefix + format(value); }
+      export const used = () => log("used");
+      export const unused = () => render(1);
+    "#;
+    let command = ShakeCommand::new(
+      "test.js".to_string(),
+      code.to_string(),
+      vec!["used".to_string()],
+    );
+    let result = command.execute().unwrap();
+
+    assert_eq!(result.removed_exports, vec!["unused".to_string()]);
+    assert!(result.code.contains("./log"));
+    assert!(result.code.contains("import \"./format\";"));
+    assert!(!result.code.contains("format("));
+    assert!(!result.code.contains("render"));
+    assert!(!result.code.contains("prefix"));
+  }
+
+  #[test]
+  fn test_shake_drops_dead_type_only_imports() {
+    let code = r#"
+      import type { Props } from './types';
+      export const used = 1;
+      export const unused = (props: Props) => props;
+    "#;
+    let command = ShakeCommand::new(
+      "test.ts".to_string(),
+      code.to_string(),
+      vec!["used".to_string()],
+    );
+    let result = command.execute().unwrap();
+
+    assert!(!result.code.contains("./types"));
+  }
+
+  #[test]
+  fn test_shake_keeps_locally_referenced_exports() {
+    let code = r#"
+      export function helper() { return 1; }
+      export function used() { return helper(); }
+    "#;
+    let command = ShakeCommand::new(
+      "test.js".to_string(),
+      code.to_string(),
+      vec!["used".to_string()],
+    );
+    let result = command.execute().unwrap();
+
+    assert!(result.removed_exports.contains(&"helper".to_string()));
+    assert!(result.code.contains("function helper"));
+    assert!(!result.code.contains("export function helper"));
+  }
+
+  #[test]
+  fn test_shake_keeps_side_effects_and_preexisting_dead_code() {
+    let code = r#"
+      const untouched = 1;
+      const store = createStore();
+      export const used = 1;
+      export const unused = store;
+      export default register();
+    "#;
+    let command = ShakeCommand::new(
+      "test.js".to_string(),
+      code.to_string(),
+      vec!["used".to_string()],
+    );
+    let result = command.execute().unwrap();
+
+    assert!(result.code.contains("untouched"));
+    assert!(result.code.contains("createStore()"));
+    assert!(result.code.contains("register()"));
+    assert!(!result.code.contains("export default"));
+  }
+
+  #[test]
+  fn test_shake_partial_export_specifiers() {
+    let code = r#"
+      const a = 1;
+      const b = 2;
+      export { a, b };
+      export { c } from './c';
+    "#;
+    let command = ShakeCommand::new(
+      "test.js".to_string(),
+      code.to_string(),
+      vec!["a".to_string()],
+    );
+    let result = command.execute().unwrap();
+
+    assert!(result.removed_exports.contains(&"b".to_string()));
+    assert!(result.removed_exports.contains(&"c".to_string()));
+    assert!(result.code.contains("export { a }"));
+    assert!(!result.code.contains("b = 2"));
+    assert!(!result.code.contains("./c"));
+  }
 }
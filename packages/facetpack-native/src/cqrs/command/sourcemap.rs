@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use oxc_sourcemap::{SourceMap, SourceMapBuilder};
+
+/// Remaps `output`, a map from generated code to an intermediate file, through `input`, the
+/// map from that intermediate file to the original sources.
+///
+/// Generated positions that do not resolve to an original position are dropped, like
+/// `@ampproject/remapping` does.
+pub fn compose_source_maps(input: &str, output: &SourceMap) -> Result<SourceMap, String> {
+  let input = SourceMap::from_json_string(input).map_err(|e| format!("{:?}", e))?;
+  let lookup_table = input.generate_lookup_table();
+
+  let mut builder = SourceMapBuilder::default();
+  if let Some(file) = output.get_file() {
+    builder.set_file(file);
+  }
+
+  let mut source_ids: Vec<Option<u32>> = vec![None; input.get_sources().count()];
+  let mut source_contents: Vec<Option<Arc<str>>> = Vec::new();
+
+  for token in output.get_tokens() {
+    let Some(original) =
+      input.lookup_token(&lookup_table, token.get_src_line(), token.get_src_col())
+    else {
+      continue;
+    };
+    let Some(input_source_id) = original.get_source_id() else {
+      continue;
+    };
+    let Some(source) = input.get_source(input_source_id) else {
+      continue;
+    };
+
+    let source_id = *source_ids[input_source_id as usize].get_or_insert_with(|| {
+      let content = input.get_source_content(input_source_id).cloned();
+      let id = builder.set_source_and_content(source, content.as_deref().unwrap_or_default());
+      source_contents.push(content);
+      id
+    });
+
+    let name = original
+      .get_name_id()
+      .and_then(|id| input.get_name(id))
+      .or_else(|| token.get_name_id().and_then(|id| output.get_name(id)));
+    let name_id = name.map(|name| builder.add_name(name));
+
+    builder.add_token(
+      token.get_dst_line(),
+      token.get_dst_col(),
+      original.get_src_line(),
+      original.get_src_col(),
+      Some(source_id),
+      name_id,
+    );
+  }
+
+  let mut map = builder.into_sourcemap();
+  map.set_source_contents(source_contents.iter().map(|c| c.as_deref()).collect());
+  Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_compose_source_maps() {
+    // original.ts line 2 -> intermediate.js line 0, intermediate.js line 0 -> output line 0 col 4
+    let input = r#"{"version":3,"sources":["original.ts"],"sourcesContent":["// header\nconst x = 1;"],"names":[],"mappings":"AACA"}"#;
+
+    let mut builder = SourceMapBuilder::default();
+    builder.set_source_and_content("intermediate.js", "const x = 1;");
+    builder.add_token(0, 4, 0, 0, Some(0), None);
+    let output = builder.into_sourcemap();
+
+    let composed = compose_source_maps(input, &output).unwrap();
+    let tokens: Vec<_> = composed.get_tokens().collect();
+
+    assert_eq!(
+      composed.get_sources().next().map(|s| s.as_ref()),
+      Some("original.ts")
+    );
+    assert_eq!(
+      composed.get_source_content(0).map(|s| s.as_ref()),
+      Some("// header\nconst x = 1;")
+    );
+    assert_eq!(tokens.len(), 1);
+    assert_eq!((tokens[0].get_dst_line(), tokens[0].get_dst_col()), (0, 4));
+    assert_eq!((tokens[0].get_src_line(), tokens[0].get_src_col()), (1, 0));
+  }
+
+  #[test]
+  fn test_compose_drops_unmapped_tokens() {
+    let input = r#"{"version":3,"sources":["a.js"],"names":[],"mappings":";AAAA"}"#;
+
+    let mut builder = SourceMapBuilder::default();
+    builder.set_source_and_content("b.js", "");
+    builder.add_token(0, 0, 0, 0, Some(0), None);
+    builder.add_token(0, 5, 1, 0, Some(0), None);
+    let output = builder.into_sourcemap();
+
+    let composed = compose_source_maps(input, &output).unwrap();
+
+    assert_eq!(composed.get_tokens().count(), 1);
+  }
+
+  #[test]
+  fn test_compose_invalid_input() {
+    let output = SourceMapBuilder::default().into_sourcemap();
+    assert!(compose_source_maps("not a map", &output).is_err());
+  }
+}
@@ -0,0 +1,95 @@
+use rayon::prelude::*;
+
+use super::TransformCommand;
+use crate::cqrs::traits::Command;
+use crate::domain::error::FacetpackError;
+use crate::domain::types::{TransformBatchResult, TransformInput};
+
+/// Command transforming many files at once across the Rayon thread pool.
+///
+/// Results are returned in input order, and a file that fails to transform gets an
+/// `error` entry instead of failing the whole batch.
+pub struct TransformBatchCommand {
+  pub files: Vec<TransformInput>,
+}
+
+impl TransformBatchCommand {
+  pub fn new(files: Vec<TransformInput>) -> Self {
+    Self { files }
+  }
+}
+
+impl Command for TransformBatchCommand {
+  type Result = Vec<TransformBatchResult>;
+
+  fn execute(&self) -> Result<Self::Result, FacetpackError> {
+    Ok(
+      self
+        .files
+        .par_iter()
+        .map(|file| {
+          let command = TransformCommand::new(
+            file.filename.clone(),
+            file.source.clone(),
+            file.options.clone(),
+          );
+          match command.execute() {
+            Ok(result) => TransformBatchResult {
+              filename: file.filename.clone(),
+              result: Some(result),
+              error: None,
+            },
+            Err(e) => TransformBatchResult {
+              filename: file.filename.clone(),
+              result: None,
+              error: Some(e.to_string()),
+            },
+          }
+        })
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn input(filename: &str, source: &str) -> TransformInput {
+    TransformInput {
+      filename: filename.to_string(),
+      source: source.to_string(),
+      options: None,
+    }
+  }
+
+  #[test]
+  fn test_transform_batch_keeps_order_and_isolates_errors() {
+    let mut files: Vec<TransformInput> = (0..32)
+      .map(|i| {
+        input(
+          &format!("file{}.ts", i),
+          &format!("export const n: number = {};", i),
+        )
+      })
+      .collect();
+    files.insert(5, input("broken.ts", "const x = ;"));
+
+    let results = TransformBatchCommand::new(files).execute().unwrap();
+
+    assert_eq!(results.len(), 33);
+    assert_eq!(results[5].filename, "broken.ts");
+    assert!(results[5].result.is_none());
+    assert!(results[5].error.is_some());
+    for (i, entry) in results
+      .iter()
+      .filter(|r| r.filename != "broken.ts")
+      .enumerate()
+    {
+      assert_eq!(entry.filename, format!("file{}.ts", i));
+      assert!(entry.error.is_none());
+      let code = &entry.result.as_ref().unwrap().code;
+      assert!(code.contains(&format!("export const n = {};", i)));
+    }
+  }
+}
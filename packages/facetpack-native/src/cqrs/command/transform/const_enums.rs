@@ -0,0 +1,324 @@
+use std::collections::{HashMap, HashSet};
+
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{
+  BinaryOperator, Expression, Program, Statement, TSEnumDeclaration, UnaryOperator,
+};
+use oxc_ast_visit::{walk_mut, Visit, VisitMut};
+use oxc_semantic::{Scoping, SymbolId};
+
+use super::template::parse_expression_template;
+
+#[derive(Debug, Clone, PartialEq)]
+enum EnumValue {
+  Number(f64),
+  String(String),
+}
+
+/// Replaces accesses to the members of `const enum`s declared in the file with their
+/// values, like Babel's `optimizeConstEnums`. Enums whose every use was inlined are
+/// removed unless they are exported.
+///
+/// Returns whether the program changed, in which case `scoping` is stale.
+pub fn inline_const_enums<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+  scoping: &Scoping,
+) -> bool {
+  let mut collector = ConstEnumCollector {
+    scoping,
+    enums: HashMap::new(),
+    invalid: HashSet::new(),
+  };
+  collector.visit_program(program);
+  for symbol_id in &collector.invalid {
+    collector.enums.remove(symbol_id);
+  }
+  if collector.enums.is_empty() {
+    return false;
+  }
+
+  let mut inliner = ConstEnumInliner {
+    allocator,
+    scoping,
+    enums: &collector.enums,
+    inlined: HashMap::new(),
+  };
+  inliner.visit_program(program);
+
+  let removable: HashSet<SymbolId> = collector
+    .enums
+    .keys()
+    .filter(|&&symbol_id| {
+      let uses = scoping.get_resolved_reference_ids(symbol_id).len();
+      inliner.inlined.get(&symbol_id).copied().unwrap_or(0) == uses
+    })
+    .copied()
+    .collect();
+  EnumRemover {
+    removable: &removable,
+  }
+  .visit_program(program);
+
+  true
+}
+
+/// Computes the member values of every `const enum`. An enum with a member whose value is
+/// not a constant is marked invalid and left to the TypeScript transform.
+struct ConstEnumCollector<'s> {
+  scoping: &'s Scoping,
+  enums: HashMap<SymbolId, HashMap<String, EnumValue>>,
+  invalid: HashSet<SymbolId>,
+}
+
+impl ConstEnumCollector<'_> {
+  /// `members` are the values collected so far for `enum_id`, the enum being evaluated.
+  fn evaluate(
+    &self,
+    expr: &Expression,
+    enum_id: SymbolId,
+    members: &HashMap<String, EnumValue>,
+  ) -> Option<EnumValue> {
+    match expr {
+      Expression::NumericLiteral(lit) => Some(EnumValue::Number(lit.value)),
+      Expression::StringLiteral(lit) => Some(EnumValue::String(lit.value.to_string())),
+      Expression::TemplateLiteral(lit) => lit
+        .single_quasi()
+        .map(|quasi| EnumValue::String(quasi.to_string())),
+      Expression::ParenthesizedExpression(paren) => {
+        self.evaluate(&paren.expression, enum_id, members)
+      }
+      Expression::Identifier(ident) => members.get(ident.name.as_str()).cloned(),
+      Expression::StaticMemberExpression(member) => {
+        let Expression::Identifier(object) = &member.object else {
+          return None;
+        };
+        let symbol_id = self
+          .scoping
+          .get_reference(object.reference_id.get()?)
+          .symbol_id()?;
+        let values = if symbol_id == enum_id {
+          members
+        } else {
+          self.enums.get(&symbol_id)?
+        };
+        values.get(member.property.name.as_str()).cloned()
+      }
+      Expression::UnaryExpression(unary) => {
+        let EnumValue::Number(n) = self.evaluate(&unary.argument, enum_id, members)? else {
+          return None;
+        };
+        match unary.operator {
+          UnaryOperator::UnaryNegation => Some(EnumValue::Number(-n)),
+          UnaryOperator::UnaryPlus => Some(EnumValue::Number(n)),
+          UnaryOperator::BitwiseNot => Some(EnumValue::Number(f64::from(!to_int32(n)))),
+          _ => None,
+        }
+      }
+      Expression::BinaryExpression(binary) => {
+        let left = self.evaluate(&binary.left, enum_id, members)?;
+        let right = self.evaluate(&binary.right, enum_id, members)?;
+        evaluate_binary(binary.operator, left, right)
+      }
+      _ => None,
+    }
+  }
+}
+
+impl<'a> Visit<'a> for ConstEnumCollector<'_> {
+  fn visit_ts_enum_declaration(&mut self, decl: &TSEnumDeclaration<'a>) {
+    let Some(symbol_id) = decl.id.symbol_id.get() else {
+      return;
+    };
+    if !decl.r#const {
+      return;
+    }
+
+    // Declarations of the same enum merge, so members of the previous ones are in scope.
+    let mut members = self.enums.remove(&symbol_id).unwrap_or_default();
+    let mut previous = None;
+    for member in &decl.body.members {
+      let value = match (&member.initializer, previous) {
+        (Some(init), _) => self.evaluate(init, symbol_id, &members),
+        (None, None) => Some(EnumValue::Number(0.0)),
+        (None, Some(EnumValue::Number(n))) => Some(EnumValue::Number(n + 1.0)),
+        (None, Some(EnumValue::String(_))) => None,
+      };
+      let Some(value) = value else {
+        self.invalid.insert(symbol_id);
+        return;
+      };
+      members.insert(member.id.static_name().to_string(), value.clone());
+      previous = Some(value);
+    }
+    self.enums.insert(symbol_id, members);
+  }
+}
+
+struct ConstEnumInliner<'a, 's> {
+  allocator: &'a Allocator,
+  scoping: &'s Scoping,
+  enums: &'s HashMap<SymbolId, HashMap<String, EnumValue>>,
+  /// The number of references inlined for each enum.
+  inlined: HashMap<SymbolId, usize>,
+}
+
+impl<'a> ConstEnumInliner<'a, '_> {
+  fn member_value(&self, expr: &Expression<'a>) -> Option<(SymbolId, &EnumValue)> {
+    let (object, property) = match expr {
+      Expression::StaticMemberExpression(member) => (&member.object, member.property.name),
+      Expression::ComputedMemberExpression(member) => match &member.expression {
+        Expression::StringLiteral(lit) => (&member.object, lit.value),
+        _ => return None,
+      },
+      _ => return None,
+    };
+    let Expression::Identifier(object) = object else {
+      return None;
+    };
+    let symbol_id = self
+      .scoping
+      .get_reference(object.reference_id.get()?)
+      .symbol_id()?;
+    let value = self.enums.get(&symbol_id)?.get(property.as_str())?;
+    Some((symbol_id, value))
+  }
+}
+
+impl<'a> VisitMut<'a> for ConstEnumInliner<'a, '_> {
+  fn visit_expression(&mut self, expr: &mut Expression<'a>) {
+    let Some((symbol_id, value)) = self.member_value(expr) else {
+      walk_mut::walk_expression(self, expr);
+      return;
+    };
+
+    let source = match value {
+      EnumValue::Number(n) if n.is_nan() => "NaN".to_string(),
+      EnumValue::Number(n) if n.is_infinite() => {
+        if *n > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+      }
+      EnumValue::Number(n) => n.to_string(),
+      EnumValue::String(s) => format!("{:?}", s),
+    };
+    *expr = parse_expression_template(self.allocator, &source, None);
+    *self.inlined.entry(symbol_id).or_default() += 1;
+  }
+}
+
+/// Removes the declarations of the enums that are no longer referenced.
+struct EnumRemover<'s> {
+  removable: &'s HashSet<SymbolId>,
+}
+
+impl<'a> VisitMut<'a> for EnumRemover<'_> {
+  fn visit_statements(&mut self, stmts: &mut oxc_allocator::Vec<'a, Statement<'a>>) {
+    stmts.retain(|stmt| {
+      !matches!(stmt, Statement::TSEnumDeclaration(decl)
+        if decl.id.symbol_id.get().is_some_and(|id| self.removable.contains(&id)))
+    });
+    walk_mut::walk_statements(self, stmts);
+  }
+}
+
+fn evaluate_binary(
+  operator: BinaryOperator,
+  left: EnumValue,
+  right: EnumValue,
+) -> Option<EnumValue> {
+  let (l, r) = match (left, right) {
+    (EnumValue::Number(l), EnumValue::Number(r)) => (l, r),
+    (left, right) if operator == BinaryOperator::Addition => {
+      return Some(EnumValue::String(to_js_string(left) + &to_js_string(right)));
+    }
+    _ => return None,
+  };
+  let value = match operator {
+    BinaryOperator::Addition => l + r,
+    BinaryOperator::Subtraction => l - r,
+    BinaryOperator::Multiplication => l * r,
+    BinaryOperator::Division => l / r,
+    BinaryOperator::Remainder => l % r,
+    BinaryOperator::Exponential => l.powf(r),
+    BinaryOperator::BitwiseOR => f64::from(to_int32(l) | to_int32(r)),
+    BinaryOperator::BitwiseAnd => f64::from(to_int32(l) & to_int32(r)),
+    BinaryOperator::BitwiseXOR => f64::from(to_int32(l) ^ to_int32(r)),
+    BinaryOperator::ShiftLeft => f64::from(to_int32(l).wrapping_shl(to_uint32(r))),
+    BinaryOperator::ShiftRight => f64::from(to_int32(l).wrapping_shr(to_uint32(r))),
+    BinaryOperator::ShiftRightZeroFill => f64::from(to_uint32(l).wrapping_shr(to_uint32(r))),
+    _ => return None,
+  };
+  Some(EnumValue::Number(value))
+}
+
+fn to_js_string(value: EnumValue) -> String {
+  match value {
+    EnumValue::Number(n) => n.to_string(),
+    EnumValue::String(s) => s,
+  }
+}
+
+fn to_uint32(n: f64) -> u32 {
+  if n.is_finite() {
+    n.trunc().rem_euclid(4294967296.0) as u32
+  } else {
+    0
+  }
+}
+
+fn to_int32(n: f64) -> i32 {
+  to_uint32(n) as i32
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_codegen::Codegen;
+  use oxc_parser::Parser;
+  use oxc_semantic::SemanticBuilder;
+  use oxc_span::SourceType;
+
+  fn inline(source: &str) -> String {
+    let allocator = Allocator::default();
+    let mut program = Parser::new(&allocator, source, SourceType::ts())
+      .parse()
+      .program;
+    let scoping = SemanticBuilder::new()
+      .build(&program)
+      .semantic
+      .into_scoping();
+    inline_const_enums(&allocator, &mut program, &scoping);
+    Codegen::new().build(&program).code
+  }
+
+  #[test]
+  fn test_inline_const_enums() {
+    let code = inline(
+      "const enum Direction { Up = 1, Down, Left = Down << 2, Right = -Left }\nconst enum Label { Prefix = 'dir', Name = Prefix + '-up' }\nmove(Direction.Up, Direction['Right'], Label.Name);",
+    );
+
+    assert_eq!(code, "move(1, -8, \"dir-up\");\n");
+  }
+
+  #[test]
+  fn test_keep_exported_and_non_constant_enums() {
+    let code = inline(
+      "export const enum Size { Small = 1 }\nconst enum Dynamic { A = compute() }\nconst enum Used { A }\nuse(Size.Small, Dynamic.A, Used);",
+    );
+
+    assert!(code.contains("export const enum Size {"));
+    assert!(code.contains("const enum Dynamic {"));
+    assert!(code.contains("const enum Used {"));
+    assert!(code.contains("use(1, Dynamic.A, Used);"));
+  }
+
+  #[test]
+  fn test_members_of_other_objects_are_not_inlined() {
+    let code = inline(
+      "import { Other } from './other';\nenum Regular { Y = 2 }\nconst enum A { Y = 1, X = Other.Y }\nconst enum B { Y = 1, X = Regular.Y }\nuse(A.X, B.X);",
+    );
+
+    assert!(code.contains("const enum A {"));
+    assert!(code.contains("const enum B {"));
+    assert!(code.contains("use(A.X, B.X);"));
+  }
+}
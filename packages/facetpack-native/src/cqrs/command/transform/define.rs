@@ -0,0 +1,195 @@
+use std::collections::{HashMap, HashSet};
+
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{
+  ImportDeclarationSpecifier, Program, Statement, StringLiteral, TemplateLiteral,
+};
+use oxc_ast_visit::Visit;
+use oxc_minifier::{CompressOptions, CompressOptionsUnused, Compressor};
+use oxc_semantic::{Scoping, SemanticBuilder};
+use oxc_span::Span;
+use oxc_transformer_plugins::{ReplaceGlobalDefines, ReplaceGlobalDefinesConfig};
+
+use crate::domain::types::Dependency;
+
+/// Replaces the defined global identifiers and member expressions (like `__DEV__` or
+/// `process.env.NODE_ENV`) with their values.
+///
+/// Defines follow esbuild: a key only matches when its root identifier is a global.
+pub fn replace_defines<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+  scoping: Scoping,
+  defines: &HashMap<String, String>,
+) -> Result<Scoping, String> {
+  let mut defines: Vec<(&str, &str)> = defines
+    .iter()
+    .map(|(key, value)| (key.as_str(), value.as_str()))
+    .collect();
+  defines.sort_unstable();
+  let config = ReplaceGlobalDefinesConfig::new(&defines).map_err(|errors| {
+    errors
+      .iter()
+      .map(|e| e.to_string())
+      .collect::<Vec<_>>()
+      .join("\n")
+  })?;
+
+  Ok(
+    ReplaceGlobalDefines::new(allocator, config)
+      .build(scoping, program)
+      .scoping,
+  )
+}
+
+/// Folds constant expressions and removes the branches they make unreachable, then the
+/// imports that were only used there. `used_imports` are the import bindings that were
+/// referenced before constants were inlined.
+pub fn eliminate_dead_code<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+  scoping: Scoping,
+  used_imports: &HashSet<Span>,
+) -> Scoping {
+  let options = CompressOptions {
+    unused: CompressOptionsUnused::Keep,
+    ..CompressOptions::dce()
+  };
+  Compressor::new(allocator).dead_code_elimination_with_scoping(program, scoping, options);
+
+  let scoping = SemanticBuilder::new()
+    .build(program)
+    .semantic
+    .into_scoping();
+  let unused_imports: HashSet<Span> = used_imports
+    .difference(&used_import_bindings(program, &scoping))
+    .copied()
+    .collect();
+  remove_import_bindings(program, &unused_imports);
+
+  SemanticBuilder::new()
+    .build(program)
+    .semantic
+    .into_scoping()
+}
+
+/// Drops the dependencies whose specifier was removed with a dead branch. Type-only
+/// dependencies are kept as they never were in the output.
+pub fn retain_live_dependencies(program: &Program, dependencies: &mut Vec<Dependency>) {
+  let mut literals = LiteralCollector::default();
+  literals.visit_program(program);
+  dependencies.retain(|d| d.is_type_only || literals.starts.contains(&d.span.start));
+}
+
+/// The spans of the import bindings that are referenced.
+pub fn used_import_bindings(program: &Program, scoping: &Scoping) -> HashSet<Span> {
+  program
+    .body
+    .iter()
+    .filter_map(|stmt| match stmt {
+      Statement::ImportDeclaration(decl) => decl.specifiers.as_ref(),
+      _ => None,
+    })
+    .flatten()
+    .map(ImportDeclarationSpecifier::local)
+    .filter(|local| {
+      local
+        .symbol_id
+        .get()
+        .is_some_and(|id| !scoping.symbol_is_unused(id))
+    })
+    .map(|local| local.span)
+    .collect()
+}
+
+/// Removes the given import bindings, and the imports left without any binding.
+fn remove_import_bindings(program: &mut Program, bindings: &HashSet<Span>) {
+  if bindings.is_empty() {
+    return;
+  }
+
+  program.body.retain_mut(|stmt| {
+    let Statement::ImportDeclaration(decl) = stmt else {
+      return true;
+    };
+    let Some(specifiers) = &mut decl.specifiers else {
+      return true;
+    };
+    let count = specifiers.len();
+    specifiers.retain(|s| !bindings.contains(&s.local().span));
+    count == 0 || !specifiers.is_empty()
+  });
+}
+
+#[derive(Default)]
+struct LiteralCollector {
+  starts: HashSet<u32>,
+}
+
+impl<'a> Visit<'a> for LiteralCollector {
+  fn visit_string_literal(&mut self, lit: &StringLiteral<'a>) {
+    self.starts.insert(lit.span.start);
+  }
+
+  fn visit_template_literal(&mut self, lit: &TemplateLiteral<'a>) {
+    self.starts.insert(lit.span.start);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_codegen::Codegen;
+  use oxc_parser::Parser;
+  use oxc_span::SourceType;
+
+  fn define(source: &str, defines: &[(&str, &str)]) -> Result<String, String> {
+    let allocator = Allocator::default();
+    let mut program = Parser::new(&allocator, source, SourceType::mjs())
+      .parse()
+      .program;
+    let scoping = SemanticBuilder::new()
+      .build(&program)
+      .semantic
+      .into_scoping();
+    let defines = defines
+      .iter()
+      .map(|(key, value)| (key.to_string(), value.to_string()))
+      .collect();
+    let used_imports = used_import_bindings(&program, &scoping);
+    let scoping = replace_defines(&allocator, &mut program, scoping, &defines)?;
+    eliminate_dead_code(&allocator, &mut program, scoping, &used_imports);
+    Ok(Codegen::new().build(&program).code)
+  }
+
+  #[test]
+  fn test_define_removes_dead_branches_and_imports() {
+    let code = define(
+      "import { connect } from './devtools';\nimport { log, warn } from './log';\nimport './polyfill';\nimport unused from './unused';\nif (__DEV__) { connect(); warn(); } else { log('prod'); }\nexport const os = process.env.EXPO_OS === 'android' ? 'A' : 'I';",
+      &[("__DEV__", "false"), ("process.env.EXPO_OS", "'ios'")],
+    )
+    .unwrap();
+
+    assert_eq!(
+      code,
+      "import { log } from \"./log\";\nimport \"./polyfill\";\nimport unused from \"./unused\";\nlog(\"prod\");\nexport const os = \"I\";\n"
+    );
+  }
+
+  #[test]
+  fn test_define_ignores_local_bindings() {
+    let code = define(
+      "const __DEV__ = true;\nif (__DEV__) dev();\nfunction f(process) { return process.env.NODE_ENV; }",
+      &[("__DEV__", "false"), ("process.env.NODE_ENV", "'production'")],
+    )
+    .unwrap();
+
+    assert!(code.contains("dev();"));
+    assert!(code.contains("return process.env.NODE_ENV;"));
+  }
+
+  #[test]
+  fn test_define_invalid_value() {
+    assert!(define("__DEV__;", &[("__DEV__", "if (")]).is_err());
+  }
+}
@@ -0,0 +1,271 @@
+use oxc_ast::ast::{
+  Argument, ArrowFunctionExpression, CallExpression, ExportAllDeclaration, ExportNamedDeclaration,
+  Expression, Function, IdentifierReference, ImportDeclaration, ImportDeclarationSpecifier,
+  ImportExpression, Program, TSImportEqualsDeclaration, TSModuleReference, TryStatement,
+};
+use oxc_ast_visit::{walk, Visit};
+use oxc_semantic::{ScopeFlags, Scoping};
+use oxc_span::Span;
+
+use crate::domain::types::{Dependency, DependencyKind, SourceSpan};
+
+/// Collects the modules a program depends on, with how each of them is loaded.
+///
+/// Only static specifiers are collected: `import(name)` or `require(name)` with a computed
+/// name cannot be resolved ahead of time.
+pub fn collect_dependencies(program: &Program, scoping: &Scoping) -> Vec<Dependency> {
+  let mut collector = DependencyCollector {
+    scoping,
+    in_try_block: false,
+    dependencies: Vec::new(),
+  };
+  collector.visit_program(program);
+  collector.dependencies
+}
+
+struct DependencyCollector<'s> {
+  scoping: &'s Scoping,
+  /// Whether the current statement is directly inside a `try` block, which makes its
+  /// `require()` calls optional like in Metro.
+  in_try_block: bool,
+  dependencies: Vec<Dependency>,
+}
+
+impl DependencyCollector<'_> {
+  fn add(&mut self, specifier: &str, span: Span, kind: DependencyKind, is_type_only: bool) {
+    self.dependencies.push(Dependency {
+      specifier: specifier.to_string(),
+      kind,
+      is_type_only,
+      is_async: kind == DependencyKind::DynamicImport,
+      is_optional: self.in_try_block
+        && matches!(
+          kind,
+          DependencyKind::Require | DependencyKind::DynamicImport
+        ),
+      span: SourceSpan {
+        start: span.start,
+        end: span.end,
+      },
+    });
+  }
+
+  /// `require` is only the module loader when no local binding shadows it.
+  fn is_global(&self, ident: &IdentifierReference) -> bool {
+    ident
+      .reference_id
+      .get()
+      .is_none_or(|id| self.scoping.get_reference(id).symbol_id().is_none())
+  }
+}
+
+impl<'a> Visit<'a> for DependencyCollector<'_> {
+  fn visit_import_declaration(&mut self, decl: &ImportDeclaration<'a>) {
+    let all_specifiers_type_only = decl.specifiers.as_ref().is_some_and(|specifiers| {
+      !specifiers.is_empty()
+        && specifiers.iter().all(|s| {
+          matches!(s, ImportDeclarationSpecifier::ImportSpecifier(s) if s.import_kind.is_type())
+        })
+    });
+    self.add(
+      &decl.source.value,
+      decl.source.span,
+      DependencyKind::Import,
+      decl.import_kind.is_type() || all_specifiers_type_only,
+    );
+  }
+
+  fn visit_export_named_declaration(&mut self, decl: &ExportNamedDeclaration<'a>) {
+    if let Some(source) = &decl.source {
+      let all_specifiers_type_only =
+        !decl.specifiers.is_empty() && decl.specifiers.iter().all(|s| s.export_kind.is_type());
+      self.add(
+        &source.value,
+        source.span,
+        DependencyKind::ReExport,
+        decl.export_kind.is_type() || all_specifiers_type_only,
+      );
+    }
+    walk::walk_export_named_declaration(self, decl);
+  }
+
+  fn visit_export_all_declaration(&mut self, decl: &ExportAllDeclaration<'a>) {
+    self.add(
+      &decl.source.value,
+      decl.source.span,
+      DependencyKind::ReExport,
+      decl.export_kind.is_type(),
+    );
+  }
+
+  fn visit_ts_import_equals_declaration(&mut self, decl: &TSImportEqualsDeclaration<'a>) {
+    if let TSModuleReference::ExternalModuleReference(reference) = &decl.module_reference {
+      self.add(
+        &reference.expression.value,
+        reference.expression.span,
+        DependencyKind::Require,
+        decl.import_kind.is_type(),
+      );
+    }
+  }
+
+  fn visit_import_expression(&mut self, expr: &ImportExpression<'a>) {
+    if let Some((specifier, span)) = static_specifier(&expr.source) {
+      self.add(&specifier, span, DependencyKind::DynamicImport, false);
+    }
+    walk::walk_import_expression(self, expr);
+  }
+
+  fn visit_call_expression(&mut self, call: &CallExpression<'a>) {
+    let kind = match &call.callee {
+      Expression::Identifier(ident) if ident.name == "require" && self.is_global(ident) => {
+        Some(DependencyKind::Require)
+      }
+      Expression::StaticMemberExpression(member) if member.property.name == "resolve" => {
+        match &member.object {
+          Expression::Identifier(ident) if ident.name == "require" && self.is_global(ident) => {
+            Some(DependencyKind::RequireResolve)
+          }
+          _ => None,
+        }
+      }
+      _ => None,
+    };
+
+    if let Some(kind) = kind {
+      let specifier = call
+        .arguments
+        .first()
+        .and_then(Argument::as_expression)
+        .and_then(static_specifier);
+      if let Some((specifier, span)) = specifier {
+        self.add(&specifier, span, kind, false);
+      }
+    }
+    walk::walk_call_expression(self, call);
+  }
+
+  fn visit_try_statement(&mut self, stmt: &TryStatement<'a>) {
+    let in_try_block = std::mem::replace(&mut self.in_try_block, true);
+    self.visit_block_statement(&stmt.block);
+    self.in_try_block = in_try_block;
+
+    if let Some(handler) = &stmt.handler {
+      self.visit_catch_clause(handler);
+    }
+    if let Some(finalizer) = &stmt.finalizer {
+      self.visit_block_statement(finalizer);
+    }
+  }
+
+  fn visit_function(&mut self, func: &Function<'a>, flags: ScopeFlags) {
+    let in_try_block = std::mem::replace(&mut self.in_try_block, false);
+    walk::walk_function(self, func, flags);
+    self.in_try_block = in_try_block;
+  }
+
+  fn visit_arrow_function_expression(&mut self, arrow: &ArrowFunctionExpression<'a>) {
+    let in_try_block = std::mem::replace(&mut self.in_try_block, false);
+    walk::walk_arrow_function_expression(self, arrow);
+    self.in_try_block = in_try_block;
+  }
+}
+
+fn static_specifier(expr: &Expression) -> Option<(String, Span)> {
+  match expr {
+    Expression::StringLiteral(lit) => Some((lit.value.to_string(), lit.span)),
+    Expression::TemplateLiteral(lit) if lit.expressions.is_empty() => lit
+      .quasis
+      .first()
+      .and_then(|q| q.value.cooked.as_ref())
+      .map(|s| (s.to_string(), lit.span)),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_allocator::Allocator;
+  use oxc_parser::Parser;
+  use oxc_semantic::SemanticBuilder;
+  use oxc_span::SourceType;
+
+  fn collect(source: &str) -> Vec<Dependency> {
+    let allocator = Allocator::default();
+    let program = Parser::new(&allocator, source, SourceType::tsx())
+      .parse()
+      .program;
+    let semantic = SemanticBuilder::new().build(&program).semantic;
+    collect_dependencies(&program, semantic.scoping())
+  }
+
+  #[test]
+  fn test_collect_dependency_kinds() {
+    let dependencies = collect(
+      "import React from 'react';\nexport * from './a';\nexport { b } from './b';\nconst c = require('./c');\nconst d = import(`./d`);\nconst e = require.resolve('./e');\nconst f = import(name);",
+    );
+    let kinds: Vec<_> = dependencies
+      .iter()
+      .map(|d| (d.specifier.as_str(), d.kind))
+      .collect();
+
+    assert_eq!(
+      kinds,
+      [
+        ("react", DependencyKind::Import),
+        ("./a", DependencyKind::ReExport),
+        ("./b", DependencyKind::ReExport),
+        ("./c", DependencyKind::Require),
+        ("./d", DependencyKind::DynamicImport),
+        ("./e", DependencyKind::RequireResolve),
+      ]
+    );
+    assert!(dependencies[4].is_async);
+    assert!(!dependencies[3].is_async);
+    assert_eq!(
+      (dependencies[0].span.start, dependencies[0].span.end),
+      (18, 25)
+    );
+  }
+
+  #[test]
+  fn test_collect_type_only_dependencies() {
+    let dependencies = collect(
+      "import type { A } from './a';\nimport { type B } from './b';\nimport { type C, D } from './c';\nexport type { E } from './e';\nimport F = require('./f');",
+    );
+    let type_only: Vec<_> = dependencies.iter().map(|d| d.is_type_only).collect();
+
+    assert_eq!(type_only, [true, true, false, true, false]);
+    assert_eq!(dependencies[4].kind, DependencyKind::Require);
+  }
+
+  #[test]
+  fn test_collect_optional_dependencies() {
+    let dependencies = collect(
+      "try { require('./optional'); } catch { require('./fallback'); }\ntry { const load = () => require('./lazy'); } finally {}",
+    );
+    let optional: Vec<_> = dependencies
+      .iter()
+      .map(|d| (d.specifier.as_str(), d.is_optional))
+      .collect();
+
+    assert_eq!(
+      optional,
+      [
+        ("./optional", true),
+        ("./fallback", false),
+        ("./lazy", false)
+      ]
+    );
+  }
+
+  #[test]
+  fn test_ignore_comments_and_shadowed_require() {
+    let dependencies = collect(
+      "// import x from 'commented';\n/* require('also-commented') */\nfunction f(require) { return require('local'); }",
+    );
+
+    assert!(dependencies.is_empty());
+  }
+}
@@ -0,0 +1,1201 @@
+use std::ops::Range;
+
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{Declaration, Program, Statement};
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_parser::Parser;
+use oxc_span::{GetSpan, SourceType};
+
+/// Statement prefixes that only declare types and can be blanked as a whole.
+const TYPE_ONLY_STATEMENTS: &[&[&str]] = &[
+  &["type"],
+  &["export", "type"],
+  &["opaque", "type"],
+  &["export", "opaque", "type"],
+  &["declare"],
+  &["export", "declare"],
+  &["import", "typeof"],
+  &["interface"],
+  &["export", "interface"],
+];
+
+/// A Flow construct that could not be turned into TypeScript-compatible syntax.
+#[derive(Debug, Clone)]
+pub struct FlowStripError {
+  pub offset: usize,
+  pub message: String,
+}
+
+/// Returns `true` when the leading comments of the file contain an `@flow` pragma.
+pub fn has_flow_pragma(source: &str) -> bool {
+  let mut rest = source.trim_start();
+  loop {
+    let comment = if let Some(line) = rest.strip_prefix("//") {
+      let end = line.find('\n').unwrap_or(line.len());
+      rest = &line[end..];
+      &line[..end]
+    } else if let Some(block) = rest.strip_prefix("/*") {
+      let end = block.find("*/").unwrap_or(block.len());
+      rest = block.get(end + 2..).unwrap_or("");
+      &block[..end]
+    } else {
+      return false;
+    };
+
+    if comment.contains("@noflow") {
+      return false;
+    }
+    if let Some(index) = comment.find("@flow") {
+      let next = comment[index + "@flow".len()..].chars().next();
+      if !next.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+        return true;
+      }
+    }
+    rest = rest.trim_start();
+  }
+}
+
+/// Upper bound on parse passes. Passes only repair what the initial scan left, and oxc stops
+/// at the first syntax error, so each pass usually repairs a single construct.
+const MAX_REPAIR_PASSES: usize = 1024;
+
+/// Rewrites Flow-only syntax so the source parses as TypeScript, which oxc then strips.
+///
+/// Every removed character is replaced by a space, so byte offsets (and therefore spans and
+/// source maps) still point at the original Flow source.
+pub fn strip_flow(source: &str, source_type: SourceType) -> Result<String, FlowStripError> {
+  strip_flow_in_passes(source, source_type).map(|(text, _)| text)
+}
+
+/// Blanks the Flow syntax found by a single scan of the source, then parses it and repairs
+/// the constructs the scan cannot tell apart from JavaScript until it parses. Also returns
+/// the number of parses.
+fn strip_flow_in_passes(
+  source: &str,
+  source_type: SourceType,
+) -> Result<(String, usize), FlowStripError> {
+  let mut bytes = source.as_bytes().to_vec();
+  strip_lexically(&mut bytes);
+
+  for pass in 1..=MAX_REPAIR_PASSES {
+    let errors = {
+      let text = std::str::from_utf8(&bytes).map_err(|e| FlowStripError {
+        offset: e.valid_up_to(),
+        message: "Flow syntax cannot be stripped: invalid UTF-8 after rewriting".to_string(),
+      })?;
+      let allocator = Allocator::default();
+      let ret = Parser::new(&allocator, text, source_type).parse();
+
+      if ret.errors.is_empty() {
+        check_unstrippable(&ret.program)?;
+        return Ok((text.to_string(), pass));
+      }
+
+      let mut errors: Vec<(usize, String)> = ret
+        .errors
+        .iter()
+        .map(|e| (error_offset(e), e.message.to_string()))
+        .collect();
+      errors.sort_by_key(|(offset, _)| *offset);
+      errors
+    };
+
+    // Rewrites keep every offset in place, so all the errors of a pass can be repaired
+    // before parsing again.
+    let mut repaired = false;
+    for (offset, message) in &errors {
+      repaired |= repair(&mut bytes, *offset, message);
+    }
+    if !repaired {
+      let (offset, message) = &errors[0];
+      return Err(FlowStripError {
+        offset: *offset,
+        message: format!("Flow syntax cannot be stripped: {}", message),
+      });
+    }
+  }
+
+  Err(FlowStripError {
+    offset: 0,
+    message: format!(
+      "Flow syntax cannot be stripped: more than {} constructs to rewrite",
+      MAX_REPAIR_PASSES
+    ),
+  })
+}
+
+fn error_offset(error: &OxcDiagnostic) -> usize {
+  error
+    .labels
+    .as_ref()
+    .and_then(|labels| {
+      labels
+        .iter()
+        .find(|l| l.primary())
+        .or(labels.first())
+        .map(|l| l.offset())
+    })
+    .unwrap_or(0)
+}
+
+/// Blanks, in one pass, the Flow constructs that are recognizable from the surrounding
+/// tokens: type-only statements, exact object braces, variance sigils of class members,
+/// bounds of declaration type parameters, existential types, `%checks` and single-line type
+/// casts. Anything ambiguous is left for the parse passes.
+fn strip_lexically(bytes: &mut [u8]) {
+  let code = code_mask(bytes);
+  let mut line_start = true;
+  let mut parens = Vec::new();
+  let mut i = 0;
+
+  while i < bytes.len() {
+    let b = bytes[i];
+    if b == b'\n' {
+      line_start = true;
+    }
+    if !code[i] || b.is_ascii_whitespace() {
+      i += 1;
+      continue;
+    }
+    if line_start && is_type_only_statement(bytes, i) {
+      let end = scan_statement_end(bytes, i);
+      blank(bytes, i..end);
+      i = end;
+      continue;
+    }
+    line_start = false;
+
+    let prev = prev_code(bytes, &code, i);
+    let next = next_code(bytes, &code, i + 1);
+    match b {
+      // Exact object types: `{| a: T |}`.
+      b'{' => {
+        if let Some(pipe) = next.filter(|&n| bytes[n] == b'|' && bytes.get(n + 1) != Some(&b'|')) {
+          bytes[pipe] = b' ';
+        }
+      }
+      b'|'
+        if prev.is_none_or(|p| bytes[p] != b'|')
+          && bytes.get(i + 1) != Some(&b'|')
+          && next.is_some_and(|n| bytes[n] == b'}') =>
+      {
+        bytes[i] = b' ';
+      }
+      // Variance sigils of class members and object type properties: `{ +x: T; -y?: U }`.
+      b'+' | b'-'
+        if prev.is_some_and(|p| matches!(bytes[p], b'{' | b';' | b','))
+          && is_annotated_member(bytes, i + 1) =>
+      {
+        bytes[i] = b' ';
+      }
+      // Existential type: `Array<*>`, `Map<*, *>`.
+      b'*' => {
+        let (prev, next) = (prev.map(|p| bytes[p]), next.map(|n| bytes[n]));
+        if matches!(
+          (prev, next),
+          (Some(b'<'), Some(b'>' | b',')) | (Some(b','), Some(b'>'))
+        ) {
+          bytes[i] = b'_';
+        }
+      }
+      // Inexact object types: `{ a: T, ... }`.
+      b'.' if bytes[i..].starts_with(b"...") => {
+        let after = next_code(bytes, &code, i + 3);
+        if after.is_some_and(|a| matches!(bytes[a], b'}' | b',')) {
+          blank(bytes, i..i + 3);
+        }
+        i += 3;
+        continue;
+      }
+      b'%' if bytes[i..].starts_with(b"%checks") => {
+        let after = next_code(bytes, &code, i + "%checks".len());
+        if after.is_some_and(|a| bytes[a] == b'{') {
+          blank(bytes, i..i + "%checks".len());
+        }
+      }
+      // Bounded type parameters of functions and classes: `function f<T: Bound>`.
+      b'<' if declares_type_parameters(bytes, prev) => strip_type_parameter_bounds(bytes, &code, i),
+      b'(' => parens.push(i),
+      b')' => {
+        if let Some(open) = parens.pop() {
+          strip_type_cast(bytes, &code, open, i);
+        }
+      }
+      _ => {}
+    }
+    i += 1;
+  }
+}
+
+/// Like `starts_type_only_statement`, but also checks what follows the keywords, so JSX text
+/// such as `type your name` is not mistaken for a statement.
+fn is_type_only_statement(bytes: &[u8], start: usize) -> bool {
+  const DECLARED: &[&str] = &[
+    "function",
+    "class",
+    "var",
+    "let",
+    "const",
+    "module",
+    "export",
+    "type",
+    "opaque",
+    "interface",
+  ];
+
+  if !starts_type_only_statement(bytes, start) {
+    return false;
+  }
+  let word = |pos: usize, word: &str| {
+    match_word(bytes, pos, word).map(|end| skip_inline_ws_forward(bytes, end))
+  };
+
+  let mut pos = word(start, "export").unwrap_or(start);
+  if let Some(next) = word(pos, "declare") {
+    return DECLARED.iter().any(|w| word(next, w).is_some());
+  }
+  if word(pos, "import").is_some() {
+    return true;
+  }
+  pos = word(pos, "opaque").unwrap_or(pos);
+  let (name, is_interface) = match (word(pos, "type"), word(pos, "interface")) {
+    (Some(name), _) => (name, false),
+    (None, Some(name)) => (name, true),
+    (None, None) => return false,
+  };
+
+  let mut pos = name;
+  while pos < bytes.len() && is_ident_byte(bytes[pos]) {
+    pos += 1;
+  }
+  pos = skip_inline_ws_forward(bytes, pos);
+  if bytes.get(pos) == Some(&b'<') {
+    let mut depth = 0;
+    while pos < bytes.len() {
+      match bytes[pos] {
+        b'=' if bytes.get(pos + 1) == Some(&b'>') => pos += 1,
+        b'<' => depth += 1,
+        b'>' => depth -= 1,
+        _ => {}
+      }
+      pos += 1;
+      if depth == 0 {
+        break;
+      }
+    }
+    pos = skip_inline_ws_forward(bytes, pos);
+  }
+  match bytes.get(pos) {
+    Some(b'{') => is_interface,
+    Some(b'=' | b':') => !is_interface,
+    _ => is_interface && match_word(bytes, pos, "extends").is_some(),
+  }
+}
+
+/// Marks the bytes that are code, as opposed to comments, strings, templates and regular
+/// expressions. Strings end at the end of their line, so an apostrophe in JSX text only
+/// hides the rest of that line.
+fn code_mask(bytes: &[u8]) -> Vec<bool> {
+  let mut code = vec![true; bytes.len()];
+  let mut prev: Option<usize> = None;
+  let mut i = 0;
+
+  while i < bytes.len() {
+    let end = match bytes[i] {
+      b'/' if bytes.get(i + 1) == Some(&b'/') => line_end(bytes, i),
+      b'/' if bytes.get(i + 1) == Some(&b'*') => bytes[i + 2..]
+        .windows(2)
+        .position(|w| w == b"*/")
+        .map_or(bytes.len(), |p| i + 2 + p + 2),
+      b'\'' | b'"' => (skip_string(bytes, i) + 1).min(bytes.len()),
+      b'`' => mask_template(bytes, &mut code, i),
+      b'/' if starts_regex(bytes, prev, i) => skip_regex(bytes, i),
+      b => {
+        if !b.is_ascii_whitespace() {
+          prev = Some(i);
+        }
+        i += 1;
+        continue;
+      }
+    };
+    let is_comment = bytes[i] == b'/' && matches!(bytes.get(i + 1), Some(b'/' | b'*'));
+    if bytes[i] != b'`' {
+      code[i..end].fill(false);
+    }
+    if !is_comment {
+      prev = Some(i);
+    }
+    i = end;
+  }
+  code
+}
+
+fn line_end(bytes: &[u8], start: usize) -> usize {
+  bytes[start..]
+    .iter()
+    .position(|&b| b == b'\n')
+    .map_or(bytes.len(), |p| start + p)
+}
+
+/// Masks the text of the template starting at `start`, leaving its substitutions as code,
+/// and returns the offset after it.
+fn mask_template(bytes: &[u8], code: &mut [bool], start: usize) -> usize {
+  code[start] = false;
+  let mut i = start + 1;
+  while i < bytes.len() {
+    code[i] = false;
+    match bytes[i] {
+      b'\\' => {
+        if let Some(escaped) = code.get_mut(i + 1) {
+          *escaped = false;
+        }
+        i += 1;
+      }
+      b'`' => return i + 1,
+      b'$' if bytes.get(i + 1) == Some(&b'{') => {
+        code[i + 1] = false;
+        let mut depth = 1;
+        i += 2;
+        while i < bytes.len() {
+          match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+              depth -= 1;
+              if depth == 0 {
+                code[i] = false;
+                break;
+              }
+            }
+            b'`' => {
+              i = mask_template(bytes, code, i);
+              continue;
+            }
+            b'\'' | b'"' => {
+              let end = (skip_string(bytes, i) + 1).min(bytes.len());
+              code[i..end].fill(false);
+              i = end;
+              continue;
+            }
+            _ => {}
+          }
+          i += 1;
+        }
+      }
+      _ => {}
+    }
+    i += 1;
+  }
+  bytes.len()
+}
+
+/// A `/` starts a regular expression where a value is expected, unless it closes a JSX
+/// element (`</View>`).
+fn starts_regex(bytes: &[u8], prev: Option<usize>, slash: usize) -> bool {
+  if slash > 0 && bytes[slash - 1] == b'<' {
+    return false;
+  }
+  match prev {
+    None => true,
+    Some(p) if is_ident_byte(bytes[p]) => {
+      matches!(
+        word_before(bytes, p + 1),
+        Some("return" | "typeof" | "case" | "in" | "of")
+      )
+    }
+    Some(p) => b"(,=:[!&|?{;+-*%<>~^".contains(&bytes[p]),
+  }
+}
+
+fn skip_regex(bytes: &[u8], start: usize) -> usize {
+  let mut in_class = false;
+  let mut i = start + 1;
+  while i < bytes.len() && bytes[i] != b'\n' {
+    match bytes[i] {
+      b'\\' => i += 1,
+      b'[' => in_class = true,
+      b']' => in_class = false,
+      b'/' if !in_class => return i + 1,
+      _ => {}
+    }
+    i += 1;
+  }
+  i
+}
+
+fn prev_code(bytes: &[u8], code: &[bool], pos: usize) -> Option<usize> {
+  (0..pos)
+    .rev()
+    .find(|&i| code[i] && !bytes[i].is_ascii_whitespace())
+}
+
+fn next_code(bytes: &[u8], code: &[bool], pos: usize) -> Option<usize> {
+  (pos..bytes.len()).find(|&i| code[i] && !bytes[i].is_ascii_whitespace())
+}
+
+/// The identifier or keyword ending at `end`.
+fn word_before(bytes: &[u8], end: usize) -> Option<&str> {
+  let start = (0..end)
+    .rev()
+    .find(|&i| !is_ident_byte(bytes[i]))
+    .map_or(0, |i| i + 1);
+  std::str::from_utf8(&bytes[start..end])
+    .ok()
+    .filter(|w| !w.is_empty())
+}
+
+/// Whether `pos` starts `name: T` or `name?: T`.
+fn is_annotated_member(bytes: &[u8], pos: usize) -> bool {
+  if !bytes.get(pos).is_some_and(|&b| is_ident_byte(b)) {
+    return false;
+  }
+  let mut i = pos;
+  while i < bytes.len() && is_ident_byte(bytes[i]) {
+    i += 1;
+  }
+  let i = skip_inline_ws_forward(bytes, i);
+  let i = if bytes.get(i) == Some(&b'?') {
+    i + 1
+  } else {
+    i
+  };
+  bytes.get(i) == Some(&b':')
+}
+
+/// Whether a `<` after `prev` opens the type parameters of a function or class declaration.
+fn declares_type_parameters(bytes: &[u8], prev: Option<usize>) -> bool {
+  let Some(prev) = prev.filter(|&p| is_ident_byte(bytes[p])) else {
+    return false;
+  };
+  let name_start = (0..=prev)
+    .rev()
+    .find(|&i| !is_ident_byte(bytes[i]))
+    .map_or(0, |i| i + 1);
+  if word_before(bytes, prev + 1) == Some("function") {
+    return true;
+  }
+  let Some(keyword_end) = skip_ws_back(bytes, name_start) else {
+    return false;
+  };
+  matches!(
+    word_before(bytes, keyword_end + 1),
+    Some("function" | "class")
+  )
+}
+
+/// Blanks the variance sigils and bounds of the type parameters opened at `open`, keeping
+/// their defaults: `<+T: Bound = Default>` becomes `< T        = Default>`.
+fn strip_type_parameter_bounds(bytes: &mut [u8], code: &[bool], open: usize) {
+  let mut depth = 0i32;
+  let mut at_param_start = true;
+  let mut i = open;
+  while i < bytes.len() {
+    if !code[i] || bytes[i].is_ascii_whitespace() {
+      i += 1;
+      continue;
+    }
+    match bytes[i] {
+      b'=' if bytes.get(i + 1) == Some(&b'>') => i += 1,
+      b'<' | b'(' | b'[' | b'{' => {
+        depth += 1;
+        at_param_start = depth == 1;
+        i += 1;
+        continue;
+      }
+      b'>' | b')' | b']' | b'}' => {
+        depth -= 1;
+        if depth == 0 {
+          return;
+        }
+      }
+      b'+' | b'-' if depth == 1 && at_param_start => bytes[i] = b' ',
+      b':' if depth == 1 => {
+        let end = scan_type_end(bytes, i + 1);
+        blank(bytes, i..end);
+        i = end;
+        continue;
+      }
+      b',' if depth == 1 => {
+        at_param_start = true;
+        i += 1;
+        continue;
+      }
+      _ => {}
+    }
+    at_param_start = false;
+    i += 1;
+  }
+}
+
+/// Blanks the annotation of a type cast written on one line, `(value: Type)`, when the
+/// parentheses are in an expression position and hold a single annotated expression.
+/// Annotated arrow function parameters match too, which is harmless as types are removed.
+fn strip_type_cast(bytes: &mut [u8], code: &[bool], open: usize, close: usize) {
+  if bytes[open..close].contains(&b'\n') {
+    return;
+  }
+  let in_expression = match prev_code(bytes, code, open) {
+    None => true,
+    Some(p) if is_ident_byte(bytes[p]) => {
+      matches!(word_before(bytes, p + 1), Some("return" | "default"))
+    }
+    Some(p) => b"=(,[:?!&|{;".contains(&bytes[p]),
+  };
+  if !in_expression {
+    return;
+  }
+
+  let mut depth = 0i32;
+  let mut colon = None;
+  let mut i = open + 1;
+  while i < close {
+    if !code[i] {
+      i += 1;
+      continue;
+    }
+    let b = bytes[i];
+    match b {
+      b'=' if bytes.get(i + 1) == Some(&b'>') => i += 1,
+      b'(' | b'[' | b'{' => depth += 1,
+      b'<' if colon.is_some() => depth += 1,
+      b')' | b']' | b'}' => depth -= 1,
+      b'>' if colon.is_some() => depth -= 1,
+      b':' if depth == 0 && colon.is_none() => colon = Some(i),
+      // Ternaries, sequences, defaults, comparisons and JSX are not casts.
+      b'?' | b'<' | b'>' if depth == 0 && colon.is_none() => return,
+      b',' | b';' | b'=' if depth == 0 => return,
+      _ => {}
+    }
+    i += 1;
+  }
+
+  if let Some(colon) = colon {
+    let has_value = bytes[open + 1..colon]
+      .iter()
+      .any(|b| !b.is_ascii_whitespace());
+    if has_value && depth == 0 {
+      blank(bytes, colon..close);
+    }
+  }
+}
+
+/// Flow enums need `flow-enums-runtime` and have no TypeScript equivalent.
+fn check_unstrippable(program: &Program) -> Result<(), FlowStripError> {
+  for stmt in program.body.iter() {
+    let enum_decl = match stmt {
+      Statement::TSEnumDeclaration(decl) => Some(decl.span),
+      Statement::ExportNamedDeclaration(export) => match &export.declaration {
+        Some(Declaration::TSEnumDeclaration(decl)) => Some(decl.span),
+        _ => None,
+      },
+      _ => None,
+    };
+    if let Some(span) = enum_decl {
+      return Err(FlowStripError {
+        offset: stmt.span().start.max(span.start) as usize,
+        message: "Flow enums require flow-enums-runtime and cannot be stripped".to_string(),
+      });
+    }
+  }
+  Ok(())
+}
+
+/// Applies the first rewrite that explains the parse error. Returns `false` when the error
+/// is not caused by a Flow construct we know how to remove.
+fn repair(bytes: &mut [u8], offset: usize, message: &str) -> bool {
+  if let Some(range) = type_only_statement_containing(bytes, offset) {
+    return blank(bytes, range);
+  }
+
+  if message.contains("Expected `=>`") {
+    return repair_type_cast(bytes, offset);
+  }
+
+  let Some(start) = skip_ws_forward(bytes, offset) else {
+    return false;
+  };
+  match bytes[start] {
+    // Bounded generics: `<T: Bound>`.
+    b':' => {
+      let end = scan_type_end(bytes, start + 1);
+      blank(bytes, start..end)
+    }
+    // Exact object types: `{| a: T |}`.
+    b'|' => {
+      let before = skip_ws_back(bytes, start).map(|i| bytes[i]);
+      let after = skip_ws_forward(bytes, start + 1).map(|i| bytes[i]);
+      (before == Some(b'{') || after == Some(b'}')) && blank(bytes, start..start + 1)
+    }
+    // Closing `|}` of an exact object type, reported at the brace.
+    b'}' => match skip_ws_back(bytes, start) {
+      Some(prev) if bytes[prev] == b'|' => blank(bytes, prev..prev + 1),
+      _ => false,
+    },
+    // Variance sigils: `+x: T`, `-y: T`, `class A<+T>`.
+    b'+' | b'-' => {
+      let next = bytes.get(start + 1).copied().unwrap_or(b' ');
+      (is_ident_byte(next) || next == b'[') && blank(bytes, start..start + 1)
+    }
+    // Existential type: `Array<*>`.
+    b'*' => {
+      bytes[start] = b'_';
+      true
+    }
+    b'%' if bytes[start..].starts_with(b"%checks") => blank(bytes, start..start + "%checks".len()),
+    _ => false,
+  }
+}
+
+/// Removes the annotation of a Flow type cast, `(value: Type)`.
+///
+/// oxc reads the cast as arrow function parameters and reports the missing `=>` at the
+/// token following the parameters (or following what it took for a return type).
+fn repair_type_cast(bytes: &mut [u8], offset: usize) -> bool {
+  let Some(prev) = skip_ws_back(bytes, offset) else {
+    return false;
+  };
+
+  let (open, close) = if bytes[prev] == b')' {
+    match match_paren_back(bytes, prev) {
+      Some(open) => (open, prev),
+      None => return false,
+    }
+  } else {
+    match innermost_open_paren(bytes, offset) {
+      Some(open) => (open, offset),
+      None => return false,
+    }
+  };
+
+  let mut depth = 0i32;
+  let mut colon = None;
+  for (i, &b) in bytes.iter().enumerate().take(close).skip(open + 1) {
+    match b {
+      b'(' | b'[' | b'{' => depth += 1,
+      b')' | b']' | b'}' => depth -= 1,
+      b':' if depth == 0 => colon = Some(i),
+      _ => {}
+    }
+  }
+
+  match colon {
+    Some(colon) => blank(bytes, colon..close),
+    None => false,
+  }
+}
+
+/// Finds a type-only statement (`type`, `opaque type`, `declare`, ...) starting at the
+/// beginning of a line and spanning `offset`.
+fn type_only_statement_containing(bytes: &[u8], offset: usize) -> Option<Range<usize>> {
+  let mut line_start = line_start(bytes, offset.min(bytes.len()));
+
+  loop {
+    let first = skip_inline_ws_forward(bytes, line_start);
+    if starts_type_only_statement(bytes, first) {
+      let end = scan_statement_end(bytes, first);
+      return (end > offset).then_some(first..end);
+    }
+
+    // Stop at the previous top-level statement boundary.
+    let continuation = matches!(bytes.get(first), Some(b'}' | b')' | b']' | b'|' | b'&'));
+    if line_start == 0 || (first == line_start && !continuation && first < offset) {
+      return None;
+    }
+    line_start = self::line_start(bytes, line_start - 1);
+  }
+}
+
+fn starts_type_only_statement(bytes: &[u8], start: usize) -> bool {
+  TYPE_ONLY_STATEMENTS.iter().any(|words| {
+    let mut pos = start;
+    for word in words.iter() {
+      let Some(end) = match_word(bytes, pos, word) else {
+        return false;
+      };
+      pos = skip_inline_ws_forward(bytes, end);
+    }
+    // The keywords must be followed by a name, not be identifiers themselves (`type = 1`,
+    // `declare(x)`).
+    match bytes.get(pos) {
+      Some(b'{' | b'*') => words.last() == Some(&"typeof"),
+      Some(&b) => is_ident_byte(b),
+      None => false,
+    }
+  })
+}
+
+fn match_word(bytes: &[u8], pos: usize, word: &str) -> Option<usize> {
+  let end = pos + word.len();
+  let matches = bytes.get(pos..end) == Some(word.as_bytes())
+    && !bytes.get(end).is_some_and(|&b| is_ident_byte(b));
+  matches.then_some(end)
+}
+
+/// Scans a type-only statement and returns the offset right after it. Such statements
+/// contain no values, so `<` and `>` are always brackets.
+fn scan_statement_end(bytes: &[u8], start: usize) -> usize {
+  let mut depth = 0i32;
+  let mut i = start;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'\'' | b'"' => i = skip_string(bytes, i),
+      b'/' if bytes.get(i + 1) == Some(&b'/') => {
+        while i < bytes.len() && bytes[i] != b'\n' {
+          i += 1;
+        }
+        continue;
+      }
+      b'=' if bytes.get(i + 1) == Some(&b'>') => i += 1,
+      b'(' | b'[' | b'{' | b'<' => depth += 1,
+      b')' | b']' | b'}' | b'>' => depth -= 1,
+      b';' if depth <= 0 => return i + 1,
+      b'\n' if depth <= 0 => {
+        let prev = skip_ws_back(bytes, i).map(|p| bytes[p]);
+        let next = skip_ws_forward(bytes, i).map(|n| bytes[n]);
+        let continues = matches!(
+          prev,
+          Some(b':' | b'=' | b'|' | b'&' | b',' | b'<' | b'(' | b'{' | b'?')
+        ) || matches!(
+          next,
+          Some(b'|' | b'&' | b'.' | b'=' | b'?' | b':' | b'{' | b'<')
+        );
+        if !continues {
+          return i;
+        }
+      }
+      _ => {}
+    }
+    i += 1;
+  }
+  bytes.len()
+}
+
+/// Returns where a type annotation starting at `start` ends: the first `,`, `)`, `>`, `]`,
+/// `}`, `;` or `=` outside of any brackets.
+fn scan_type_end(bytes: &[u8], start: usize) -> usize {
+  let mut depth = 0i32;
+  let mut i = start;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'\'' | b'"' => i = skip_string(bytes, i),
+      b'=' if bytes.get(i + 1) == Some(&b'>') => i += 1,
+      b'(' | b'[' | b'{' | b'<' => depth += 1,
+      b')' | b']' | b'}' | b'>' if depth > 0 => depth -= 1,
+      b',' | b')' | b']' | b'}' | b'>' | b';' | b'=' if depth == 0 => return i,
+      _ => {}
+    }
+    i += 1;
+  }
+  bytes.len()
+}
+
+fn skip_string(bytes: &[u8], start: usize) -> usize {
+  let quote = bytes[start];
+  let mut i = start + 1;
+  while i < bytes.len() && bytes[i] != quote && bytes[i] != b'\n' {
+    if bytes[i] == b'\\' {
+      i += 1;
+    }
+    i += 1;
+  }
+  i
+}
+
+fn match_paren_back(bytes: &[u8], close: usize) -> Option<usize> {
+  let mut depth = 0i32;
+  for i in (0..=close).rev() {
+    match bytes[i] {
+      b')' => depth += 1,
+      b'(' => {
+        depth -= 1;
+        if depth == 0 {
+          return Some(i);
+        }
+      }
+      _ => {}
+    }
+  }
+  None
+}
+
+fn innermost_open_paren(bytes: &[u8], offset: usize) -> Option<usize> {
+  let mut depth = 0i32;
+  for i in (0..offset.min(bytes.len())).rev() {
+    match bytes[i] {
+      b')' => depth += 1,
+      b'(' if depth == 0 => return Some(i),
+      b'(' => depth -= 1,
+      _ => {}
+    }
+  }
+  None
+}
+
+fn blank(bytes: &mut [u8], range: Range<usize>) -> bool {
+  let mut changed = false;
+  for b in &mut bytes[range] {
+    if !matches!(*b, b'\n' | b'\r' | b' ') {
+      *b = b' ';
+      changed = true;
+    }
+  }
+  changed
+}
+
+fn is_ident_byte(b: u8) -> bool {
+  b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80
+}
+
+fn line_start(bytes: &[u8], pos: usize) -> usize {
+  bytes[..pos]
+    .iter()
+    .rposition(|&b| b == b'\n')
+    .map_or(0, |i| i + 1)
+}
+
+fn skip_inline_ws_forward(bytes: &[u8], mut pos: usize) -> usize {
+  while pos < bytes.len() && matches!(bytes[pos], b' ' | b'\t') {
+    pos += 1;
+  }
+  pos
+}
+
+fn skip_ws_forward(bytes: &[u8], pos: usize) -> Option<usize> {
+  (pos..bytes.len()).find(|&i| !bytes[i].is_ascii_whitespace())
+}
+
+fn skip_ws_back(bytes: &[u8], pos: usize) -> Option<usize> {
+  (0..pos.min(bytes.len()))
+    .rev()
+    .find(|&i| !bytes[i].is_ascii_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn strip(source: &str) -> String {
+    strip_flow(source, SourceType::tsx()).unwrap()
+  }
+
+  #[test]
+  fn test_flow_pragma() {
+    assert!(has_flow_pragma("/**\n * @flow strict\n */\nconst x = 1;"));
+    assert!(has_flow_pragma("// @flow\nconst x = 1;"));
+    assert!(!has_flow_pragma("// @noflow\nconst x = 1;"));
+    assert!(!has_flow_pragma("const x = 1; // @flow"));
+    assert!(!has_flow_pragma("// @flowtype\nconst x = 1;"));
+  }
+
+  #[test]
+  fn test_strip_preserves_offsets() {
+    let source = "const x: {| a: string |} = y;";
+    let stripped = strip(source);
+    assert_eq!(stripped.len(), source.len());
+    assert_eq!(stripped, "const x: {  a: string  } = y;");
+  }
+
+  #[test]
+  fn test_strip_type_only_statements() {
+    let stripped = strip(
+      "opaque type T = string;\nexport type Props = $ReadOnly<{|\n  ...ViewProps,\n|}>;\nimport typeof X from 'x';\ndeclare module.exports: { a: number };\nconst a = 1;",
+    );
+    assert_eq!(stripped.trim(), "const a = 1;");
+  }
+
+  #[test]
+  fn test_strip_annotations() {
+    let stripped =
+      strip("function f<T: Object>(x: T, y: Array<*>): boolean %checks { return !!x; }");
+    assert!(!stripped.contains("Object"));
+    assert!(!stripped.contains("%checks"));
+
+    let stripped = strip("class A<+T> { +x: number; -y: T; }");
+    assert!(!stripped.contains('+'));
+    assert!(!stripped.contains('-'));
+  }
+
+  #[test]
+  fn test_strip_type_casts() {
+    assert_eq!(strip("const a = (x: any);"), "const a = (x     );");
+    assert_eq!(
+      strip("const a = ((x: any): Foo);"),
+      "const a = ((x     )     );"
+    );
+    assert_eq!(strip("(this: any).x = 1;"), "(this     ).x = 1;");
+  }
+
+  #[test]
+  fn test_strip_leaves_strings_and_jsx() {
+    let source = "const s = '{|'; const t = <Text>Don't</Text>;";
+    assert_eq!(strip(source), source);
+  }
+
+  #[test]
+  fn test_strip_react_native_component() {
+    let source = r#"/**
+ * @flow strict-local
+ */
+'use strict';
+import type {ViewProps} from '../View/ViewPropTypes';
+import * as React from 'react';
+export type Props = $ReadOnly<{|
+  ...ViewProps,
+  onPress?: ?(event: PressEvent) => mixed,
+  +color?: ?string,
+|}>;
+function Foo(props: Props): React.Node {
+  const [x, setX] = React.useState<?number>(null);
+  return <View {...props} />;
+}
+module.exports = (Foo: React.AbstractComponent<Props>);
+"#;
+    let stripped = strip(source);
+
+    assert!(!stripped.contains("$ReadOnly"));
+    assert!(!stripped.contains("AbstractComponent"));
+    assert!(stripped.contains("module.exports = (Foo"));
+  }
+
+  #[test]
+  fn test_flow_enum_is_reported() {
+    let result = strip_flow("const a = 1;\nenum Status {Active, Off}", SourceType::tsx());
+    let error = result.unwrap_err();
+    assert_eq!(error.offset, 13);
+    assert!(error.message.contains("enum"));
+  }
+
+  /// Modeled on `Libraries/Components/Switch/Switch.js` from react-native.
+  const SWITCH: &str = r#"/**
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ *
+ * @flow strict-local
+ * @format
+ */
+
+'use strict';
+
+import type {HostComponent} from '../../Renderer/shims/ReactNativeTypes';
+import type {ColorValue} from '../../StyleSheet/StyleSheet';
+import type {SyntheticEvent} from '../../Types/CoreEventTypes';
+import type {ViewProps} from '../View/ViewPropTypes';
+import typeof * as PlatformModule from '../../Utilities/Platform';
+
+import StyleSheet from '../../StyleSheet/StyleSheet';
+import Platform from '../../Utilities/Platform';
+import useMergeRefs from '../../Utilities/useMergeRefs';
+import AndroidSwitchNativeComponent, {
+  Commands as AndroidSwitchCommands,
+} from './AndroidSwitchNativeComponent';
+import SwitchNativeComponent, {
+  Commands as SwitchCommands,
+} from './SwitchNativeComponent';
+import * as React from 'react';
+import {useLayoutEffect, useRef, useState} from 'react';
+
+type Event<T, +U = T> = SyntheticEvent<T>;
+
+type SwitchChangeEventData = $ReadOnly<{|
+  target: number,
+  value: boolean,
+|}>;
+
+export type SwitchChangeEvent = SyntheticEvent<SwitchChangeEventData>;
+
+type SwitchPropsIOS = {|
+  /**
+   * Background color when the switch is turned on.
+   *
+   * *ios only*
+   */
+  onTintColor?: ?ColorValue,
+  thumbTintColor?: ?ColorValue,
+  tintColor?: ?ColorValue,
+|};
+
+type SwitchProps = $ReadOnly<{|
+  ...ViewProps,
+  ...SwitchPropsIOS,
+  disabled?: ?boolean,
+  value?: ?boolean,
+  thumbColor?: ?ColorValue,
+  trackColor?: ?$ReadOnly<{|
+    false?: ?ColorValue,
+    true?: ?ColorValue,
+  |}>,
+  ios_backgroundColor?: ?ColorValue,
+  onChange?: ?(event: SwitchChangeEvent) => Promise<void> | void,
+  onValueChange?: ?(value: boolean) => Promise<void> | void,
+  +[key: string]: mixed,
+|}>;
+
+declare export function isOn<T: {+value: ?boolean, ...}>(props: T): boolean %checks;
+
+const returnsFalse = () => false;
+const returnsTrue = () => true;
+const PATTERN = /^[{|}]+$/;
+const label = `switch ${String((null: ?string))} {| |}`;
+
+function toggle<T: boolean>(value: T): boolean %checks {
+  return !value;
+}
+
+function nativeValue(native: {+value: ?boolean, ...}, fallback: boolean): boolean {
+  return native.value ?? fallback;
+}
+
+const SwitchWithForwardedRef: React.AbstractComponent<
+  SwitchProps,
+  React.ElementRef<typeof SwitchNativeComponent>
+> = React.forwardRef(function Switch(props, forwardedRef): React.Node {
+  const {
+    disabled,
+    ios_backgroundColor,
+    onChange,
+    onValueChange,
+    style,
+    thumbColor,
+    trackColor,
+    value,
+    ...restProps
+  } = props;
+  const trackColorForFalse = trackColor?.false;
+  const trackColorForTrue = trackColor?.true;
+
+  const nativeSwitchRef = useRef<React.ElementRef<
+    typeof SwitchNativeComponent | typeof AndroidSwitchNativeComponent,
+  > | null>(null);
+
+  const ref = useMergeRefs(nativeSwitchRef, forwardedRef);
+
+  const [native, setNative] = useState({value: (null: ?boolean)});
+
+  const handleChange = (event: SwitchChangeEvent) => {
+    onChange?.(event);
+    onValueChange?.(event.nativeEvent.value);
+    setNative({value: event.nativeEvent.value});
+  };
+
+  useLayoutEffect(() => {
+    const jsValue = value === true;
+    const shouldUpdateNativeSwitch =
+      native.value != null && native.value !== jsValue;
+    if (
+      shouldUpdateNativeSwitch &&
+      nativeSwitchRef.current?.setNativeProps != null
+    ) {
+      if (Platform.OS === 'android') {
+        AndroidSwitchCommands.setNativeValue(
+          (nativeSwitchRef.current: any),
+          jsValue,
+        );
+      } else {
+        SwitchCommands.setValue((nativeSwitchRef.current: any), jsValue);
+      }
+    }
+  }, [value, native]);
+
+  if (Platform.OS === 'android') {
+    const {accessibilityState} = restProps;
+    const _disabled =
+      disabled != null ? disabled : accessibilityState?.disabled;
+
+    const _accessibilityState =
+      _disabled !== accessibilityState?.disabled
+        ? {...accessibilityState, disabled: _disabled}
+        : accessibilityState;
+
+    const platformProps = {
+      accessibilityState: _accessibilityState,
+      enabled: _disabled !== true,
+      on: value === true,
+      style,
+      thumbTintColor: thumbColor,
+      trackColorForFalse: trackColorForFalse,
+      trackColorForTrue: trackColorForTrue,
+      trackTintColor: value === true ? trackColorForTrue : trackColorForFalse,
+    };
+
+    return (
+      <AndroidSwitchNativeComponent
+        {...restProps}
+        {...platformProps}
+        accessibilityRole={props.accessibilityRole ?? 'switch'}
+        onChange={handleChange}
+        onResponderTerminationRequest={returnsFalse}
+        onStartShouldSetResponder={returnsTrue}
+        ref={ref}
+      />
+    );
+  } else {
+    const platformProps = {
+      disabled,
+      onTintColor: trackColorForTrue,
+      style: StyleSheet.compose(
+        {height: 31, width: 51},
+        StyleSheet.compose(
+          style,
+          ios_backgroundColor == null
+            ? null
+            : {backgroundColor: ios_backgroundColor, borderRadius: 16},
+        ),
+      ),
+      thumbTintColor: thumbColor,
+      tintColor: trackColorForFalse,
+      value: value === true,
+    };
+
+    return (
+      <SwitchNativeComponent
+        {...restProps}
+        {...platformProps}
+        accessibilityRole={props.accessibilityRole ?? 'switch'}
+        onChange={handleChange}
+        onResponderTerminationRequest={returnsFalse}
+        onStartShouldSetResponder={returnsTrue}
+        ref={ref}
+      >
+        Don't switch (while: loading)
+        type the name
+      </SwitchNativeComponent>
+    );
+  }
+});
+
+class SwitchState<+T: mixed = boolean> {
+  +current: T;
+  -listeners: Array<(value: T) => mixed>;
+  +platform: PlatformModule;
+
+  constructor(initial: T) {
+    this.current = initial;
+    this.listeners = [];
+  }
+}
+
+export default (SwitchWithForwardedRef: any);
+module.exports.State = (SwitchState: Class<SwitchState<*>>);
+module.exports.helpers = {toggle, nativeValue, PATTERN, label};
+"#;
+
+  #[test]
+  fn test_strip_react_native_library_in_one_parse() {
+    let (stripped, passes) = strip_flow_in_passes(SWITCH, SourceType::tsx()).unwrap();
+
+    assert_eq!(passes, 1);
+    assert_eq!(stripped.len(), SWITCH.len());
+    assert!(!stripped.contains("$ReadOnly"));
+    assert!(!stripped.contains("%checks"));
+    assert!(!stripped.contains("|}>"));
+    assert!(!stripped.contains("+current"));
+    assert!(stripped.contains("const PATTERN = /^[{|}]+$/;"));
+    assert!(stripped.contains("class SwitchState< T        = boolean> {"));
+    assert!(stripped.contains("`switch ${String((null         ))} {| |}`"));
+    assert!(stripped.contains("Don't switch (while: loading)"));
+    assert!(stripped.contains("type the name"));
+    assert!(!stripped.contains("type Event"));
+    assert!(stripped.contains("export default (SwitchWithForwardedRef     );"));
+  }
+}
@@ -0,0 +1,315 @@
+use std::collections::{BTreeSet, HashMap};
+
+use oxc_allocator::{Allocator, Vec as ArenaVec};
+use oxc_ast::ast::{
+  BindingIdentifier, Expression, IdentifierReference, Program, StaticMemberExpression,
+};
+use oxc_ast::AstBuilder;
+use oxc_ast_visit::{walk, walk_mut, Visit, VisitMut};
+use oxc_parser::Parser;
+use oxc_semantic::Scoping;
+use oxc_span::SourceType;
+
+use super::template::unique_name;
+
+/// Global object oxc references helpers from in `HelperLoaderMode::External`.
+pub const EXTERNAL_HELPERS_OBJECT: &str = "babelHelpers";
+
+/// Babel helpers that can be inlined: name, helpers it depends on, and its source.
+/// Every helper is declared as `_<name>` so dependencies can call each other.
+const INLINE_HELPERS: &[(&str, &[&str], &str)] = &[
+  (
+    "typeof",
+    &[],
+    r#"function _typeof(o) { return _typeof = "function" == typeof Symbol && "symbol" == typeof Symbol.iterator ? function (o) { return typeof o; } : function (o) { return o && "function" == typeof Symbol && o.constructor === Symbol && o !== Symbol.prototype ? "symbol" : typeof o; }, _typeof(o); }"#,
+  ),
+  (
+    "toPrimitive",
+    &["typeof"],
+    r#"function _toPrimitive(t, r) { if ("object" != _typeof(t) || !t) return t; var e = t[Symbol.toPrimitive]; if (void 0 !== e) { var i = e.call(t, r || "default"); if ("object" != _typeof(i)) return i; throw new TypeError("@@toPrimitive must return a primitive value."); } return ("string" === r ? String : Number)(t); }"#,
+  ),
+  (
+    "toPropertyKey",
+    &["typeof", "toPrimitive"],
+    r#"function _toPropertyKey(t) { var i = _toPrimitive(t, "string"); return "symbol" == _typeof(i) ? i : i + ""; }"#,
+  ),
+  (
+    "defineProperty",
+    &["toPropertyKey"],
+    r#"function _defineProperty(e, r, t) { return (r = _toPropertyKey(r)) in e ? Object.defineProperty(e, r, { value: t, enumerable: !0, configurable: !0, writable: !0 }) : e[r] = t, e; }"#,
+  ),
+  (
+    "ownKeys",
+    &[],
+    r#"function _ownKeys(e, r) { var t = Object.keys(e); if (Object.getOwnPropertySymbols) { var o = Object.getOwnPropertySymbols(e); r && (o = o.filter(function (r) { return Object.getOwnPropertyDescriptor(e, r).enumerable; })), t.push.apply(t, o); } return t; }"#,
+  ),
+  (
+    "objectSpread2",
+    &["ownKeys", "defineProperty"],
+    r#"function _objectSpread2(e) { for (var r = 1; r < arguments.length; r++) { var t = null != arguments[r] ? arguments[r] : {}; r % 2 ? _ownKeys(Object(t), !0).forEach(function (r) { _defineProperty(e, r, t[r]); }) : Object.getOwnPropertyDescriptors ? Object.defineProperties(e, Object.getOwnPropertyDescriptors(t)) : _ownKeys(Object(t)).forEach(function (r) { Object.defineProperty(e, r, Object.getOwnPropertyDescriptor(t, r)); }); } return e; }"#,
+  ),
+  (
+    "extends",
+    &[],
+    r#"function _extends() { return _extends = Object.assign ? Object.assign.bind() : function (n) { for (var e = 1; e < arguments.length; e++) { var t = arguments[e]; for (var r in t) ({}).hasOwnProperty.call(t, r) && (n[r] = t[r]); } return n; }, _extends.apply(null, arguments); }"#,
+  ),
+  (
+    "objectWithoutPropertiesLoose",
+    &[],
+    r#"function _objectWithoutPropertiesLoose(r, e) { if (null == r) return {}; var t = {}; for (var n in r) if ({}.hasOwnProperty.call(r, n)) { if (-1 !== e.indexOf(n)) continue; t[n] = r[n]; } return t; }"#,
+  ),
+  (
+    "objectWithoutProperties",
+    &["objectWithoutPropertiesLoose"],
+    r#"function _objectWithoutProperties(e, t) { if (null == e) return {}; var o, r, i = _objectWithoutPropertiesLoose(e, t); if (Object.getOwnPropertySymbols) { var n = Object.getOwnPropertySymbols(e); for (r = 0; r < n.length; r++) o = n[r], -1 === t.indexOf(o) && {}.propertyIsEnumerable.call(e, o) && (i[o] = e[o]); } return i; }"#,
+  ),
+  (
+    "objectDestructuringEmpty",
+    &[],
+    r#"function _objectDestructuringEmpty(t) { if (null == t) throw new TypeError("Cannot destructure " + t); }"#,
+  ),
+  (
+    "asyncGeneratorStep",
+    &[],
+    r#"function _asyncGeneratorStep(n, t, e, r, o, a, c) { try { var i = n[a](c), u = i.value; } catch (n) { return void e(n); } i.done ? t(u) : Promise.resolve(u).then(r, o); }"#,
+  ),
+  (
+    "asyncToGenerator",
+    &["asyncGeneratorStep"],
+    r#"function _asyncToGenerator(n) { return function () { var t = this, e = arguments; return new Promise(function (r, o) { var a = n.apply(t, e); function _next(n) { _asyncGeneratorStep(a, r, o, _next, _throw, "next", n); } function _throw(n) { _asyncGeneratorStep(a, r, o, _next, _throw, "throw", n); } _next(void 0); }); }; }"#,
+  ),
+  (
+    "taggedTemplateLiteral",
+    &[],
+    r#"function _taggedTemplateLiteral(e, t) { return t || (t = e.slice(0)), Object.freeze(Object.defineProperties(e, { raw: { value: Object.freeze(t) } })); }"#,
+  ),
+  (
+    "readOnlyError",
+    &[],
+    r#"function _readOnlyError(r) { throw new TypeError('"' + r + '" is read-only'); }"#,
+  ),
+  (
+    "writeOnlyError",
+    &[],
+    r#"function _writeOnlyError(r) { throw new TypeError('"' + r + '" is write-only'); }"#,
+  ),
+  (
+    "checkInRHS",
+    &[],
+    r#"function _checkInRHS(e) { if (Object(e) !== e) throw TypeError("right-hand side of 'in' should be an object, got " + (null !== e ? typeof e : "null")); return e; }"#,
+  ),
+  (
+    "assertClassBrand",
+    &[],
+    r#"function _assertClassBrand(e, t, n) { if ("function" == typeof e ? e === t : e.has(t)) return arguments.length < 3 ? t : n; throw new TypeError("Private element is not present on this object"); }"#,
+  ),
+  (
+    "checkPrivateRedeclaration",
+    &[],
+    r#"function _checkPrivateRedeclaration(e, t) { if (t.has(e)) throw new TypeError("Cannot initialize the same private elements twice on an object"); }"#,
+  ),
+  (
+    "classPrivateFieldInitSpec",
+    &["checkPrivateRedeclaration"],
+    r#"function _classPrivateFieldInitSpec(e, t, a) { _checkPrivateRedeclaration(e, t), t.set(e, a); }"#,
+  ),
+  (
+    "classPrivateMethodInitSpec",
+    &["checkPrivateRedeclaration"],
+    r#"function _classPrivateMethodInitSpec(e, a) { _checkPrivateRedeclaration(e, a), a.add(e); }"#,
+  ),
+  (
+    "classPrivateFieldGet2",
+    &["assertClassBrand"],
+    r#"function _classPrivateFieldGet2(s, a) { return s.get(_assertClassBrand(s, a)); }"#,
+  ),
+  (
+    "classPrivateFieldSet2",
+    &["assertClassBrand"],
+    r#"function _classPrivateFieldSet2(s, a, r) { return s.set(_assertClassBrand(s, a), r), r; }"#,
+  ),
+];
+
+/// Collects the helpers referenced as `babelHelpers.<name>`.
+#[derive(Default)]
+struct ExternalHelperCollector {
+  used: BTreeSet<String>,
+}
+
+impl<'a> Visit<'a> for ExternalHelperCollector {
+  fn visit_static_member_expression(&mut self, member: &StaticMemberExpression<'a>) {
+    if matches!(&member.object, Expression::Identifier(object) if object.name == EXTERNAL_HELPERS_OBJECT)
+    {
+      self.used.insert(member.property.name.to_string());
+    }
+    walk::walk_static_member_expression(self, member);
+  }
+}
+
+/// Rewrites `babelHelpers.<name>` references into calls to the local helper declarations.
+struct ExternalHelperRewriter<'a, 'n> {
+  ast: AstBuilder<'a>,
+  /// Local name of each helper, by helper name.
+  locals: &'n HashMap<String, String>,
+}
+
+impl<'a> VisitMut<'a> for ExternalHelperRewriter<'a, '_> {
+  fn visit_expression(&mut self, expr: &mut Expression<'a>) {
+    if let Expression::StaticMemberExpression(member) = expr {
+      if let Expression::Identifier(object) = &member.object {
+        if object.name == EXTERNAL_HELPERS_OBJECT {
+          if let Some(local) = self.locals.get(member.property.name.as_str()) {
+            let local = self.ast.atom(local);
+            *expr = self.ast.expression_identifier(member.span, local);
+            return;
+          }
+        }
+      }
+    }
+    walk_mut::walk_expression(self, expr);
+  }
+}
+
+/// Renames the `_<name>` declarations of the helper sources, and the calls between them,
+/// to their local names.
+struct HelperRenamer<'a, 'n> {
+  ast: AstBuilder<'a>,
+  /// Local name of each helper, by declared name (`_<name>`).
+  renames: &'n HashMap<String, String>,
+}
+
+impl<'a> VisitMut<'a> for HelperRenamer<'a, '_> {
+  fn visit_binding_identifier(&mut self, ident: &mut BindingIdentifier<'a>) {
+    if let Some(local) = self.renames.get(ident.name.as_str()) {
+      ident.name = self.ast.atom(local);
+    }
+  }
+
+  fn visit_identifier_reference(&mut self, ident: &mut IdentifierReference<'a>) {
+    if let Some(local) = self.renames.get(ident.name.as_str()) {
+      ident.name = self.ast.atom(local);
+    }
+  }
+}
+
+/// Turns the output of oxc's external helper mode into self-contained code by declaring
+/// every helper the program uses at its top.
+///
+/// Helpers are named `_<name>`, with a number appended when the program already has a
+/// binding of that name. Fails with the helper name when a helper has no inline
+/// implementation.
+pub fn inline_external_helpers<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+  scoping: &Scoping,
+) -> Result<(), String> {
+  let mut collector = ExternalHelperCollector::default();
+  collector.visit_program(program);
+
+  if collector.used.is_empty() {
+    return Ok(());
+  }
+
+  let mut required = Vec::new();
+  for name in &collector.used {
+    collect_helper(name, &mut required)?;
+  }
+
+  let mut taken: Vec<String> = scoping.symbol_names().map(str::to_string).collect();
+  taken.extend(
+    scoping
+      .root_unresolved_references()
+      .keys()
+      .map(|name| name.to_string()),
+  );
+  let mut locals = HashMap::new();
+  let mut renames = HashMap::new();
+  for (name, _, _) in &required {
+    let declared = format!("_{}", name);
+    let local = unique_name(&declared, &taken);
+    taken.push(local.clone());
+    locals.insert(name.to_string(), local.clone());
+    renames.insert(declared, local);
+  }
+
+  let ast = AstBuilder::new(allocator);
+  ExternalHelperRewriter {
+    ast,
+    locals: &locals,
+  }
+  .visit_program(program);
+
+  let source = required
+    .iter()
+    .map(|(_, _, source)| *source)
+    .collect::<Vec<_>>()
+    .join("\n");
+  let source = allocator.alloc_str(&source);
+  let mut helpers = Parser::new(allocator, source, SourceType::cjs())
+    .parse()
+    .program;
+  HelperRenamer {
+    ast,
+    renames: &renames,
+  }
+  .visit_program(&mut helpers);
+
+  let mut body = ArenaVec::with_capacity_in(helpers.body.len() + program.body.len(), allocator);
+  body.extend(helpers.body);
+  body.extend(std::mem::replace(
+    &mut program.body,
+    ArenaVec::new_in(allocator),
+  ));
+  program.body = body;
+
+  Ok(())
+}
+
+fn collect_helper(
+  name: &str,
+  required: &mut Vec<&'static (&'static str, &'static [&'static str], &'static str)>,
+) -> Result<(), String> {
+  if required.iter().any(|(n, _, _)| *n == name) {
+    return Ok(());
+  }
+
+  let helper = INLINE_HELPERS
+    .iter()
+    .find(|(n, _, _)| *n == name)
+    .ok_or_else(|| format!("helper '{}' cannot be inlined, use the runtime mode", name))?;
+
+  for dependency in helper.1 {
+    collect_helper(dependency, required)?;
+  }
+  required.push(helper);
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_inline_helpers_parse() {
+    for (name, dependencies, source) in INLINE_HELPERS {
+      let allocator = Allocator::default();
+      let ret = Parser::new(&allocator, source, SourceType::cjs()).parse();
+      assert!(ret.errors.is_empty(), "helper '{}' does not parse", name);
+      assert!(source.starts_with(&format!("function _{}(", name)));
+      for dependency in dependencies.iter() {
+        assert!(INLINE_HELPERS.iter().any(|(n, _, _)| n == dependency));
+      }
+    }
+  }
+
+  #[test]
+  fn test_collect_helper_orders_dependencies() {
+    let mut required = Vec::new();
+    collect_helper("objectSpread2", &mut required).unwrap();
+    let names: Vec<&str> = required.iter().map(|(n, _, _)| *n).collect();
+
+    assert_eq!(names.last(), Some(&"objectSpread2"));
+    assert!(names.contains(&"toPrimitive"));
+    assert!(collect_helper("wrapAsyncGenerator", &mut required).is_err());
+  }
+}
@@ -0,0 +1,256 @@
+use std::collections::{HashMap, HashSet};
+
+use oxc_allocator::{Allocator, CloneIn};
+use oxc_ast::ast::{
+  Argument, CallExpression, Expression, IdentifierReference, ObjectProperty, Program, Statement,
+};
+use oxc_ast_visit::{walk, walk_mut, Visit, VisitMut};
+use oxc_semantic::{Scoping, SymbolId};
+
+use super::template::{parse_expression_template, PLACEHOLDER};
+
+/// Modules Metro never inlines by default, as they are needed by almost every module.
+pub const DEFAULT_NON_INLINED_REQUIRES: [&str; 6] = [
+  "React",
+  "react",
+  "react/jsx-dev-runtime",
+  "react/jsx-runtime",
+  "react-compiler-runtime",
+  "react-native",
+];
+
+/// Global calls wrapping a module load that are inlined like `require()` itself.
+const INLINEABLE_CALLS: [&str; 3] = ["require", "_$$_IMPORT_DEFAULT", "_$$_IMPORT_ALL"];
+
+/// Moves top-level `require()` bindings to where they are used, so modules are only
+/// loaded when first needed, like Metro's `inlineRequires`.
+///
+/// A binding is inlined when it is never reassigned and every use of it is an
+/// expression. Modules in `non_inlined` are kept at the top. `helpers` are the import
+/// helpers module lowering declares in the file, inlined like the global ones.
+pub fn inline_requires<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+  scoping: &Scoping,
+  non_inlined: &[String],
+  helpers: &[String],
+) {
+  let mut references = ReferenceCollector {
+    scoping,
+    unreplaceable: HashSet::new(),
+  };
+  references.visit_program(program);
+
+  let mut inliner = RequireInliner {
+    allocator,
+    scoping,
+    inlined: HashMap::new(),
+  };
+
+  let body = std::mem::replace(&mut program.body, oxc_allocator::Vec::new_in(allocator));
+  for mut stmt in body {
+    if let Statement::VariableDeclaration(decl) = &mut stmt {
+      let mut kept = oxc_allocator::Vec::new_in(allocator);
+      for mut declarator in decl.declarations.drain(..) {
+        if let Some(init) = &mut declarator.init {
+          inliner.visit_expression(init);
+        }
+
+        let symbol_id = declarator
+          .id
+          .get_binding_identifier()
+          .and_then(|ident| ident.symbol_id.get());
+        let inlinable = symbol_id.is_some_and(|symbol_id| {
+          !references.unreplaceable.contains(&symbol_id)
+            && !scoping.symbol_is_mutated(symbol_id)
+            && !scoping.symbol_is_unused(symbol_id)
+        }) && declarator
+          .init
+          .as_ref()
+          .and_then(|init| required_module(init, scoping, helpers))
+          .is_some_and(|module| !non_inlined.iter().any(|m| m == module));
+
+        match (symbol_id, declarator.init.take()) {
+          (Some(symbol_id), Some(init)) if inlinable => {
+            inliner.inlined.insert(symbol_id, init);
+          }
+          (_, init) => {
+            declarator.init = init;
+            kept.push(declarator);
+          }
+        }
+      }
+      if kept.is_empty() {
+        continue;
+      }
+      decl.declarations = kept;
+    }
+    program.body.push(stmt);
+  }
+
+  if !inliner.inlined.is_empty() {
+    inliner.visit_program(program);
+  }
+}
+
+/// The module loaded by an inlinable initializer: a call to `require()` or to one of
+/// the import helpers, possibly followed by property accesses.
+fn required_module<'e>(
+  expr: &'e Expression,
+  scoping: &Scoping,
+  helpers: &[String],
+) -> Option<&'e str> {
+  match expr {
+    Expression::StaticMemberExpression(member) => required_module(&member.object, scoping, helpers),
+    Expression::CallExpression(call) => {
+      let is_loader = matches!(&call.callee, Expression::Identifier(ident)
+        if (INLINEABLE_CALLS.contains(&ident.name.as_str()) && is_global(ident, scoping))
+          || helpers.iter().any(|helper| ident.name == helper.as_str()));
+      if !is_loader || call.arguments.len() != 1 {
+        return None;
+      }
+      match call.arguments.first().and_then(Argument::as_expression)? {
+        Expression::StringLiteral(lit) => Some(lit.value.as_str()),
+        arg @ Expression::CallExpression(_) => required_module(arg, scoping, helpers),
+        _ => None,
+      }
+    }
+    _ => None,
+  }
+}
+
+fn is_global(ident: &IdentifierReference, scoping: &Scoping) -> bool {
+  ident
+    .reference_id
+    .get()
+    .is_none_or(|id| scoping.get_reference(id).symbol_id().is_none())
+}
+
+/// Finds the symbols used where an expression cannot be substituted, such as in
+/// `export { name }` or a JSX tag name.
+struct ReferenceCollector<'s> {
+  scoping: &'s Scoping,
+  unreplaceable: HashSet<SymbolId>,
+}
+
+impl<'a> Visit<'a> for ReferenceCollector<'_> {
+  fn visit_expression(&mut self, expr: &Expression<'a>) {
+    if !matches!(expr, Expression::Identifier(_)) {
+      walk::walk_expression(self, expr);
+    }
+  }
+
+  fn visit_identifier_reference(&mut self, ident: &IdentifierReference<'a>) {
+    let symbol_id = ident
+      .reference_id
+      .get()
+      .and_then(|id| self.scoping.get_reference(id).symbol_id());
+    if let Some(symbol_id) = symbol_id {
+      self.unreplaceable.insert(symbol_id);
+    }
+  }
+}
+
+struct RequireInliner<'a, 's> {
+  allocator: &'a Allocator,
+  scoping: &'s Scoping,
+  inlined: HashMap<SymbolId, Expression<'a>>,
+}
+
+impl<'a> RequireInliner<'a, '_> {
+  fn replacement(&self, expr: &Expression<'a>) -> Option<Expression<'a>> {
+    let Expression::Identifier(ident) = expr else {
+      return None;
+    };
+    let symbol_id = self
+      .scoping
+      .get_reference(ident.reference_id.get()?)
+      .symbol_id()?;
+    self
+      .inlined
+      .get(&symbol_id)
+      .map(|init| init.clone_in(self.allocator))
+  }
+}
+
+impl<'a> VisitMut<'a> for RequireInliner<'a, '_> {
+  fn visit_expression(&mut self, expr: &mut Expression<'a>) {
+    match self.replacement(expr) {
+      Some(replacement) => *expr = replacement,
+      None => walk_mut::walk_expression(self, expr),
+    }
+  }
+
+  fn visit_call_expression(&mut self, call: &mut CallExpression<'a>) {
+    // Calling a member of the module must not bind `this` to the module.
+    if let Some(replacement @ Expression::StaticMemberExpression(_)) =
+      self.replacement(&call.callee)
+    {
+      call.callee = parse_expression_template(
+        self.allocator,
+        &format!("0, {}", PLACEHOLDER),
+        Some(replacement),
+      );
+    }
+    walk_mut::walk_call_expression(self, call);
+  }
+
+  fn visit_object_property(&mut self, property: &mut ObjectProperty<'a>) {
+    if property.shorthand && self.replacement(&property.value).is_some() {
+      property.shorthand = false;
+    }
+    walk_mut::walk_object_property(self, property);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_codegen::Codegen;
+  use oxc_parser::Parser;
+  use oxc_semantic::SemanticBuilder;
+  use oxc_span::SourceType;
+
+  fn inline(source: &str) -> String {
+    let allocator = Allocator::default();
+    let mut program = Parser::new(&allocator, source, SourceType::mjs())
+      .parse()
+      .program;
+    let scoping = SemanticBuilder::new()
+      .build(&program)
+      .semantic
+      .into_scoping();
+    let non_inlined: Vec<String> = DEFAULT_NON_INLINED_REQUIRES
+      .iter()
+      .map(|m| m.to_string())
+      .collect();
+    inline_requires(&allocator, &mut program, &scoping, &non_inlined, &[]);
+    Codegen::new().build(&program).code
+  }
+
+  #[test]
+  fn test_inline_requires() {
+    let code = inline(
+      "var Foo = require('foo');\nvar _bar = _$$_IMPORT_DEFAULT('bar'), other = 1;\nvar _utils = require('utils');\nvar format = _utils.format;\nfunction run() { return Foo.run(_bar, { format }); }\nformat('x');",
+    );
+
+    assert_eq!(
+      code,
+      "var other = 1;\nfunction run() {\n\treturn require(\"foo\").run(_$$_IMPORT_DEFAULT(\"bar\"), { format: require(\"utils\").format });\n}\n(0, require(\"utils\").format)(\"x\");\n"
+    );
+  }
+
+  #[test]
+  fn test_keep_non_inlinable_requires() {
+    let code = inline(
+      "var React = _$$_IMPORT_DEFAULT('react');\nvar RN = require('react-native');\nvar a = require('a');\na = null;\nvar b = require('b');\nexport { b };\nvar unused = require('unused');\nvar dynamic = require(name);\nReact(RN, a, dynamic);",
+    );
+
+    assert!(code.contains("var React = _$$_IMPORT_DEFAULT(\"react\");"));
+    assert!(code.contains("var RN = require(\"react-native\");"));
+    assert!(code.contains("var a = require(\"a\");"));
+    assert!(code.contains("var b = require(\"b\");"));
+    assert!(code.contains("var unused = require(\"unused\");"));
+    assert!(code.contains("var dynamic = require(name);"));
+  }
+}
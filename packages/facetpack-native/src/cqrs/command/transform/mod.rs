@@ -0,0 +1,1406 @@
+mod batch;
+mod const_enums;
+mod define;
+mod dependencies;
+mod flow;
+mod helpers;
+mod inline_requires;
+mod modules;
+mod platform;
+mod require_context;
+mod target;
+mod template;
+mod worklets;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+pub use batch::TransformBatchCommand;
+
+use super::sourcemap::compose_source_maps;
+use crate::cqrs::estree::{program_to_json, EstreeOptions};
+use crate::cqrs::query::DiagnosticReporter;
+use crate::cqrs::traits::Command;
+use crate::domain::error::FacetpackError;
+use crate::domain::types::{
+  Dependency, DependencyKind, Diagnostic, DiagnosticSeverity, HelperMode, JsxRuntime, ModuleFormat,
+  SourceType, TransformOptions, TransformResult,
+};
+
+use const_enums::inline_const_enums;
+use define::{
+  eliminate_dead_code, replace_defines, retain_live_dependencies, used_import_bindings,
+};
+use dependencies::collect_dependencies;
+use flow::{has_flow_pragma, strip_flow, FlowStripError};
+use helpers::inline_external_helpers;
+use inline_requires::{inline_requires, DEFAULT_NON_INLINED_REQUIRES};
+use modules::{assign_dependency_map, lower_modules};
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{Expression, Program, Statement};
+use oxc_ast::{AstBuilder, AstKind};
+use oxc_codegen::{Codegen, CodegenOptions};
+use oxc_diagnostics::{OxcDiagnostic, Severity};
+use oxc_parser::{ParseOptions, Parser};
+use oxc_semantic::{Semantic, SemanticBuilder};
+use oxc_span::{SourceType as OxcSourceType, Span};
+use oxc_transformer::{
+  DecoratorOptions, HelperLoaderMode, HelperLoaderOptions, JsxOptions, JsxRuntime as OxcJsxRuntime,
+  ReactRefreshOptions, TransformOptions as OxcTransformOptions, Transformer, TypeScriptOptions,
+};
+use platform::inline_platform;
+use require_context::{lower_require_context, ContextOptions, DEFAULT_SOURCE_EXTS};
+use std::borrow::Cow;
+use target::resolve_env_options;
+use worklets::transform_worklets;
+
+pub struct TransformCommand {
+  pub filename: String,
+  pub source_text: String,
+  pub options: TransformOptions,
+}
+
+impl TransformCommand {
+  pub fn new(filename: String, source_text: String, options: Option<TransformOptions>) -> Self {
+    Self {
+      filename,
+      source_text,
+      options: options.unwrap_or_default(),
+    }
+  }
+
+  fn get_oxc_source_type(&self) -> OxcSourceType {
+    match self.options.source_type {
+      Some(SourceType::Module) => OxcSourceType::mjs(),
+      Some(SourceType::Script) => OxcSourceType::cjs(),
+      Some(SourceType::Jsx) => OxcSourceType::jsx(),
+      Some(SourceType::Tsx) => OxcSourceType::tsx(),
+      Some(SourceType::Typescript) => OxcSourceType::ts(),
+      None => OxcSourceType::from_path(&self.filename).unwrap_or_default(),
+    }
+  }
+
+  fn is_flow(&self) -> bool {
+    self
+      .options
+      .flow
+      .unwrap_or_else(|| has_flow_pragma(&self.source_text))
+  }
+
+  fn reporter<'s>(&'s self, source_text: &'s str) -> DiagnosticReporter<'s> {
+    let reporter = DiagnosticReporter::new(&self.filename, source_text);
+    match self.options.locale {
+      Some(locale) => reporter.with_locale(locale),
+      None => reporter,
+    }
+  }
+
+  /// The result of a Flow file whose syntax cannot be stripped: no code, and an error
+  /// diagnostic at the construct that could not be rewritten.
+  fn flow_error_result(&self, error: FlowStripError) -> TransformResult {
+    let reporter = self.reporter(&self.source_text);
+    let offset = error.offset.min(self.source_text.len()) as u32;
+    let diagnostic = OxcDiagnostic::error(error.message.clone()).with_label(Span::empty(offset));
+
+    TransformResult {
+      code: String::new(),
+      map: None,
+      errors: vec![error.message],
+      diagnostics: vec![reporter.report(&diagnostic, DiagnosticSeverity::Error)],
+      helpers_mode: self.helper_mode(),
+      dependencies: Vec::new(),
+      ast: None,
+    }
+  }
+
+  fn helper_mode(&self) -> HelperMode {
+    self
+      .options
+      .helpers
+      .as_ref()
+      .and_then(|h| h.mode)
+      .unwrap_or_default()
+  }
+
+  fn build_transform_options(&self) -> Result<OxcTransformOptions, FacetpackError> {
+    let mut transform_options = OxcTransformOptions::default();
+
+    let target = self.options.target.as_deref().unwrap_or("es2020");
+    transform_options.env = resolve_env_options(target).map_err(FacetpackError::InvalidTarget)?;
+
+    let module_name = self
+      .options
+      .helpers
+      .as_ref()
+      .and_then(|h| h.module.clone())
+      .map_or(Cow::Borrowed("@babel/runtime"), Cow::Owned);
+    transform_options.helper_loader = HelperLoaderOptions {
+      module_name,
+      // Inline helpers are not implemented by oxc: emit external references and
+      // replace them with local declarations after the transform.
+      mode: match self.helper_mode() {
+        HelperMode::Runtime => HelperLoaderMode::Runtime,
+        HelperMode::Inline | HelperMode::External => HelperLoaderMode::External,
+      },
+    };
+
+    if self.options.jsx.unwrap_or(true) {
+      let runtime = match self.options.jsx_runtime {
+        Some(JsxRuntime::Classic) => OxcJsxRuntime::Classic,
+        Some(JsxRuntime::Automatic) | None => OxcJsxRuntime::Automatic,
+      };
+
+      transform_options.jsx = JsxOptions {
+        runtime,
+        import_source: self.options.jsx_import_source.clone(),
+        pragma: self.options.jsx_pragma.clone(),
+        pragma_frag: self.options.jsx_pragma_frag.clone(),
+        development: self.options.jsx_development.unwrap_or(false),
+        ..Default::default()
+      };
+    }
+
+    if self.options.refresh.unwrap_or(false) {
+      let defaults = ReactRefreshOptions::default();
+      transform_options.jsx.refresh = Some(ReactRefreshOptions {
+        refresh_reg: self
+          .options
+          .refresh_reg
+          .clone()
+          .unwrap_or(defaults.refresh_reg),
+        refresh_sig: self
+          .options
+          .refresh_sig
+          .clone()
+          .unwrap_or(defaults.refresh_sig),
+        ..defaults
+      });
+    }
+
+    if self.options.typescript.unwrap_or(true) {
+      let defaults = TypeScriptOptions::default();
+      transform_options.typescript = TypeScriptOptions {
+        only_remove_type_imports: self
+          .options
+          .only_remove_type_imports
+          .unwrap_or(defaults.only_remove_type_imports),
+        allow_namespaces: self
+          .options
+          .allow_namespaces
+          .unwrap_or(defaults.allow_namespaces),
+        allow_declare_fields: self
+          .options
+          .allow_declare_fields
+          .unwrap_or(defaults.allow_declare_fields),
+        ..defaults
+      };
+    }
+
+    if self.is_flow() {
+      // Flow has no import elision: keep every import that is not `import type`.
+      transform_options.typescript.only_remove_type_imports = true;
+    }
+
+    transform_options.decorator = DecoratorOptions {
+      legacy: self.options.experimental_decorators.unwrap_or(false),
+      emit_decorator_metadata: self.options.emit_decorator_metadata.unwrap_or(false),
+    };
+
+    Ok(transform_options)
+  }
+
+  /// The path `__source` reports: relative to the project root when one is given and
+  /// contains the file.
+  fn source_path(&self) -> PathBuf {
+    let path = Path::new(&self.filename);
+    self
+      .options
+      .project_root
+      .as_ref()
+      .and_then(|root| path.strip_prefix(root).ok())
+      .unwrap_or(path)
+      .to_path_buf()
+  }
+
+  fn source_exts(&self) -> Vec<String> {
+    self.options.source_exts.clone().unwrap_or_else(|| {
+      DEFAULT_SOURCE_EXTS
+        .iter()
+        .map(|ext| ext.to_string())
+        .collect()
+    })
+  }
+
+  /// Rewrites the `_jsxFileName` variable the development JSX transform declares, so
+  /// `__source.fileName` is the path from `source_path` while the rest of the transform
+  /// keeps seeing the real filename.
+  fn relativize_jsx_file_name<'a>(&self, allocator: &'a Allocator, program: &mut Program<'a>) {
+    let path = self.source_path();
+    let source_path = path.to_string_lossy();
+    if source_path == self.filename {
+      return;
+    }
+
+    let ast = AstBuilder::new(allocator);
+    for stmt in program.body.iter_mut() {
+      let Statement::VariableDeclaration(decl) = stmt else {
+        continue;
+      };
+      for declarator in decl.declarations.iter_mut() {
+        let is_file_name = declarator
+          .id
+          .get_binding_identifier()
+          .is_some_and(|ident| ident.name.starts_with("_jsxFileName"));
+        if let Some(Expression::StringLiteral(lit)) = &mut declarator.init {
+          if is_file_name && lit.value == self.filename.as_str() {
+            lit.value = ast.atom(&source_path);
+            lit.raw = None;
+            return;
+          }
+        }
+      }
+    }
+  }
+
+  /// The modules kept at the top when inline requires are enabled for this file.
+  fn non_inlined_requires(&self) -> Option<Vec<String>> {
+    let options = self.options.inline_requires.as_ref()?;
+    let blocked = options
+      .block_list
+      .as_ref()
+      .is_some_and(|block_list| block_list.contains(&self.filename));
+    if blocked {
+      return None;
+    }
+
+    Some(options.non_inlined_requires.clone().unwrap_or_else(|| {
+      DEFAULT_NON_INLINED_REQUIRES
+        .iter()
+        .map(|m| m.to_string())
+        .collect()
+    }))
+  }
+
+  /// Parses the generated code back so the AST positions match the returned `code`. The
+  /// transformed program cannot be serialized as is: its nodes keep the spans of the source,
+  /// and the nodes the transform creates have none.
+  fn output_ast(
+    &self,
+    code: &str,
+    source_type: OxcSourceType,
+  ) -> Result<Option<String>, FacetpackError> {
+    let Some(format) = self.options.ast else {
+      return Ok(None);
+    };
+
+    let keep_jsx = source_type.is_jsx() && !self.options.jsx.unwrap_or(true);
+    let keep_typescript =
+      source_type.is_typescript() && !self.options.typescript.unwrap_or(true) && !self.is_flow();
+    let source_type = OxcSourceType::unambiguous()
+      .with_jsx(keep_jsx)
+      .with_typescript(keep_typescript);
+
+    let allocator = Allocator::default();
+    let parser_return = Parser::new(&allocator, code, source_type)
+      .with_options(ParseOptions {
+        preserve_parens: false,
+        ..Default::default()
+      })
+      .parse();
+    if parser_return.panicked {
+      return Err(FacetpackError::CodegenError(
+        parser_return
+          .errors
+          .iter()
+          .map(|e| e.to_string())
+          .collect::<Vec<_>>()
+          .join("\n"),
+      ));
+    }
+
+    let mut program = parser_return.program;
+    Ok(Some(program_to_json(
+      &mut program,
+      format,
+      // Metro maps the output back to the source through `loc`.
+      &EstreeOptions {
+        loc: true,
+        ..Default::default()
+      },
+    )))
+  }
+}
+
+impl Command for TransformCommand {
+  type Result = TransformResult;
+
+  fn execute(&self) -> Result<Self::Result, FacetpackError> {
+    let allocator = Allocator::default();
+    let mut source_type = self.get_oxc_source_type();
+
+    let flow_source;
+    let source_text = if self.is_flow() {
+      source_type = source_type.with_typescript(true).with_jsx(true);
+      flow_source = match strip_flow(&self.source_text, source_type) {
+        Ok(stripped) => stripped,
+        Err(error) => return Ok(self.flow_error_result(error)),
+      };
+      flow_source.as_str()
+    } else {
+      self.source_text.as_str()
+    };
+
+    let parser_return = Parser::new(&allocator, source_text, source_type).parse();
+
+    if parser_return.panicked {
+      return Err(FacetpackError::ParseError(
+        parser_return
+          .errors
+          .iter()
+          .map(|e| e.to_string())
+          .collect::<Vec<_>>()
+          .join("\n"),
+      ));
+    }
+
+    let mut program = parser_return.program;
+
+    let reporter = self.reporter(source_text);
+    let semantic_return = SemanticBuilder::new()
+      .with_check_syntax_error(true)
+      .build(&program);
+
+    // Early errors (a redeclared binding, an undefined label...) make the program invalid
+    // and fail the transform. Errors that leave the emitted code valid are warnings.
+    let type_declarations = type_declaration_spans(&semantic_return.semantic);
+    let mut errors = Vec::new();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for error in &semantic_return.errors {
+      let severity = match error.severity {
+        Severity::Error if is_recoverable(error, &type_declarations) => DiagnosticSeverity::Warning,
+        Severity::Error => {
+          errors.push(error.to_string());
+          DiagnosticSeverity::Error
+        }
+        Severity::Warning => DiagnosticSeverity::Warning,
+        Severity::Advice => DiagnosticSeverity::Info,
+      };
+      diagnostics.push(reporter.report(error, severity));
+    }
+
+    let mut scoping = semantic_return.semantic.into_scoping();
+
+    if self.options.optimize_const_enums.unwrap_or(false)
+      && inline_const_enums(&allocator, &mut program, &scoping)
+    {
+      scoping = SemanticBuilder::new()
+        .build(&program)
+        .semantic
+        .into_scoping();
+    }
+
+    // The transform elides `import type` and `export type`, so they are collected now.
+    let type_only_dependencies: Vec<Dependency> = collect_dependencies(&program, &scoping)
+      .into_iter()
+      .filter(|d| d.is_type_only)
+      .collect();
+
+    let transform_options = self.build_transform_options()?;
+    let transformer = Transformer::new(&allocator, Path::new(&self.filename), &transform_options);
+    let transformer_return = transformer.build_with_scoping(scoping, &mut program);
+    if self.options.jsx_development.unwrap_or(false) {
+      self.relativize_jsx_file_name(&allocator, &mut program);
+    }
+
+    errors.extend(transformer_return.errors.iter().map(|e| e.to_string()));
+    diagnostics.extend(
+      transformer_return
+        .errors
+        .iter()
+        .map(|e| reporter.report(e, DiagnosticSeverity::Error)),
+    );
+
+    let mut scoping = transformer_return.scoping;
+    // Collected after the transform, which injects the JSX runtime, helpers and refresh
+    // imports.
+    let mut dependencies = collect_dependencies(&program, &scoping);
+    dependencies.extend(type_only_dependencies);
+    if self.options.define.is_some() || self.options.platform.is_some() {
+      let used_imports = used_import_bindings(&program, &scoping);
+      if let Some(define) = &self.options.define {
+        scoping = replace_defines(&allocator, &mut program, scoping, define)
+          .map_err(FacetpackError::InvalidDefine)?;
+      }
+      if let Some(platform) = &self.options.platform {
+        inline_platform(&allocator, &mut program, &scoping, platform);
+        scoping = SemanticBuilder::new()
+          .build(&program)
+          .semantic
+          .into_scoping();
+      }
+      scoping = eliminate_dead_code(&allocator, &mut program, scoping, &used_imports);
+      retain_live_dependencies(&program, &mut dependencies);
+    }
+
+    if self.options.require_context.unwrap_or(false) {
+      dependencies.extend(
+        lower_require_context(
+          &allocator,
+          &mut program,
+          &self.filename,
+          &ContextOptions {
+            source_exts: &self.source_exts(),
+            platform: self.options.platform.as_deref(),
+            project_root: self.options.project_root.as_deref(),
+            alias: self.options.alias.as_ref(),
+          },
+        )
+        .map_err(FacetpackError::TransformError)?,
+      );
+      dependencies.sort_by_key(|d| d.span.start);
+    }
+
+    if self.options.worklets.unwrap_or(false) {
+      let warnings = transform_worklets(
+        &allocator,
+        &mut program,
+        &scoping,
+        &self.filename,
+        &self.source_text,
+      );
+      diagnostics.extend(
+        warnings
+          .iter()
+          .map(|w| reporter.report(w, DiagnosticSeverity::Warning)),
+      );
+    }
+
+    if self.helper_mode() == HelperMode::Inline {
+      inline_external_helpers(&allocator, &mut program, &scoping)
+        .map_err(FacetpackError::TransformError)?;
+    }
+
+    let module_format = self.options.module_format.unwrap_or_default();
+    let import_helpers = lower_modules(&allocator, &mut program, &scoping, module_format);
+
+    if let Some(non_inlined) = self.non_inlined_requires() {
+      // Module lowering declares new bindings: resolve references again.
+      scoping = SemanticBuilder::new()
+        .build(&program)
+        .semantic
+        .into_scoping();
+      inline_requires(
+        &allocator,
+        &mut program,
+        &scoping,
+        &non_inlined,
+        &import_helpers,
+      );
+    }
+
+    if module_format == ModuleFormat::MetroCjs {
+      let unloaded: Vec<_> = dependencies
+        .iter()
+        .filter(|d| d.kind == DependencyKind::Context || d.is_type_only)
+        .cloned()
+        .collect();
+      dependencies = assign_dependency_map(&allocator, &mut program, &scoping, &dependencies);
+      // Context directories and type-only imports are not loaded, so they have no index in
+      // the dependency map.
+      dependencies.extend(unloaded);
+    }
+
+    let codegen_options = CodegenOptions {
+      source_map_path: if self.options.sourcemap.unwrap_or(false)
+        || self.options.input_source_map.is_some()
+      {
+        Some(self.filename.clone().into())
+      } else {
+        None
+      },
+      ..Default::default()
+    };
+
+    let codegen_return = Codegen::new().with_options(codegen_options).build(&program);
+
+    let map = match (codegen_return.map, &self.options.input_source_map) {
+      (Some(map), Some(input)) => Some(
+        compose_source_maps(input, &map)
+          .map_err(FacetpackError::InvalidSourceMap)?
+          .to_json_string(),
+      ),
+      (map, _) => map.map(|m| m.to_json_string()),
+    };
+
+    let ast = self.output_ast(&codegen_return.code, source_type)?;
+
+    Ok(TransformResult {
+      code: codegen_return.code,
+      map,
+      errors,
+      diagnostics,
+      helpers_mode: self.helper_mode(),
+      dependencies,
+      ast,
+    })
+  }
+}
+
+/// The names of the type aliases and interfaces of the program.
+fn type_declaration_spans(semantic: &Semantic) -> HashSet<Span> {
+  semantic
+    .nodes()
+    .iter()
+    .filter_map(|node| match node.kind() {
+      AstKind::TSTypeAliasDeclaration(decl) => Some(decl.id.span),
+      AstKind::TSInterfaceDeclaration(decl) => Some(decl.id.span),
+      _ => None,
+    })
+    .collect()
+}
+
+/// Whether a semantic error leaves a program that runs once its types are stripped: checks
+/// only TypeScript reports (a missing overload implementation...) and redeclared types.
+fn is_recoverable(error: &OxcDiagnostic, type_declarations: &HashSet<Span>) -> bool {
+  if error.code.scope.as_deref() == Some("TS") {
+    return true;
+  }
+  let labels = error.labels.as_deref().unwrap_or_default();
+  !labels.is_empty()
+    && labels.iter().all(|label| {
+      let span = Span::sized(label.offset() as u32, label.len() as u32);
+      type_declarations.contains(&span)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::domain::types::{AstFormat, HelperOptions, InlineRequiresOptions, Locale};
+  use std::collections::HashMap;
+
+  #[test]
+  fn test_transform_typescript_stripping() {
+    let command = TransformCommand::new(
+      "test.ts".to_string(),
+      "const x: number = 1;".to_string(),
+      None,
+    );
+    let result = command.execute().unwrap();
+
+    assert!(result.errors.is_empty());
+    assert!(!result.code.contains(":"));
+    assert!(result.code.contains("const x = 1"));
+  }
+
+  #[test]
+  fn test_transform_jsx_automatic() {
+    let command = TransformCommand::new(
+      "test.tsx".to_string(),
+      "const App = () => <div>Hello</div>;".to_string(),
+      None,
+    );
+    let result = command.execute().unwrap();
+
+    assert!(result.errors.is_empty());
+    assert!(!result.code.contains("<div>"));
+    assert!(result.code.contains("jsx"));
+  }
+
+  #[test]
+  fn test_transform_jsx_classic() {
+    let options = TransformOptions {
+      jsx_runtime: Some(JsxRuntime::Classic),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "test.tsx".to_string(),
+      "const App = () => <div>Hello</div>;".to_string(),
+      Some(options),
+    );
+    let result = command.execute().unwrap();
+
+    assert!(result.errors.is_empty());
+    assert!(!result.code.contains("<div>"));
+    assert!(result.code.contains("createElement"));
+  }
+
+  #[test]
+  fn test_transform_with_sourcemap() {
+    let options = TransformOptions {
+      sourcemap: Some(true),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "test.ts".to_string(),
+      "const x: number = 1;".to_string(),
+      Some(options),
+    );
+    let result = command.execute().unwrap();
+
+    assert!(result.errors.is_empty());
+    assert!(result.map.is_some());
+    assert!(result.map.unwrap().contains("mappings"));
+  }
+
+  #[test]
+  fn test_transform_complex_tsx() {
+    let code = r#"
+      interface Props {
+        name: string;
+      }
+      const Greeting = ({ name }: Props) => {
+        return <div>Hello, {name}!</div>;
+      };
+      export default Greeting;
+    "#;
+    let command = TransformCommand::new("Greeting.tsx".to_string(), code.to_string(), None);
+    let result = command.execute().unwrap();
+
+    assert!(result.errors.is_empty());
+    assert!(!result.code.contains("interface"));
+    assert!(!result.code.contains(": Props"));
+    assert!(!result.code.contains("<div>"));
+  }
+
+  #[test]
+  fn test_transform_all_source_types() {
+    let options = TransformOptions {
+      source_type: Some(SourceType::Module),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "test.txt".to_string(),
+      "export const x = 1;".to_string(),
+      Some(options),
+    );
+    assert!(command.execute().is_ok());
+
+    let options = TransformOptions {
+      source_type: Some(SourceType::Script),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "test.txt".to_string(),
+      "var x = 1;".to_string(),
+      Some(options),
+    );
+    assert!(command.execute().is_ok());
+
+    let options = TransformOptions {
+      source_type: Some(SourceType::Jsx),
+      ..Default::default()
+    };
+    let command =
+      TransformCommand::new("test.txt".to_string(), "<div/>".to_string(), Some(options));
+    assert!(command.execute().is_ok());
+
+    let options = TransformOptions {
+      source_type: Some(SourceType::Tsx),
+      ..Default::default()
+    };
+    let command =
+      TransformCommand::new("test.txt".to_string(), "<div/>".to_string(), Some(options));
+    assert!(command.execute().is_ok());
+
+    let options = TransformOptions {
+      source_type: Some(SourceType::Typescript),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "test.txt".to_string(),
+      "const x: number = 1;".to_string(),
+      Some(options),
+    );
+    assert!(command.execute().is_ok());
+  }
+
+  #[test]
+  fn test_transform_parse_error() {
+    let command = TransformCommand::new(
+      "test.ts".to_string(),
+      "const x: number = ;".to_string(),
+      None,
+    );
+    let result = command.execute();
+
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_transform_empty_code() {
+    let command = TransformCommand::new("test.js".to_string(), "".to_string(), None);
+    let result = command.execute().unwrap();
+
+    assert!(result.errors.is_empty());
+  }
+
+  #[test]
+  fn test_error_transform_invalid_jsx_attribute() {
+    let code = r#"const App = () => <View style= />;"#;
+    let command = TransformCommand::new("Component.tsx".to_string(), code.to_string(), None);
+    let result = command.execute();
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_error_transform_unclosed_jsx_tag() {
+    let code = r#"const App = () => <View><Text>Hello</View>;"#;
+    let command = TransformCommand::new("App.tsx".to_string(), code.to_string(), None);
+    let result = command.execute();
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_error_transform_invalid_typescript_generic() {
+    let code = "const fn = <T,>(x: T) => x; fn<>(5);";
+    let command = TransformCommand::new("generic.ts".to_string(), code.to_string(), None);
+    let _result = command.execute();
+  }
+
+  #[test]
+  fn test_error_transform_mixed_jsx_children() {
+    let code = r#"const App = () => <View>{items.map(i => <Text key={i}>{i}</Text>)}</View>;"#;
+    let command = TransformCommand::new("List.tsx".to_string(), code.to_string(), None);
+    let result = command.execute();
+    assert!(result.is_ok());
+  }
+
+  #[test]
+  fn test_error_transform_async_in_wrong_context() {
+    let code = r#"const x = await fetch('/api');"#;
+    let command = TransformCommand::new("api.ts".to_string(), code.to_string(), None);
+    let _result = command.execute();
+  }
+
+  #[test]
+  fn test_transform_default_target_keeps_es2020_syntax() {
+    let command =
+      TransformCommand::new("test.js".to_string(), "const x = a ?? b;".to_string(), None);
+    let result = command.execute().unwrap();
+
+    assert!(result.code.contains("??"));
+  }
+
+  #[test]
+  fn test_transform_target_lowers_syntax() {
+    let options = TransformOptions {
+      target: Some("es2019".to_string()),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "test.js".to_string(),
+      "const x = a ?? b;".to_string(),
+      Some(options),
+    );
+    let result = command.execute().unwrap();
+
+    assert!(!result.code.contains("??"));
+  }
+
+  #[test]
+  fn test_transform_hermes_target_lowers_async() {
+    let options = TransformOptions {
+      target: Some("hermes-stable".to_string()),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "test.js".to_string(),
+      "async function load() { await fetch('/'); }".to_string(),
+      Some(options),
+    );
+    let result = command.execute().unwrap();
+
+    assert!(!result.code.contains("await"));
+    assert!(result.code.contains("yield"));
+  }
+
+  #[test]
+  fn test_transform_invalid_target() {
+    let options = TransformOptions {
+      target: Some("netscape4".to_string()),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "test.js".to_string(),
+      "const x = 1;".to_string(),
+      Some(options),
+    );
+
+    assert!(command.execute().is_err());
+  }
+
+  fn transform_with_helpers(code: &str, helpers: HelperOptions) -> TransformResult {
+    let options = TransformOptions {
+      target: Some("es2017".to_string()),
+      helpers: Some(helpers),
+      ..Default::default()
+    };
+    TransformCommand::new("test.js".to_string(), code.to_string(), Some(options))
+      .execute()
+      .unwrap()
+  }
+
+  #[test]
+  fn test_transform_runtime_helpers() {
+    let result = transform_with_helpers(
+      "const b = { ...a };",
+      HelperOptions {
+        module: Some("@oxc-project/runtime".to_string()),
+        ..Default::default()
+      },
+    );
+
+    assert_eq!(result.helpers_mode, HelperMode::Runtime);
+    assert!(result
+      .code
+      .contains("@oxc-project/runtime/helpers/objectSpread2"));
+  }
+
+  #[test]
+  fn test_transform_external_helpers() {
+    let result = transform_with_helpers(
+      "const b = { ...a };",
+      HelperOptions {
+        mode: Some(HelperMode::External),
+        ..Default::default()
+      },
+    );
+
+    assert_eq!(result.helpers_mode, HelperMode::External);
+    assert!(result.code.contains("babelHelpers.objectSpread2"));
+    assert!(!result.code.contains("import"));
+  }
+
+  #[test]
+  fn test_transform_inline_helpers() {
+    let result = transform_with_helpers(
+      "const b = { ...a };",
+      HelperOptions {
+        mode: Some(HelperMode::Inline),
+        ..Default::default()
+      },
+    );
+
+    assert_eq!(result.helpers_mode, HelperMode::Inline);
+    assert!(!result.code.contains("import"));
+    assert!(!result.code.contains("babelHelpers"));
+    assert!(result.code.contains("function _objectSpread2"));
+    assert!(result.code.contains("function _defineProperty"));
+    assert!(result.code.contains("_objectSpread2({}, a)"));
+  }
+
+  #[test]
+  fn test_transform_inline_helpers_avoid_existing_bindings() {
+    let result = transform_with_helpers(
+      "import { _defineProperty } from './utils';\nfunction _ownKeys() {}\nexport const b = { ..._defineProperty(a), c: _ownKeys() };",
+      HelperOptions {
+        mode: Some(HelperMode::Inline),
+        ..Default::default()
+      },
+    );
+
+    assert!(result
+      .code
+      .contains("import { _defineProperty } from \"./utils\";"));
+    assert!(result.code.contains("function _defineProperty2(e, r, t)"));
+    assert!(result.code.contains("function _ownKeys2(e, r)"));
+    assert!(result.code.contains("_ownKeys2(Object(t), !0).forEach"));
+    assert!(result.code.contains("_defineProperty2(e, r, t[r])"));
+    assert!(result
+      .code
+      .contains("_objectSpread2(_objectSpread2({}, _defineProperty(a))"));
+  }
+
+  #[test]
+  fn test_transform_inline_helpers_unsupported() {
+    let options = TransformOptions {
+      target: Some("es2017".to_string()),
+      helpers: Some(HelperOptions {
+        mode: Some(HelperMode::Inline),
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "test.js".to_string(),
+      "async function* gen() { yield 1; }".to_string(),
+      Some(options),
+    );
+
+    assert!(command.execute().is_err());
+  }
+
+  #[test]
+  fn test_transform_flow_pragma() {
+    let command = TransformCommand::new(
+      "Button.js".to_string(),
+      "// @flow\nimport type { Node } from 'react';\nimport { View } from 'react-native';\ntype Props = {| title: ?string |};\nexport default function Button(props: Props): Node { return <View />; }".to_string(),
+      None,
+    );
+    let result = command.execute().unwrap();
+
+    assert!(!result.code.contains("Props ="));
+    assert!(!result.code.contains("Node"));
+    assert!(result.code.contains("react-native"));
+    assert!(result.code.contains("function Button(props)"));
+  }
+
+  #[test]
+  fn test_transform_flow_option() {
+    let options = TransformOptions {
+      flow: Some(true),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "cast.js".to_string(),
+      "opaque type Id = string;\nconst id = (value: any);".to_string(),
+      Some(options),
+    );
+    let result = command.execute().unwrap();
+
+    assert!(!result.code.contains("opaque"));
+    assert!(result.code.contains("const id = value;"));
+  }
+
+  #[test]
+  fn test_transform_flow_unstrippable() {
+    let command = TransformCommand::new(
+      "enum.js".to_string(),
+      "// @flow\nenum Status {Active, Off}".to_string(),
+      None,
+    );
+    let result = command.execute().unwrap();
+
+    assert!(result.code.is_empty());
+    assert_eq!(result.errors.len(), 1);
+    assert!(result.errors[0].contains("Flow enums"));
+    let diag = &result.diagnostics[0];
+    assert!(matches!(diag.severity, DiagnosticSeverity::Error));
+    assert_eq!((diag.line, diag.column), (2, 1));
+  }
+
+  #[test]
+  fn test_transform_with_input_source_map() {
+    let input_map = r#"{"version":3,"sources":["Greeting.vue"],"sourcesContent":["<script>\nconst greeting: string = 'hi';\n</script>"],"names":[],"mappings":"AACA"}"#;
+    let options = TransformOptions {
+      input_source_map: Some(input_map.to_string()),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "Greeting.ts".to_string(),
+      "const greeting: string = 'hi';".to_string(),
+      Some(options),
+    );
+    let map = command.execute().unwrap().map.unwrap();
+
+    assert!(map.contains(r#""sources":["Greeting.vue"]"#));
+    assert!(map.contains("<script>"));
+  }
+
+  #[test]
+  fn test_transform_worklets() {
+    let source = "import { useAnimatedStyle } from 'react-native-reanimated';\nexport function Box({ progress }: Props) {\n  const style = useAnimatedStyle(() => ({ opacity: progress.value as number }));\n  return <Animated.View style={style} />;\n}";
+    let options = TransformOptions {
+      worklets: Some(true),
+      ..Default::default()
+    };
+    let command = TransformCommand::new("Box.tsx".to_string(), source.to_string(), Some(options));
+    let result = command.execute().unwrap();
+
+    assert!(result.code.contains("_worklet.__closure = { progress };"));
+    assert!(result
+      .code
+      .contains("this.__closure;return{opacity:progress.value}"));
+
+    let command = TransformCommand::new("Box.tsx".to_string(), source.to_string(), None);
+    assert!(!command.execute().unwrap().code.contains("__workletHash"));
+  }
+
+  #[test]
+  fn test_transform_require_context() {
+    let filename = concat!(env!("CARGO_MANIFEST_DIR"), "/src/lib.rs").to_string();
+    let source = "const ctx = require.context('./domain', false, /types\\.rs$/);";
+    let options = TransformOptions {
+      require_context: Some(true),
+      source_exts: Some(vec!["rs".to_string()]),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(filename.clone(), source.to_string(), Some(options));
+    let result = command.execute().unwrap();
+    let specifiers: Vec<_> = result
+      .dependencies
+      .iter()
+      .map(|d| d.specifier.as_str())
+      .collect();
+
+    assert_eq!(specifiers, ["./domain", "./domain/types.rs"]);
+    assert_eq!(result.dependencies[0].kind, DependencyKind::Context);
+    assert!(result.code.contains("require(\"./domain/types.rs\")"));
+
+    let command = TransformCommand::new(filename, source.to_string(), None);
+    let result = command.execute().unwrap();
+    assert!(result.dependencies.is_empty());
+    assert!(result.code.contains("require.context"));
+  }
+
+  #[test]
+  fn test_transform_babel_ast() {
+    let source = "const label: string = 'é';\nexport const App = () => <Text>{label}</Text>;";
+    let options = TransformOptions {
+      ast: Some(AstFormat::Babel),
+      ..Default::default()
+    };
+    let command = TransformCommand::new("App.tsx".to_string(), source.to_string(), Some(options));
+    let result = command.execute().unwrap();
+    let ast = result.ast.unwrap();
+
+    assert!(ast.starts_with("{\"type\":\"File\""));
+    assert!(ast.contains("\"sourceType\":\"module\""));
+    assert!(ast.contains("\"type\":\"ImportDeclaration\""));
+    assert!(ast.contains("\"type\":\"StringLiteral\""));
+    assert!(!ast.contains("JSXElement"));
+
+    let command = TransformCommand::new("App.tsx".to_string(), source.to_string(), None);
+    assert!(command.execute().unwrap().ast.is_none());
+  }
+
+  #[test]
+  fn test_transform_metro_module_format() {
+    let source = "import { View } from 'react-native';\nimport type { Props } from './types';\nexport const App = (props: Props) => <View {...props} />;";
+    let options = TransformOptions {
+      module_format: Some(ModuleFormat::MetroCjs),
+      ..Default::default()
+    };
+    let command = TransformCommand::new("App.tsx".to_string(), source.to_string(), Some(options));
+    let result = command.execute().unwrap();
+    let specifiers: Vec<_> = result
+      .dependencies
+      .iter()
+      .map(|d| d.specifier.as_str())
+      .collect();
+
+    assert!(result
+      .code
+      .contains("var _reactNative = _$$_REQUIRE(_dependencyMap[0], \"react-native\");"));
+    assert!(result.code.contains("exports.App = App;"));
+    assert!(!result.code.contains("import "));
+    assert_eq!(specifiers, ["react-native", "react/jsx-runtime", "./types"]);
+    assert!(result.dependencies[2].is_type_only);
+    assert_eq!(result.dependencies[0].span.start, 21);
+  }
+
+  #[test]
+  fn test_transform_dependencies_follow_the_transform() {
+    let source = "import type { Props } from './types';\nimport { Theme } from './theme';\nimport { View } from 'react-native';\nexport const App = (props: Props & Theme) => <View {...props} />;";
+    let command = TransformCommand::new("App.tsx".to_string(), source.to_string(), None);
+    let result = command.execute().unwrap();
+    let mut specifiers: Vec<_> = result
+      .dependencies
+      .iter()
+      .map(|d| (d.specifier.as_str(), d.is_type_only))
+      .collect();
+    specifiers.sort();
+
+    assert_eq!(
+      specifiers,
+      [
+        ("./types", true),
+        ("react-native", false),
+        ("react/jsx-runtime", false)
+      ]
+    );
+  }
+
+  #[test]
+  fn test_transform_inline_requires() {
+    let source = "import React from 'react';\nimport { format } from './format';\nexport function label(value) { return format(value); }";
+    let options = |block_list: Vec<String>| TransformOptions {
+      module_format: Some(ModuleFormat::MetroCjs),
+      inline_requires: Some(InlineRequiresOptions {
+        block_list: Some(block_list),
+        non_inlined_requires: None,
+      }),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "label.js".to_string(),
+      source.to_string(),
+      Some(options(vec![])),
+    );
+    let result = command.execute().unwrap();
+
+    assert!(result
+      .code
+      .contains("var React = _$$_IMPORT_DEFAULT(_dependencyMap[0], \"react\");"));
+    assert!(result
+      .code
+      .contains("return (0, _$$_REQUIRE(_dependencyMap[1], \"./format\").format)(value);"));
+
+    let command = TransformCommand::new(
+      "label.js".to_string(),
+      source.to_string(),
+      Some(options(vec!["label.js".to_string()])),
+    );
+    let result = command.execute().unwrap();
+    assert!(result.code.contains("var format = _format.format;"));
+  }
+
+  #[test]
+  fn test_transform_inline_requires_cjs() {
+    let source = "import Button from './button';\nimport * as colors from './colors';\nimport { format } from './format';\nexport const label = () => format(Button, colors);";
+    let options = |module_format| TransformOptions {
+      module_format: Some(module_format),
+      inline_requires: Some(InlineRequiresOptions::default()),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "label.js".to_string(),
+      source.to_string(),
+      Some(options(ModuleFormat::Cjs)),
+    );
+    let code = command.execute().unwrap().code;
+
+    assert!(code.contains("function _importDefault(m)"));
+    assert!(!code.contains("var Button"));
+    assert!(!code.contains("var colors"));
+    assert!(code.contains(
+      "(0, require(\"./format\").format)(_importDefault(require(\"./button\")), _importAll(require(\"./colors\")))"
+    ));
+
+    // Imports are kept as is in `esm`: only the `require()` calls of the source are inlined.
+    let command = TransformCommand::new(
+      "label.js".to_string(),
+      "import a from 'a';\nconst b = require('b');\nexport const f = () => [a, b];".to_string(),
+      Some(options(ModuleFormat::Esm)),
+    );
+    let code = command.execute().unwrap().code;
+    assert!(code.contains("import a from \"a\";"));
+    assert!(code.contains("[a, require(\"b\")]"));
+  }
+
+  #[test]
+  fn test_transform_define() {
+    let source = "import { connect } from './devtools';\nimport { View } from 'react-native';\nif (__DEV__) { connect(); require('./dev-only'); }\nexport const env = process.env.NODE_ENV;\nexport const App = () => <View />;";
+    let options = TransformOptions {
+      define: Some(HashMap::from([
+        ("__DEV__".to_string(), "false".to_string()),
+        (
+          "process.env.NODE_ENV".to_string(),
+          "'production'".to_string(),
+        ),
+      ])),
+      ..Default::default()
+    };
+    let command = TransformCommand::new("App.tsx".to_string(), source.to_string(), Some(options));
+    let result = command.execute().unwrap();
+    let specifiers: Vec<_> = result
+      .dependencies
+      .iter()
+      .map(|d| d.specifier.as_str())
+      .collect();
+
+    assert!(!result.code.contains("devtools"));
+    assert!(!result.code.contains("__DEV__"));
+    assert!(result.code.contains("export const env = \"production\";"));
+    assert_eq!(specifiers, ["react-native", "react/jsx-runtime"]);
+  }
+
+  #[test]
+  fn test_transform_invalid_define() {
+    let options = TransformOptions {
+      define: Some(HashMap::from([("__DEV__".to_string(), "{".to_string())])),
+      ..Default::default()
+    };
+    let command = TransformCommand::new("a.js".to_string(), "__DEV__;".to_string(), Some(options));
+
+    assert!(matches!(
+      command.execute(),
+      Err(FacetpackError::InvalidDefine(_))
+    ));
+  }
+
+  #[test]
+  fn test_transform_platform() {
+    let source = "import { Platform, View } from 'react-native';\nimport { IosOnly } from './IosOnly';\nexport const App = () => Platform.OS === 'ios' ? <IosOnly /> : <View />;\nexport const padding = Platform.select({ ios: 8, android: 4 });";
+    let options = TransformOptions {
+      platform: Some("android".to_string()),
+      ..Default::default()
+    };
+    let command = TransformCommand::new("App.tsx".to_string(), source.to_string(), Some(options));
+    let result = command.execute().unwrap();
+    let specifiers: Vec<_> = result
+      .dependencies
+      .iter()
+      .map(|d| d.specifier.as_str())
+      .collect();
+
+    assert!(!result.code.contains("IosOnly"));
+    assert!(result.code.contains("export const padding = 4;"));
+    assert!(result
+      .code
+      .contains("import { View } from \"react-native\";"));
+    assert_eq!(specifiers, ["react-native", "react/jsx-runtime"]);
+  }
+
+  #[test]
+  fn test_transform_refresh() {
+    let source = "import { useState } from 'react';\nexport function Counter() {\n  const [count] = useState(0);\n  return <span>{count}</span>;\n}";
+    let options = TransformOptions {
+      refresh: Some(true),
+      refresh_reg: Some("__register__".to_string()),
+      ..Default::default()
+    };
+    let command =
+      TransformCommand::new("Counter.tsx".to_string(), source.to_string(), Some(options));
+    let result = command.execute().unwrap();
+
+    assert!(result.code.contains("__register__(_c, \"Counter\")"));
+    assert!(result.code.contains("$RefreshSig$()"));
+    assert!(result.code.contains("_s(Counter, "));
+
+    let command = TransformCommand::new("Counter.tsx".to_string(), source.to_string(), None);
+    assert!(!command.execute().unwrap().code.contains("$RefreshSig$"));
+  }
+
+  #[test]
+  fn test_transform_jsx_development() {
+    let source = "export const App = () => <View>\n  <Text />\n</View>;";
+    let options = TransformOptions {
+      jsx_development: Some(true),
+      project_root: Some("/project".to_string()),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "/project/src/App.tsx".to_string(),
+      source.to_string(),
+      Some(options),
+    );
+    let result = command.execute().unwrap();
+
+    assert!(result.code.contains("react/jsx-dev-runtime"));
+    assert!(result.code.contains("jsxDEV(Text"));
+    assert!(result.code.contains("\"src/App.tsx\""));
+    assert!(!result.code.contains("/project/src/App.tsx"));
+    assert!(result.code.contains("lineNumber: 2"));
+    assert!(result.code.contains("columnNumber: 3"));
+    assert!(result.code.contains("this)"));
+
+    let options = TransformOptions {
+      jsx_development: Some(true),
+      jsx_runtime: Some(JsxRuntime::Classic),
+      ..Default::default()
+    };
+    let command = TransformCommand::new(
+      "/project/src/App.tsx".to_string(),
+      source.to_string(),
+      Some(options),
+    );
+    let result = command.execute().unwrap();
+    assert!(result.code.contains("__source"));
+    assert!(result.code.contains("__self: this"));
+    assert!(result.code.contains("\"/project/src/App.tsx\""));
+  }
+
+  #[test]
+  fn test_early_errors_fail_the_transform() {
+    let cmd = TransformCommand::new(
+      "app.js".to_string(),
+      "let a = 1;\nlet a = 2;\nexport default a;".to_string(),
+      None,
+    );
+    let result = cmd.execute().unwrap();
+
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.diagnostics.len(), 1);
+    let diag = &result.diagnostics[0];
+    assert!(matches!(diag.severity, DiagnosticSeverity::Error));
+    assert_eq!(diag.code.as_deref(), Some("E0050"));
+    assert_eq!((diag.line, diag.column), (1, 5));
+    assert!(diag.formatted.contains("ERROR"));
+
+    let cmd = TransformCommand::new(
+      "loop.js".to_string(),
+      "for (;;) { continue outer; }".to_string(),
+      None,
+    );
+    assert!(!cmd.execute().unwrap().errors.is_empty());
+  }
+
+  #[test]
+  fn test_recoverable_semantic_errors_are_warnings() {
+    let cmd = TransformCommand::new(
+      "app.ts".to_string(),
+      "type T = 1;\ntype T = 2;\nclass K { constructor(); }\nexport const x = 1;".to_string(),
+      None,
+    );
+    let result = cmd.execute().unwrap();
+
+    assert!(result.errors.is_empty());
+    assert_eq!(result.diagnostics.len(), 2);
+    assert!(result
+      .diagnostics
+      .iter()
+      .all(|d| matches!(d.severity, DiagnosticSeverity::Warning)));
+    assert!(result.code.contains("export const x = 1;"));
+
+    let options = TransformOptions {
+      source_type: Some(SourceType::Script),
+      ..Default::default()
+    };
+    let cmd = TransformCommand::new(
+      "legacy.js".to_string(),
+      "{ function f() {} function f() {} }".to_string(),
+      Some(options),
+    );
+    let result = cmd.execute().unwrap();
+
+    assert!(result.errors.is_empty());
+    assert!(result.diagnostics.is_empty());
+  }
+
+  #[test]
+  fn test_localized_diagnostics() {
+    let transform = |locale| {
+      let options = TransformOptions {
+        locale: Some(locale),
+        ..Default::default()
+      };
+      let cmd = TransformCommand::new(
+        "app.js".to_string(),
+        "let a = 1;\nlet a = 2;".to_string(),
+        Some(options),
+      );
+      cmd.execute().unwrap().diagnostics.remove(0)
+    };
+
+    let en = transform(Locale::En);
+    let fr = transform(Locale::Fr);
+
+    assert_eq!(en.code, fr.code);
+    assert_ne!(en.formatted, fr.formatted);
+  }
+
+  #[test]
+  fn test_typescript_options() {
+    let source = "import { observable } from 'mobx';\nimport { Store } from './store';\nconst enum Kind { Todo = 'todo' }\nexport class TodoStore {\n  @observable items: string[] = [];\n  kind = Kind.Todo;\n  constructor(store: Store) {}\n}";
+    let options = TransformOptions {
+      only_remove_type_imports: Some(true),
+      optimize_const_enums: Some(true),
+      experimental_decorators: Some(true),
+      emit_decorator_metadata: Some(true),
+      ..Default::default()
+    };
+    let cmd = TransformCommand::new("store.ts".to_string(), source.to_string(), Some(options));
+    let result = cmd.execute().unwrap();
+
+    assert!(result.errors.is_empty());
+    assert!(result.code.contains("import { Store } from \"./store\";"));
+    assert!(result
+      .code
+      .contains("_defineProperty(this, \"kind\", \"todo\");"));
+    assert!(!result.code.contains("Kind"));
+    assert!(result.code.contains("_decorate"));
+    assert!(result.code.contains("\"design:type\", Array"));
+  }
+}
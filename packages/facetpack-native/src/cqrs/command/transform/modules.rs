@@ -0,0 +1,578 @@
+use oxc_allocator::{Allocator, Vec as ArenaVec};
+use oxc_ast::ast::{
+  Argument, ClassType, Declaration, ExportAllDeclaration, ExportDefaultDeclaration,
+  ExportDefaultDeclarationKind, ExportNamedDeclaration, Expression, FunctionType,
+  ImportDeclaration, ImportDeclarationSpecifier, Program, Statement,
+};
+use oxc_ast_visit::{walk_mut, VisitMut};
+use oxc_semantic::Scoping;
+
+use super::template::{
+  parse_expression_template, parse_template, unique_name, PlaceholderReplacer,
+};
+use crate::domain::types::{Dependency, DependencyKind, ModuleFormat, SourceSpan};
+
+const METRO_REQUIRE: &str = "_$$_REQUIRE";
+const METRO_IMPORT_DEFAULT: &str = "_$$_IMPORT_DEFAULT";
+const METRO_IMPORT_ALL: &str = "_$$_IMPORT_ALL";
+const DEPENDENCY_MAP: &str = "_dependencyMap";
+const ASYNC_REQUIRE_MODULE: &str = "metro-runtime/src/modules/asyncRequire";
+
+/// Same semantics as `metroImportDefault` and `metroImportAll` in Metro's runtime.
+fn cjs_import_helpers(import_default: &str, import_all: &str) -> String {
+  format!(
+    "function {import_default}(m) {{ return m && m.__esModule ? m.default : m; }}\n\
+     function {import_all}(m) {{ if (m && m.__esModule) return m; var ns = {{}}; if (m != null) for (var k in m) if (Object.prototype.hasOwnProperty.call(m, k)) ns[k] = m[k]; ns.default = m; return ns; }}"
+  )
+}
+
+/// Lowers `import` and `export` statements to CommonJS, the way `metro-transform-plugins`
+/// does with `experimentalImportSupport`: imports are hoisted into `var` declarations and
+/// exports are assigned to `exports` at the end of the module.
+///
+/// Returns the names of the import helpers declared in the module.
+pub fn lower_modules<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+  scoping: &Scoping,
+  format: ModuleFormat,
+) -> Vec<String> {
+  if format == ModuleFormat::Esm {
+    return Vec::new();
+  }
+
+  let mut taken: Vec<String> = scoping.symbol_names().map(str::to_string).collect();
+  taken.extend(
+    scoping
+      .root_unresolved_references()
+      .keys()
+      .map(|name| name.to_string()),
+  );
+  let mut lowering = ModuleLowering {
+    allocator,
+    format,
+    taken,
+    module_vars: Vec::new(),
+    imports: Vec::new(),
+    exports: Vec::new(),
+    has_exports: false,
+    helpers: None,
+  };
+
+  let body = std::mem::replace(&mut program.body, ArenaVec::new_in(allocator));
+  let mut statements = Vec::with_capacity(body.len());
+  for stmt in body {
+    lowering.lower_statement(stmt, &mut statements);
+  }
+
+  let mut header = Vec::new();
+  if let Some((import_default, import_all)) = &lowering.helpers {
+    header.push(cjs_import_helpers(import_default, import_all));
+  }
+  if lowering.has_exports {
+    header.push("Object.defineProperty(exports, '__esModule', { value: true });".to_string());
+  }
+  header.append(&mut lowering.imports);
+
+  let mut lowered = lowering.parse(&header.join("\n"));
+  lowered.extend(statements);
+  lowered.extend(lowering.parse(&lowering.exports.join("\n")));
+  program.body = ArenaVec::from_iter_in(lowered, allocator);
+  lowering
+    .helpers
+    .map(|(import_default, import_all)| vec![import_default, import_all])
+    .unwrap_or_default()
+}
+
+/// Rewrites `require()`, Metro import helpers and `import()` calls to load modules from
+/// the dependency map, as Metro's `collectDependencies` does. Returns the dependency map:
+/// `_dependencyMap[i]` is the i-th dependency.
+pub fn assign_dependency_map<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+  scoping: &Scoping,
+  collected: &[Dependency],
+) -> Vec<Dependency> {
+  let mut assigner = DependencyMapAssigner {
+    allocator,
+    scoping,
+    collected,
+    dependencies: Vec::new(),
+  };
+  assigner.visit_program(program);
+  assigner.dependencies
+}
+
+struct ModuleLowering<'a> {
+  allocator: &'a Allocator,
+  format: ModuleFormat,
+  taken: Vec<String>,
+  /// The variable holding each required module, shared by its named imports.
+  module_vars: Vec<(String, String)>,
+  /// Hoisted statements loading the imported modules.
+  imports: Vec<String>,
+  /// Assignments to `exports`, appended to the module.
+  exports: Vec<String>,
+  has_exports: bool,
+  /// The names of the `cjs` import helpers, declared on first use.
+  helpers: Option<(String, String)>,
+}
+
+impl<'a> ModuleLowering<'a> {
+  fn parse(&self, source: &str) -> ArenaVec<'a, Statement<'a>> {
+    parse_template(self.allocator, source).body
+  }
+
+  fn require(&self, specifier: &str) -> String {
+    format!("require({:?})", specifier)
+  }
+
+  fn import_default(&mut self, specifier: &str) -> String {
+    match self.format {
+      ModuleFormat::MetroCjs => format!("{}({:?})", METRO_IMPORT_DEFAULT, specifier),
+      _ => format!("{}({})", self.helpers().0, self.require(specifier)),
+    }
+  }
+
+  fn import_all(&mut self, specifier: &str) -> String {
+    match self.format {
+      ModuleFormat::MetroCjs => format!("{}({:?})", METRO_IMPORT_ALL, specifier),
+      _ => format!("{}({})", self.helpers().1, self.require(specifier)),
+    }
+  }
+
+  fn helpers(&mut self) -> (String, String) {
+    if self.helpers.is_none() {
+      let import_default = unique_name("_importDefault", &self.taken);
+      self.taken.push(import_default.clone());
+      let import_all = unique_name("_importAll", &self.taken);
+      self.taken.push(import_all.clone());
+      self.helpers = Some((import_default, import_all));
+    }
+    self.helpers.clone().unwrap_or_default()
+  }
+
+  /// The variable holding the module, declared on first use.
+  fn module_var(&mut self, specifier: &str) -> String {
+    if let Some((_, name)) = self.module_vars.iter().find(|(s, _)| s == specifier) {
+      return name.clone();
+    }
+    let name = unique_name(&module_var_base(specifier), &self.taken);
+    self.taken.push(name.clone());
+    self
+      .imports
+      .push(format!("var {} = {};", name, self.require(specifier)));
+    self.module_vars.push((specifier.to_string(), name.clone()));
+    name
+  }
+
+  fn export(&mut self, exported: &str, value: &str) {
+    self.has_exports = true;
+    self
+      .exports
+      .push(format!("{} = {};", member("exports", exported), value));
+  }
+
+  fn lower_statement(&mut self, stmt: Statement<'a>, out: &mut Vec<Statement<'a>>) {
+    match stmt {
+      Statement::ImportDeclaration(decl) => self.lower_import(&decl),
+      Statement::ExportNamedDeclaration(decl) => self.lower_export_named(decl.unbox(), out),
+      Statement::ExportDefaultDeclaration(decl) => self.lower_export_default(decl.unbox(), out),
+      Statement::ExportAllDeclaration(decl) => self.lower_export_all(&decl),
+      stmt => out.push(stmt),
+    }
+  }
+
+  fn lower_import(&mut self, decl: &ImportDeclaration<'a>) {
+    let specifier = decl.source.value.as_str();
+    let Some(specifiers) = decl.specifiers.as_ref().filter(|s| !s.is_empty()) else {
+      let require = self.require(specifier);
+      self.imports.push(format!("{};", require));
+      return;
+    };
+
+    for import in specifiers {
+      let (local, value) = match import {
+        ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => {
+          (&s.local.name, self.import_default(specifier))
+        }
+        ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => {
+          (&s.local.name, self.import_all(specifier))
+        }
+        ImportDeclarationSpecifier::ImportSpecifier(s) if s.imported.name() == "default" => {
+          (&s.local.name, self.import_default(specifier))
+        }
+        ImportDeclarationSpecifier::ImportSpecifier(s) => {
+          let module = self.module_var(specifier);
+          (&s.local.name, member(&module, &s.imported.name()))
+        }
+      };
+      self.imports.push(format!("var {} = {};", local, value));
+    }
+  }
+
+  fn lower_export_named(&mut self, decl: ExportNamedDeclaration<'a>, out: &mut Vec<Statement<'a>>) {
+    if let Some(declaration) = decl.declaration {
+      for name in declared_names(&declaration) {
+        self.export(&name, &name);
+      }
+      out.push(Statement::from(declaration));
+      return;
+    }
+
+    for export in &decl.specifiers {
+      let exported = export.exported.name();
+      let value = match &decl.source {
+        None => export.local.name().to_string(),
+        Some(source) if export.local.name() == "default" => self.import_default(&source.value),
+        Some(source) => {
+          let module = self.module_var(&source.value);
+          member(&module, &export.local.name())
+        }
+      };
+      self.export(&exported, &value);
+    }
+  }
+
+  fn lower_export_default(
+    &mut self,
+    decl: ExportDefaultDeclaration<'a>,
+    out: &mut Vec<Statement<'a>>,
+  ) {
+    let value = match decl.declaration {
+      ExportDefaultDeclarationKind::FunctionDeclaration(func) => match &func.id {
+        Some(id) => {
+          self.export("default", &id.name);
+          out.push(Statement::FunctionDeclaration(func));
+          return;
+        }
+        None => {
+          let mut func = func;
+          func.r#type = FunctionType::FunctionExpression;
+          Expression::FunctionExpression(func)
+        }
+      },
+      ExportDefaultDeclarationKind::ClassDeclaration(class) => match &class.id {
+        Some(id) => {
+          self.export("default", &id.name);
+          out.push(Statement::ClassDeclaration(class));
+          return;
+        }
+        None => {
+          let mut class = class;
+          class.r#type = ClassType::ClassExpression;
+          Expression::ClassExpression(class)
+        }
+      },
+      ExportDefaultDeclarationKind::TSInterfaceDeclaration(_) => return,
+      kind => kind.into_expression(),
+    };
+
+    self.has_exports = true;
+    let mut program = parse_template(
+      self.allocator,
+      "exports.default = __facetpack_placeholder__;",
+    );
+    PlaceholderReplacer {
+      replacement: Some(value),
+    }
+    .visit_program(&mut program);
+    out.extend(program.body);
+  }
+
+  fn lower_export_all(&mut self, decl: &ExportAllDeclaration<'a>) {
+    let specifier = decl.source.value.as_str();
+    match &decl.exported {
+      Some(exported) => {
+        let value = self.import_all(specifier);
+        self.export(&exported.name(), &value);
+      }
+      None => {
+        let module = self.module_var(specifier);
+        let key = unique_name("_key", &self.taken);
+        self.taken.push(key.clone());
+        self.imports.push(format!(
+          "for (var {key} in {module}) {{ exports[{key}] = {module}[{key}]; }}"
+        ));
+        self.has_exports = true;
+      }
+    }
+  }
+}
+
+/// The names a declaration binds, which `export` makes exports of.
+fn declared_names(declaration: &Declaration) -> Vec<String> {
+  match declaration {
+    Declaration::VariableDeclaration(decl) => decl
+      .declarations
+      .iter()
+      .flat_map(|d| d.id.get_binding_identifiers())
+      .map(|ident| ident.name.to_string())
+      .collect(),
+    Declaration::FunctionDeclaration(func) => {
+      func.id.iter().map(|id| id.name.to_string()).collect()
+    }
+    Declaration::ClassDeclaration(class) => class.id.iter().map(|id| id.name.to_string()).collect(),
+    _ => Vec::new(),
+  }
+}
+
+/// `_reactNative` for `react-native`, like Babel's generated module names.
+fn module_var_base(specifier: &str) -> String {
+  let segment = specifier
+    .rsplit('/')
+    .find(|s| !s.is_empty() && *s != "." && *s != "..")
+    .unwrap_or("module");
+  let mut name = String::from("_");
+  let mut upper = false;
+  for c in segment.chars() {
+    if c.is_ascii_alphanumeric() {
+      name.push(if upper { c.to_ascii_uppercase() } else { c });
+      upper = false;
+    } else {
+      upper = name.len() > 1;
+    }
+  }
+  name
+}
+
+fn is_identifier_name(name: &str) -> bool {
+  let mut chars = name.chars();
+  chars
+    .next()
+    .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+    && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+fn member(object: &str, property: &str) -> String {
+  if is_identifier_name(property) {
+    format!("{}.{}", object, property)
+  } else {
+    format!("{}[{:?}]", object, property)
+  }
+}
+
+struct DependencyMapAssigner<'a, 's> {
+  allocator: &'a Allocator,
+  scoping: &'s Scoping,
+  collected: &'s [Dependency],
+  dependencies: Vec<Dependency>,
+}
+
+impl DependencyMapAssigner<'_, '_> {
+  /// The index of a module in the dependency map, keeping what was collected from the
+  /// source about it.
+  fn index(&mut self, specifier: &str, is_async: bool) -> usize {
+    if let Some(index) = self
+      .dependencies
+      .iter()
+      .position(|d| d.specifier == specifier && d.is_async == is_async)
+    {
+      return index;
+    }
+
+    let dependency = self
+      .collected
+      .iter()
+      .find(|d| d.specifier == specifier && d.is_async == is_async && !d.is_type_only)
+      .cloned()
+      .unwrap_or_else(|| Dependency {
+        specifier: specifier.to_string(),
+        kind: if is_async {
+          DependencyKind::DynamicImport
+        } else {
+          DependencyKind::Require
+        },
+        is_type_only: false,
+        is_async,
+        is_optional: false,
+        span: SourceSpan { start: 0, end: 0 },
+      });
+    self.dependencies.push(dependency);
+    self.dependencies.len() - 1
+  }
+
+  fn is_global(&self, expr: &Expression, name: &str) -> bool {
+    matches!(expr, Expression::Identifier(ident) if ident.name == name
+      && ident
+        .reference_id
+        .get()
+        .is_none_or(|id| self.scoping.get_reference(id).symbol_id().is_none()))
+  }
+}
+
+impl<'a> VisitMut<'a> for DependencyMapAssigner<'a, '_> {
+  fn visit_expression(&mut self, expr: &mut Expression<'a>) {
+    let source = match expr {
+      Expression::CallExpression(call) => {
+        let loader = [
+          ("require", METRO_REQUIRE),
+          (METRO_IMPORT_DEFAULT, METRO_IMPORT_DEFAULT),
+          (METRO_IMPORT_ALL, METRO_IMPORT_ALL),
+        ]
+        .into_iter()
+        .find(|(name, _)| self.is_global(&call.callee, name));
+        let specifier = call
+          .arguments
+          .first()
+          .and_then(Argument::as_expression)
+          .and_then(static_specifier)
+          .filter(|_| call.arguments.len() == 1);
+        match (loader, specifier) {
+          (Some((_, loader)), Some(specifier)) => {
+            let index = self.index(&specifier, false);
+            Some(format!(
+              "{}({}[{}], {:?})",
+              loader, DEPENDENCY_MAP, index, specifier
+            ))
+          }
+          _ => None,
+        }
+      }
+      Expression::ImportExpression(import) if import.options.is_none() => {
+        static_specifier(&import.source).map(|specifier| {
+          let index = self.index(&specifier, true);
+          let async_require = self.index(ASYNC_REQUIRE_MODULE, false);
+          format!(
+            "{METRO_REQUIRE}({DEPENDENCY_MAP}[{async_require}], {:?})({DEPENDENCY_MAP}[{index}], {DEPENDENCY_MAP}.paths)",
+            ASYNC_REQUIRE_MODULE
+          )
+        })
+      }
+      _ => None,
+    };
+
+    match source {
+      Some(source) => *expr = parse_expression_template(self.allocator, &source, None),
+      None => walk_mut::walk_expression(self, expr),
+    }
+  }
+}
+
+fn static_specifier(expr: &Expression) -> Option<String> {
+  match expr {
+    Expression::StringLiteral(lit) => Some(lit.value.to_string()),
+    Expression::TemplateLiteral(lit) if lit.expressions.is_empty() => lit
+      .quasis
+      .first()
+      .and_then(|q| q.value.cooked.as_ref())
+      .map(|s| s.to_string()),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_codegen::Codegen;
+  use oxc_parser::Parser;
+  use oxc_semantic::SemanticBuilder;
+  use oxc_span::SourceType;
+
+  fn lower(source: &str, format: ModuleFormat) -> (String, Vec<Dependency>) {
+    let allocator = Allocator::default();
+    let mut program = Parser::new(&allocator, source, SourceType::mjs())
+      .parse()
+      .program;
+    let scoping = SemanticBuilder::new()
+      .build(&program)
+      .semantic
+      .into_scoping();
+    lower_modules(&allocator, &mut program, &scoping, format);
+    let dependencies = if format == ModuleFormat::MetroCjs {
+      assign_dependency_map(&allocator, &mut program, &scoping, &[])
+    } else {
+      Vec::new()
+    };
+    (Codegen::new().build(&program).code, dependencies)
+  }
+
+  #[test]
+  fn test_lower_metro_imports() {
+    let (code, dependencies) = lower(
+      "import React, { useState as useLocal, useEffect } from 'react';\nimport * as RN from 'react-native';\nimport './polyfill';\nconst lazy = import('./lazy');\nuseLocal(RN);",
+      ModuleFormat::MetroCjs,
+    );
+    let specifiers: Vec<_> = dependencies.iter().map(|d| d.specifier.as_str()).collect();
+
+    assert!(code.contains("var React = _$$_IMPORT_DEFAULT(_dependencyMap[0], \"react\");"));
+    assert!(code.contains("var _react = _$$_REQUIRE(_dependencyMap[0], \"react\");"));
+    assert!(code.contains("var useLocal = _react.useState;"));
+    assert!(code.contains("var RN = _$$_IMPORT_ALL(_dependencyMap[1], \"react-native\");"));
+    assert!(code.contains("_$$_REQUIRE(_dependencyMap[2], \"./polyfill\");"));
+    assert!(code.contains(
+      "_$$_REQUIRE(_dependencyMap[4], \"metro-runtime/src/modules/asyncRequire\")(_dependencyMap[3], _dependencyMap.paths)"
+    ));
+    assert!(!code.contains("__esModule"));
+    assert_eq!(
+      specifiers,
+      [
+        "react",
+        "react-native",
+        "./polyfill",
+        "./lazy",
+        "metro-runtime/src/modules/asyncRequire"
+      ]
+    );
+    assert!(dependencies[3].is_async);
+  }
+
+  #[test]
+  fn test_lower_metro_exports() {
+    let (code, dependencies) = lower(
+      "export const a = 1, { b } = o;\nexport function f() {}\nexport default function () {}\nexport { a as 'kebab-case' };\nexport { c, default as d } from './c';\nexport * from './all';\nexport * as ns from './ns';",
+      ModuleFormat::MetroCjs,
+    );
+
+    assert!(code.starts_with("Object.defineProperty(exports, \"__esModule\", { value: true });"));
+    assert!(code.contains("const a = 1, { b } = o;"));
+    assert!(code.contains("exports.default = function() {};"));
+    assert!(code.contains("for (var _key in _all) {\n\texports[_key] = _all[_key];\n}"));
+    assert!(code.ends_with(
+      "exports.a = a;\nexports.b = b;\nexports.f = f;\nexports[\"kebab-case\"] = a;\nexports.c = _c.c;\nexports.d = _$$_IMPORT_DEFAULT(_dependencyMap[0], \"./c\");\nexports.ns = _$$_IMPORT_ALL(_dependencyMap[2], \"./ns\");\n"
+    ));
+    assert_eq!(dependencies.len(), 3);
+  }
+
+  #[test]
+  fn test_lower_cjs() {
+    let (code, _) = lower(
+      "import React from 'react';\nimport { View } from 'react-native';\nexport default React;",
+      ModuleFormat::Cjs,
+    );
+
+    assert!(code.contains("function _importDefault(m)"));
+    assert!(code.contains("var React = _importDefault(require(\"react\"));"));
+    assert!(code.contains("var View = _reactNative.View;"));
+    assert!(code.contains("exports.default = React;"));
+    assert!(!code.contains("_dependencyMap"));
+  }
+
+  #[test]
+  fn test_lower_cjs_helpers_avoid_user_bindings() {
+    let (code, _) = lower(
+      "import a from 'a';\nimport * as b from 'b';\nconst _importDefault = 1;\nfunction _importAll() {}",
+      ModuleFormat::Cjs,
+    );
+
+    assert!(code.contains("function _importDefault2(m)"));
+    assert!(code.contains("function _importAll2(m)"));
+    assert!(code.contains("var a = _importDefault2(require(\"a\"));"));
+    assert!(code.contains("var b = _importAll2(require(\"b\"));"));
+    assert!(code.contains("const _importDefault = 1;"));
+  }
+
+  #[test]
+  fn test_lower_keeps_esm_and_shadowed_require() {
+    let source = "import a from 'a';\nexport { a };";
+    let (code, _) = lower(source, ModuleFormat::Esm);
+    assert!(code.contains("import a from \"a\";"));
+
+    let (code, dependencies) = lower(
+      "function load(require) { return require('local'); }",
+      ModuleFormat::MetroCjs,
+    );
+    assert!(code.contains("require(\"local\")"));
+    assert!(dependencies.is_empty());
+  }
+}
@@ -0,0 +1,247 @@
+use std::collections::HashSet;
+
+use oxc_allocator::{Allocator, TakeIn};
+use oxc_ast::ast::{
+  Argument, BindingPatternKind, Expression, ImportDeclarationSpecifier, ObjectExpression,
+  ObjectPropertyKind, Program, PropertyKey, PropertyKind, Statement,
+};
+use oxc_ast_visit::{walk_mut, VisitMut};
+use oxc_semantic::{Scoping, SymbolId};
+
+use super::template::parse_expression_template;
+
+const REACT_NATIVE: &str = "react-native";
+const PLATFORM: &str = "Platform";
+
+/// Inlines `Platform.OS` and reduces `Platform.select()` over a static object to the
+/// value for `platform`, like Metro's inline plugin.
+///
+/// `Platform` is recognized when it is imported or required from `react-native`, directly
+/// or through the module (`ReactNative.Platform`), or when it is a global.
+pub fn inline_platform<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+  scoping: &Scoping,
+  platform: &str,
+) {
+  let mut inliner = PlatformInliner {
+    allocator,
+    scoping,
+    platform,
+    platform_symbols: HashSet::new(),
+    module_symbols: HashSet::new(),
+  };
+  inliner.collect_bindings(program);
+  inliner.visit_program(program);
+}
+
+struct PlatformInliner<'a, 's> {
+  allocator: &'a Allocator,
+  scoping: &'s Scoping,
+  platform: &'s str,
+  /// Bindings of `Platform` from `react-native`.
+  platform_symbols: HashSet<SymbolId>,
+  /// Bindings of the `react-native` module itself.
+  module_symbols: HashSet<SymbolId>,
+}
+
+impl<'a> PlatformInliner<'a, '_> {
+  fn collect_bindings(&mut self, program: &Program<'a>) {
+    for stmt in &program.body {
+      match stmt {
+        Statement::ImportDeclaration(decl) if decl.source.value == REACT_NATIVE => {
+          for specifier in decl.specifiers.iter().flatten() {
+            let symbols = match specifier {
+              ImportDeclarationSpecifier::ImportSpecifier(s) if s.imported.name() == PLATFORM => {
+                &mut self.platform_symbols
+              }
+              ImportDeclarationSpecifier::ImportSpecifier(_) => continue,
+              _ => &mut self.module_symbols,
+            };
+            symbols.extend(specifier.local().symbol_id.get());
+          }
+        }
+        Statement::VariableDeclaration(decl) => {
+          for declarator in &decl.declarations {
+            let Some(init) = &declarator.init else {
+              continue;
+            };
+            match (&declarator.id.kind, init) {
+              (
+                BindingPatternKind::BindingIdentifier(id),
+                Expression::StaticMemberExpression(m),
+              ) if m.property.name == PLATFORM && is_require_react_native(&m.object) => {
+                self.platform_symbols.extend(id.symbol_id.get());
+              }
+              (BindingPatternKind::BindingIdentifier(id), init)
+                if is_require_react_native(init) =>
+              {
+                self.module_symbols.extend(id.symbol_id.get());
+              }
+              (BindingPatternKind::ObjectPattern(pattern), init)
+                if is_require_react_native(init) =>
+              {
+                for property in &pattern.properties {
+                  if property.key.is_specific_static_name(PLATFORM) {
+                    if let BindingPatternKind::BindingIdentifier(id) = &property.value.kind {
+                      self.platform_symbols.extend(id.symbol_id.get());
+                    }
+                  }
+                }
+              }
+              _ => {}
+            }
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  fn is_platform(&self, expr: &Expression) -> bool {
+    match expr {
+      Expression::Identifier(ident) => {
+        match ident
+          .reference_id
+          .get()
+          .and_then(|id| self.scoping.get_reference(id).symbol_id())
+        {
+          Some(symbol_id) => self.platform_symbols.contains(&symbol_id),
+          None => ident.name == PLATFORM,
+        }
+      }
+      Expression::StaticMemberExpression(member) if member.property.name == PLATFORM => {
+        matches!(&member.object, Expression::Identifier(ident) if ident
+          .reference_id
+          .get()
+          .and_then(|id| self.scoping.get_reference(id).symbol_id())
+          .is_some_and(|symbol_id| self.module_symbols.contains(&symbol_id)))
+      }
+      _ => false,
+    }
+  }
+
+  /// The value `Platform.select()` returns for the platform, or `None` when the object is
+  /// not static.
+  fn select(&self, object: &mut ObjectExpression<'a>) -> Option<Expression<'a>> {
+    let is_static = object.properties.iter().all(|property| match property {
+      ObjectPropertyKind::ObjectProperty(p) => {
+        !p.computed
+          && p.kind == PropertyKind::Init
+          && matches!(
+            p.key,
+            PropertyKey::StaticIdentifier(_) | PropertyKey::StringLiteral(_)
+          )
+      }
+      ObjectPropertyKind::SpreadProperty(_) => false,
+    });
+    if !is_static {
+      return None;
+    }
+
+    let mut keys = vec![self.platform];
+    if self.platform != "web" {
+      keys.push("native");
+    }
+    keys.push("default");
+
+    let value = keys.into_iter().find_map(|key| {
+      object
+        .properties
+        .iter_mut()
+        .rev()
+        .find_map(|property| match property {
+          ObjectPropertyKind::ObjectProperty(p) if p.key.is_specific_static_name(key) => {
+            Some(p.value.take_in(self.allocator))
+          }
+          _ => None,
+        })
+    });
+    Some(value.unwrap_or_else(|| parse_expression_template(self.allocator, "undefined", None)))
+  }
+}
+
+impl<'a> VisitMut<'a> for PlatformInliner<'a, '_> {
+  fn visit_expression(&mut self, expr: &mut Expression<'a>) {
+    let replacement = match expr {
+      Expression::StaticMemberExpression(member)
+        if member.property.name == "OS" && self.is_platform(&member.object) =>
+      {
+        Some(parse_expression_template(
+          self.allocator,
+          &format!("{:?}", self.platform),
+          None,
+        ))
+      }
+      Expression::CallExpression(call) if call.arguments.len() == 1 => {
+        let is_select = matches!(&call.callee, Expression::StaticMemberExpression(member)
+          if member.property.name == "select" && self.is_platform(&member.object));
+        match call.arguments.first_mut() {
+          Some(Argument::ObjectExpression(object)) if is_select => self.select(object),
+          _ => None,
+        }
+      }
+      _ => None,
+    };
+
+    if let Some(replacement) = replacement {
+      *expr = replacement;
+    }
+    walk_mut::walk_expression(self, expr);
+  }
+}
+
+fn is_require_react_native(expr: &Expression) -> bool {
+  let Expression::CallExpression(call) = expr else {
+    return false;
+  };
+  matches!(&call.callee, Expression::Identifier(ident) if ident.name == "require")
+    && matches!(
+      call.arguments.first(),
+      Some(Argument::StringLiteral(lit)) if lit.value == REACT_NATIVE
+    )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_codegen::Codegen;
+  use oxc_parser::Parser;
+  use oxc_semantic::SemanticBuilder;
+  use oxc_span::SourceType;
+
+  fn inline(source: &str, platform: &str) -> String {
+    let allocator = Allocator::default();
+    let mut program = Parser::new(&allocator, source, SourceType::mjs())
+      .parse()
+      .program;
+    let scoping = SemanticBuilder::new()
+      .build(&program)
+      .semantic
+      .into_scoping();
+    inline_platform(&allocator, &mut program, &scoping, platform);
+    Codegen::new().build(&program).code
+  }
+
+  #[test]
+  fn test_inline_platform_os() {
+    let code = inline(
+      "import { Platform as P } from 'react-native';\nimport * as RN from 'react-native';\nconst { Platform } = require('react-native');\nconst a = P.OS, b = RN.Platform.OS, c = Platform.OS;\nfunction f(Platform) { return Platform.OS; }",
+      "android",
+    );
+
+    assert!(code.contains("const a = \"android\", b = \"android\", c = \"android\";"));
+    assert!(code.contains("return Platform.OS;"));
+  }
+
+  #[test]
+  fn test_platform_select() {
+    let source = "const s = Platform.select({ ios: 'I', android: 'A', native: 'N', default: 'D' });\nconst n = Platform.select({ web: 'W', native: () => 1 });\nconst d = Platform.select({ ios: 'I', default: 'D' });\nconst none = Platform.select({ ios: 'I' });\nconst dynamic = Platform.select({ [key]: 1, android: 2 });";
+
+    assert_eq!(
+      inline(source, "android"),
+      "const s = \"A\";\nconst n = () => 1;\nconst d = \"D\";\nconst none = undefined;\nconst dynamic = Platform.select({\n\t[key]: 1,\n\tandroid: 2\n});\n"
+    );
+    assert!(inline(source, "web").contains("const n = \"W\";\nconst d = \"D\";"));
+  }
+}
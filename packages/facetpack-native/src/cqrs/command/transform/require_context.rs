@@ -0,0 +1,661 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use fancy_regex::Regex;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{Argument, BinaryOperator, CallExpression, Expression, Program, RegExpFlags};
+use oxc_ast_visit::{walk_mut, VisitMut};
+
+use super::template::parse_expression_template;
+use crate::cqrs::query::{ResolveBatchQuery, ResolverOptions};
+use crate::domain::types::{Dependency, DependencyKind, SourceSpan};
+
+const DEFAULT_FILTER: &str = r"^\./.*$";
+
+/// Metro's default `resolver.sourceExts`.
+pub const DEFAULT_SOURCE_EXTS: &[&str] = &["js", "jsx", "json", "ts", "tsx"];
+
+/// How the modules of a context are loaded, as in Metro.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContextMode {
+  Sync,
+  Eager,
+  Lazy,
+  LazyOnce,
+}
+
+impl ContextMode {
+  fn parse(mode: &str) -> Result<Self, String> {
+    match mode {
+      "sync" => Ok(Self::Sync),
+      "eager" => Ok(Self::Eager),
+      "lazy" => Ok(Self::Lazy),
+      "lazy-once" => Ok(Self::LazyOnce),
+      "weak" => Err(
+        "mode \"weak\" is not supported by Metro context modules, use \"sync\", \"eager\", \"lazy\" or \"lazy-once\""
+          .to_string(),
+      ),
+      _ => Err(format!(
+        "unknown mode \"{}\", expected \"sync\", \"eager\", \"lazy\" or \"lazy-once\"",
+        mode
+      )),
+    }
+  }
+
+  fn dependency_kind(self) -> DependencyKind {
+    match self {
+      Self::Sync | Self::Eager => DependencyKind::Require,
+      Self::Lazy | Self::LazyOnce => DependencyKind::DynamicImport,
+    }
+  }
+}
+
+/// The evaluated arguments of `require.context(directory, recursive, filter, mode)`.
+#[derive(Debug)]
+struct ContextParams {
+  directory: String,
+  recursive: bool,
+  filter: Regex,
+  mode: ContextMode,
+}
+
+/// Where the files of a context are looked up.
+pub struct ContextOptions<'e> {
+  pub source_exts: &'e [String],
+  /// Files with this platform suffix (`Button.ios.tsx`), or `native`, replace the file
+  /// without one, as when Metro resolves them.
+  pub platform: Option<&'e str>,
+  /// The directory of non-relative context directories that are not aliased.
+  pub project_root: Option<&'e str>,
+  pub alias: Option<&'e HashMap<String, String>>,
+}
+
+/// Replaces every `require.context()` call with the context module Metro would generate
+/// for it, and returns the matched files as dependencies of the file.
+///
+/// The files of the directory are resolved with the resolver, so only files with one of
+/// `source_exts` are matched and platform-specific files win. Each context directory is
+/// returned as a `Context` dependency too, as the generated module is stale once its files
+/// change.
+pub fn lower_require_context<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+  filename: &str,
+  options: &ContextOptions,
+) -> Result<Vec<Dependency>, String> {
+  let mut lowering = RequireContextLowering {
+    allocator,
+    base_dir: Path::new(filename)
+      .parent()
+      .map(Path::to_path_buf)
+      .unwrap_or_default(),
+    options,
+    dependencies: Vec::new(),
+    error: None,
+  };
+  lowering.visit_program(program);
+
+  match lowering.error {
+    Some(error) => Err(error),
+    None => Ok(lowering.dependencies),
+  }
+}
+
+struct RequireContextLowering<'a, 'e> {
+  allocator: &'a Allocator,
+  base_dir: PathBuf,
+  options: &'e ContextOptions<'e>,
+  dependencies: Vec<Dependency>,
+  error: Option<String>,
+}
+
+impl<'a> RequireContextLowering<'a, '_> {
+  fn lower(&mut self, call: &CallExpression<'a>) -> Result<Expression<'a>, String> {
+    let params = evaluate_params(call)?;
+    let root = self.context_root(&params.directory)?;
+
+    let mut files = Vec::new();
+    match collect_files(
+      &root,
+      params.recursive,
+      self.options.source_exts,
+      &mut files,
+    ) {
+      Ok(()) => {}
+      Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+      Err(e) => return Err(format!("cannot read '{}': {}", root.display(), e)),
+    }
+
+    // The resolver follows symlinks, so resolved files may be under the real root.
+    let real_root = root.canonicalize().unwrap_or_else(|_| root.clone());
+    let mut entries = Vec::new();
+    for file in self.resolve_files(&root, &files) {
+      let Ok(relative) = file
+        .strip_prefix(&root)
+        .or_else(|_| file.strip_prefix(&real_root))
+      else {
+        continue;
+      };
+      let relative = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+      let key = format!("./{}", relative);
+      if !params.filter.is_match(&key).unwrap_or(false) {
+        continue;
+      }
+
+      // Aliased and project directories are not relative to the file, so their files are
+      // loaded by absolute path.
+      let specifier = if params.directory.starts_with('.') {
+        format!("{}/{}", params.directory.trim_end_matches('/'), relative)
+      } else {
+        file.to_string_lossy().into_owned()
+      };
+      entries.push((key, specifier));
+    }
+    entries.sort();
+    entries.dedup();
+
+    let source = context_module_source(&params, &entries);
+    let span = SourceSpan {
+      start: call.span.start,
+      end: call.span.end,
+    };
+    self.dependencies.push(Dependency {
+      specifier: params.directory.clone(),
+      kind: DependencyKind::Context,
+      is_type_only: false,
+      is_async: false,
+      is_optional: false,
+      span,
+    });
+    self
+      .dependencies
+      .extend(entries.into_iter().map(|(_, specifier)| Dependency {
+        specifier,
+        kind: params.mode.dependency_kind(),
+        is_type_only: false,
+        is_async: params.mode.dependency_kind() == DependencyKind::DynamicImport,
+        is_optional: false,
+        span,
+      }));
+
+    Ok(parse_expression_template(self.allocator, &source, None))
+  }
+
+  /// The directory a `require.context()` call lists, after applying the aliases.
+  fn context_root(&self, directory: &str) -> Result<PathBuf, String> {
+    let aliased = self
+      .options
+      .alias
+      .and_then(|alias| apply_alias(directory, alias));
+    let directory = aliased.as_deref().unwrap_or(directory);
+    let project_root = self.options.project_root.map(Path::new);
+
+    if Path::new(directory).is_absolute() {
+      Ok(PathBuf::from(directory))
+    } else if directory.starts_with('.') {
+      // Relative aliases are relative to the project, like in the resolver configuration.
+      let base = match (aliased.is_some(), project_root) {
+        (true, Some(project_root)) => project_root,
+        _ => &self.base_dir,
+      };
+      Ok(base.join(directory))
+    } else if let Some(project_root) = project_root {
+      Ok(project_root.join(directory))
+    } else {
+      Err(format!(
+        "cannot locate '{}' without an alias or a project root",
+        directory
+      ))
+    }
+  }
+
+  /// Resolves the modules of `files` the way `require()` would, so a platform-specific file
+  /// replaces the generic one and the resolver's extension order applies.
+  fn resolve_files(&self, root: &Path, files: &[PathBuf]) -> Vec<PathBuf> {
+    let mut modules: Vec<String> = files
+      .iter()
+      .map(|file| self.module_path(file).to_string_lossy().into_owned())
+      .collect();
+    modules.sort();
+    modules.dedup();
+
+    let options = ResolverOptions {
+      extensions: Some(self.extensions()),
+      alias: self.options.alias.cloned(),
+      ..Default::default()
+    };
+    ResolveBatchQuery::new(root.to_string_lossy().into_owned(), modules, Some(options))
+      .execute()
+      .into_iter()
+      .filter_map(|result| result.path.map(PathBuf::from))
+      .collect()
+  }
+
+  /// `file` without its extension and its platform suffix.
+  fn module_path(&self, file: &Path) -> PathBuf {
+    let name = file
+      .file_name()
+      .map(|name| name.to_string_lossy().into_owned())
+      .unwrap_or_default();
+    let mut base = self
+      .options
+      .source_exts
+      .iter()
+      .filter_map(|ext| name.strip_suffix(ext.as_str())?.strip_suffix('.'))
+      .min_by_key(|base| base.len())
+      .unwrap_or(&name);
+    if let Some(platform) = self.options.platform {
+      for suffix in [platform, "native"] {
+        if let Some(stripped) = base.strip_suffix(suffix).and_then(|b| b.strip_suffix('.')) {
+          base = stripped;
+          break;
+        }
+      }
+    }
+    file.with_file_name(base)
+  }
+
+  /// Metro's order: for each source extension, the platform file, then the native file,
+  /// then the generic one.
+  fn extensions(&self) -> Vec<String> {
+    let mut extensions = Vec::new();
+    for ext in self.options.source_exts {
+      if let Some(platform) = self.options.platform {
+        extensions.push(format!(".{}.{}", platform, ext));
+        extensions.push(format!(".native.{}", ext));
+      }
+      extensions.push(format!(".{}", ext));
+    }
+    extensions
+  }
+}
+
+/// Applies the first alias matching `specifier` exactly or as a `key/` prefix.
+fn apply_alias(specifier: &str, alias: &HashMap<String, String>) -> Option<String> {
+  let mut keys: Vec<_> = alias.keys().collect();
+  // The longest key is the most specific one.
+  keys.sort_by_key(|key| std::cmp::Reverse(key.len()));
+  keys.into_iter().find_map(|key| {
+    let rest = specifier.strip_prefix(key.as_str())?;
+    if rest.is_empty() || rest.starts_with('/') {
+      Some(format!("{}{}", alias[key], rest))
+    } else {
+      None
+    }
+  })
+}
+
+impl<'a> VisitMut<'a> for RequireContextLowering<'a, '_> {
+  fn visit_expression(&mut self, expr: &mut Expression<'a>) {
+    walk_mut::walk_expression(self, expr);
+
+    let Expression::CallExpression(call) = expr else {
+      return;
+    };
+    if !is_require_context(&call.callee) || self.error.is_some() {
+      return;
+    }
+
+    match self.lower(call) {
+      Ok(context) => *expr = context,
+      Err(error) => self.error = Some(format!("require.context: {}", error)),
+    }
+  }
+}
+
+fn is_require_context(callee: &Expression) -> bool {
+  matches!(
+    callee,
+    Expression::StaticMemberExpression(member)
+      if member.property.name == "context"
+        && matches!(&member.object, Expression::Identifier(ident) if ident.name == "require")
+  )
+}
+
+fn evaluate_params(call: &CallExpression) -> Result<ContextParams, String> {
+  let argument = |index: usize| {
+    call
+      .arguments
+      .get(index)
+      .and_then(Argument::as_expression)
+      .filter(|expr| !matches!(expr, Expression::Identifier(ident) if ident.name == "undefined"))
+  };
+
+  let directory = argument(0)
+    .and_then(static_string)
+    .ok_or("the directory must be a static string")?;
+
+  let recursive = match argument(1) {
+    None => true,
+    Some(Expression::BooleanLiteral(lit)) => lit.value,
+    Some(_) => return Err("the recursive flag must be a boolean literal".to_string()),
+  };
+
+  let filter = match argument(2) {
+    None => Regex::new(DEFAULT_FILTER).map_err(|e| e.to_string())?,
+    Some(Expression::RegExpLiteral(lit)) => {
+      let mut pattern = String::new();
+      for (flag, inline) in [
+        (RegExpFlags::I, "(?i)"),
+        (RegExpFlags::M, "(?m)"),
+        (RegExpFlags::S, "(?s)"),
+      ] {
+        if lit.regex.flags.contains(flag) {
+          pattern.push_str(inline);
+        }
+      }
+      pattern.push_str(&lit.regex.pattern.text);
+      Regex::new(&pattern)
+        .map_err(|e| format!("unsupported filter /{}/: {}", lit.regex.pattern.text, e))?
+    }
+    Some(_) => return Err("the filter must be a regular expression literal".to_string()),
+  };
+
+  let mode = match argument(3) {
+    None => ContextMode::Sync,
+    Some(expr) => {
+      ContextMode::parse(&static_string(expr).ok_or("the mode must be a static string")?)?
+    }
+  };
+
+  Ok(ContextParams {
+    directory,
+    recursive,
+    filter,
+    mode,
+  })
+}
+
+/// Evaluates string literals, quasi-only templates, `process.env` variables of the bundler
+/// process and concatenations of those.
+fn static_string(expr: &Expression) -> Option<String> {
+  match expr {
+    Expression::StringLiteral(lit) => Some(lit.value.to_string()),
+    Expression::TemplateLiteral(lit) if lit.expressions.is_empty() => lit
+      .quasis
+      .first()
+      .and_then(|q| q.value.cooked.as_ref())
+      .map(|s| s.to_string()),
+    Expression::StaticMemberExpression(member) => match &member.object {
+      Expression::StaticMemberExpression(env)
+        if env.property.name == "env"
+          && matches!(&env.object, Expression::Identifier(ident) if ident.name == "process") =>
+      {
+        std::env::var(member.property.name.as_str()).ok()
+      }
+      _ => None,
+    },
+    Expression::BinaryExpression(binary) if binary.operator == BinaryOperator::Addition => {
+      Some(static_string(&binary.left)? + &static_string(&binary.right)?)
+    }
+    Expression::ParenthesizedExpression(paren) => static_string(&paren.expression),
+    _ => None,
+  }
+}
+
+/// Lists the files of `dir` with one of `source_exts`, like Metro's file map does.
+fn collect_files(
+  dir: &Path,
+  recursive: bool,
+  source_exts: &[String],
+  files: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+  for entry in std::fs::read_dir(dir)? {
+    let entry = entry?;
+    let file_type = entry.file_type()?;
+    if file_type.is_dir() {
+      if recursive {
+        collect_files(&entry.path(), recursive, source_exts, files)?;
+      }
+    } else {
+      let path = entry.path();
+      let has_source_ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| source_exts.iter().any(|source_ext| source_ext == ext));
+      if has_source_ext {
+        files.push(path);
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Metro's context module templates, as an expression evaluating to the context function.
+fn context_module_source(params: &ContextParams, entries: &[(String, String)]) -> String {
+  const RESOLVE: &str = "function metroContextResolve(request) { throw new Error('Unimplemented Metro module context functionality'); }";
+
+  if entries.is_empty() {
+    return format!(
+      "(function () {{ function metroEmptyContext(request) {{ let e = new Error('No modules in context'); e.code = 'MODULE_NOT_FOUND'; throw e; }} \
+       metroEmptyContext.keys = () => ([]); metroEmptyContext.resolve = {RESOLVE}; \
+       metroEmptyContext.id = {:?}; return metroEmptyContext; }})()",
+      params.directory
+    );
+  }
+
+  let import = match params.mode {
+    ContextMode::Sync | ContextMode::Eager => "require",
+    ContextMode::Lazy | ContextMode::LazyOnce => "import",
+  };
+  let map = entries
+    .iter()
+    .map(|(key, specifier)| {
+      format!(
+        "{:?}: {{ enumerable: true, get() {{ return {}({:?}); }} }}",
+        key, import, specifier
+      )
+    })
+    .collect::<Vec<_>>()
+    .join(", ");
+  let body = match params.mode {
+    ContextMode::Eager => "return Promise.resolve().then(() => map[request]);",
+    _ => "return map[request];",
+  };
+
+  format!(
+    "(function () {{ const map = Object.defineProperties({{}}, {{ {map} }}); \
+     function metroContext(request) {{ {body} }} \
+     metroContext.keys = function metroContextKeys() {{ return Object.keys(map); }}; \
+     metroContext.resolve = {RESOLVE}; return metroContext; }})()"
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_codegen::Codegen;
+  use oxc_parser::Parser;
+  use oxc_span::SourceType;
+
+  /// A temporary project, removed when dropped.
+  struct Fixture(PathBuf);
+
+  impl Drop for Fixture {
+    fn drop(&mut self) {
+      let _ = std::fs::remove_dir_all(&self.0);
+    }
+  }
+
+  fn fixture(name: &str) -> Fixture {
+    let root =
+      std::env::temp_dir().join(format!("facetpack-context-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    for file in [
+      "app/index.tsx",
+      "app/(tabs)/home.tsx",
+      "app/button.tsx",
+      "app/button.ios.tsx",
+      "app/+html.tsx",
+      "app/styles.css",
+      "app/.DS_Store",
+    ] {
+      let path = root.join(file);
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(path, "").unwrap();
+    }
+    Fixture(root)
+  }
+
+  fn lower(root: &Fixture, source: &str) -> Result<(String, Vec<Dependency>), String> {
+    lower_with(root, source, None, None)
+  }
+
+  fn lower_with(
+    root: &Fixture,
+    source: &str,
+    platform: Option<&str>,
+    alias: Option<&HashMap<String, String>>,
+  ) -> Result<(String, Vec<Dependency>), String> {
+    let allocator = Allocator::default();
+    let mut program = Parser::new(&allocator, source, SourceType::mjs())
+      .parse()
+      .program;
+    let filename = root.0.join("_ctx.js");
+    let source_exts: Vec<String> = DEFAULT_SOURCE_EXTS.iter().map(|e| e.to_string()).collect();
+    let dependencies = lower_require_context(
+      &allocator,
+      &mut program,
+      filename.to_str().unwrap(),
+      &ContextOptions {
+        source_exts: &source_exts,
+        platform,
+        project_root: root.0.to_str(),
+        alias,
+      },
+    )?;
+    Ok((Codegen::new().build(&program).code, dependencies))
+  }
+
+  #[test]
+  fn test_lower_sync_context() {
+    let root = fixture("sync");
+    let (code, dependencies) = lower(
+      &root,
+      r"export const ctx = require.context('./app', true, /^(?:\.\/)(?!(?:.*\/)?\+html).*\.tsx$/);",
+    )
+    .unwrap();
+
+    let specifiers: Vec<_> = dependencies.iter().map(|d| d.specifier.as_str()).collect();
+    assert_eq!(
+      specifiers,
+      [
+        "./app",
+        "./app/(tabs)/home.tsx",
+        "./app/button.ios.tsx",
+        "./app/button.tsx",
+        "./app/index.tsx"
+      ]
+    );
+    assert_eq!(dependencies[0].kind, DependencyKind::Context);
+    assert!(dependencies[1..]
+      .iter()
+      .all(|d| d.kind == DependencyKind::Require));
+    assert!(code.contains(r#""./(tabs)/home.tsx": {"#));
+    assert!(code.contains(r#"return require("./app/index.tsx");"#));
+    assert!(code.contains("metroContext.keys = function metroContextKeys()"));
+    assert!(!code.contains("require.context"));
+  }
+
+  #[test]
+  fn test_lower_lazy_non_recursive_context() {
+    let root = fixture("lazy");
+    let (code, dependencies) = lower(
+      &root,
+      "const ctx = require.context(`./app`, false, /\\.TSX$/i, 'lazy');",
+    )
+    .unwrap();
+
+    assert_eq!(dependencies.len(), 5);
+    assert!(dependencies[1..]
+      .iter()
+      .all(|d| d.kind == DependencyKind::DynamicImport));
+    assert!(code.contains(r#"return import("./app/index.tsx");"#));
+  }
+
+  #[test]
+  fn test_lower_empty_context() {
+    let root = fixture("empty");
+    let (code, dependencies) = lower(&root, "const ctx = require.context('./missing');").unwrap();
+
+    assert_eq!(dependencies.len(), 1);
+    assert_eq!(dependencies[0].kind, DependencyKind::Context);
+    assert!(code.contains("metroEmptyContext.id = \"./missing\";"));
+  }
+
+  #[test]
+  fn test_lower_context_filters_source_exts() {
+    let root = fixture("exts");
+    let (_, dependencies) = lower(&root, "const ctx = require.context('./app');").unwrap();
+    let specifiers: Vec<_> = dependencies.iter().map(|d| d.specifier.as_str()).collect();
+
+    assert_eq!(
+      specifiers,
+      [
+        "./app",
+        "./app/(tabs)/home.tsx",
+        "./app/+html.tsx",
+        "./app/button.ios.tsx",
+        "./app/button.tsx",
+        "./app/index.tsx"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_lower_context_resolves_platform_files() {
+    let root = fixture("platform");
+    let (code, dependencies) = lower_with(
+      &root,
+      "const ctx = require.context('./app', false);",
+      Some("ios"),
+      None,
+    )
+    .unwrap();
+    let specifiers: Vec<_> = dependencies.iter().map(|d| d.specifier.as_str()).collect();
+
+    assert_eq!(
+      specifiers,
+      [
+        "./app",
+        "./app/+html.tsx",
+        "./app/button.ios.tsx",
+        "./app/index.tsx"
+      ]
+    );
+    assert!(code.contains(r#""./button.ios.tsx": {"#));
+  }
+
+  #[test]
+  fn test_lower_aliased_and_project_contexts() {
+    let root = fixture("alias");
+    let alias = HashMap::from([("@app".to_string(), "./app".to_string())]);
+    let home = root.0.join("app/(tabs)/home.tsx");
+
+    for directory in ["@app/(tabs)", "app/(tabs)"] {
+      let source = format!("const ctx = require.context('{}');", directory);
+      let (_, dependencies) = lower_with(&root, &source, None, Some(&alias)).unwrap();
+
+      assert_eq!(dependencies[0].specifier, directory);
+      assert_eq!(dependencies[1].specifier, home.to_str().unwrap());
+    }
+  }
+
+  #[test]
+  fn test_lower_rejects_dynamic_arguments() {
+    let root = fixture("dynamic");
+
+    assert!(lower(&root, "const ctx = require.context(dir);").is_err());
+    assert!(lower(
+      &root,
+      "const ctx = require.context('./app', true, /x/, 'weak');"
+    )
+    .unwrap_err()
+    .contains("\"weak\" is not supported"));
+  }
+}
@@ -0,0 +1,73 @@
+use oxc_transformer::EnvOptions;
+
+/// Resolves the `target` transform option into the syntax features oxc should lower.
+///
+/// Accepts named engine presets (`hermes`, `hermes-stable`, `jsc-ios15`), ES versions and
+/// engine lists (`es2019`, `chrome80,safari14`) or a browserslist query (`ios >= 15`).
+pub fn resolve_env_options(target: &str) -> Result<EnvOptions, String> {
+  let target = target.trim();
+  let normalized = target.to_ascii_lowercase();
+
+  match normalized.as_str() {
+    "hermes" | "hermes-stable" => hermes_env_options(),
+    preset if preset.starts_with("jsc-ios") => {
+      EnvOptions::from_target(&format!("ios{}", &preset["jsc-ios".len()..]))
+    }
+    _ => EnvOptions::from_target(&normalized).or_else(|_| {
+      EnvOptions::from_browserslist_query(target).map_err(|_| {
+        format!(
+          "'{}' is not an ES version, engine preset or browserslist query",
+          target
+        )
+      })
+    }),
+  }
+}
+
+/// Hermes parses ES2019 syntax but still has no native async functions, so those are
+/// lowered to generators the same way `@react-native/babel-preset` does.
+fn hermes_env_options() -> Result<EnvOptions, String> {
+  let mut env = EnvOptions::from_target("es2019")?;
+  env.es2017.async_to_generator = true;
+  env.es2018.async_generator_functions = true;
+  Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_resolve_es_version() {
+    let env = resolve_env_options("es2019").unwrap();
+    assert!(env.es2020.nullish_coalescing_operator);
+    assert!(!env.es2019.optional_catch_binding);
+  }
+
+  #[test]
+  fn test_resolve_hermes_presets() {
+    for preset in ["hermes", "hermes-stable", "Hermes"] {
+      let env = resolve_env_options(preset).unwrap();
+      assert!(env.es2017.async_to_generator);
+      assert!(env.es2020.optional_chaining);
+    }
+  }
+
+  #[test]
+  fn test_resolve_jsc_preset() {
+    let env = resolve_env_options("jsc-ios15").unwrap();
+    assert!(env.es2022.class_properties.is_some());
+    assert!(!env.es2020.nullish_coalescing_operator);
+  }
+
+  #[test]
+  fn test_resolve_browserslist_query() {
+    let env = resolve_env_options("safari >= 13").unwrap();
+    assert!(env.es2020.nullish_coalescing_operator);
+  }
+
+  #[test]
+  fn test_resolve_invalid_target() {
+    assert!(resolve_env_options("not-an-engine").is_err());
+  }
+}
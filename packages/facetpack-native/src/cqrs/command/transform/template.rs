@@ -0,0 +1,103 @@
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{Expression, Program, Statement};
+use oxc_ast_visit::{walk_mut, VisitMut};
+use oxc_parser::Parser;
+use oxc_span::{SourceType, Span, SPAN};
+
+/// Identifier a template uses where an existing node is spliced in.
+pub const PLACEHOLDER: &str = "__facetpack_placeholder__";
+
+/// Parses generated code into the program's arena, without source positions so the
+/// generated nodes do not produce source map mappings.
+pub fn parse_template<'a>(allocator: &'a Allocator, source: &str) -> Program<'a> {
+  let source = allocator.alloc_str(source);
+  let mut program = Parser::new(allocator, source, SourceType::mjs())
+    .parse()
+    .program;
+  SpanRemover.visit_program(&mut program);
+  program
+}
+
+/// Parses a template made of a single expression, splicing `replacement` in place of
+/// [`PLACEHOLDER`].
+pub fn parse_expression_template<'a>(
+  allocator: &'a Allocator,
+  source: &str,
+  replacement: Option<Expression<'a>>,
+) -> Expression<'a> {
+  let mut program = parse_template(allocator, &format!("({});", source));
+  let Some(Statement::ExpressionStatement(stmt)) = program.body.pop() else {
+    unreachable!("expression templates are a single expression");
+  };
+  let mut expression = match stmt.unbox().expression {
+    Expression::ParenthesizedExpression(paren) => paren.unbox().expression,
+    expression => expression,
+  };
+  PlaceholderReplacer { replacement }.visit_expression(&mut expression);
+  expression
+}
+
+/// Replaces the first [`PLACEHOLDER`] reference with an existing expression.
+pub struct PlaceholderReplacer<'a> {
+  pub replacement: Option<Expression<'a>>,
+}
+
+impl<'a> VisitMut<'a> for PlaceholderReplacer<'a> {
+  fn visit_expression(&mut self, expr: &mut Expression<'a>) {
+    if matches!(expr, Expression::Identifier(ident) if ident.name == PLACEHOLDER) {
+      if let Some(replacement) = self.replacement.take() {
+        *expr = replacement;
+      }
+      return;
+    }
+    walk_mut::walk_expression(self, expr);
+  }
+}
+
+/// Appends a number to `base` until it does not collide with a taken name.
+pub fn unique_name(base: &str, taken: &[String]) -> String {
+  let mut name = base.to_string();
+  let mut index = 1;
+  while taken.contains(&name) {
+    index += 1;
+    name = format!("{}{}", base, index);
+  }
+  name
+}
+
+struct SpanRemover;
+
+impl<'a> VisitMut<'a> for SpanRemover {
+  fn visit_span(&mut self, span: &mut Span) {
+    *span = SPAN;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_codegen::Codegen;
+
+  #[test]
+  fn test_parse_expression_template() {
+    let allocator = Allocator::default();
+    let mut program = parse_template(&allocator, "const value = 42;");
+    let Some(Statement::VariableDeclaration(mut decl)) = program.body.pop() else {
+      unreachable!();
+    };
+    let init = decl.declarations[0].init.take();
+
+    let expr = parse_expression_template(
+      &allocator,
+      &format!("wrap({}, {})", PLACEHOLDER, PLACEHOLDER),
+      init,
+    );
+    let mut codegen = Codegen::new();
+    codegen.print_expression(&expr);
+
+    assert_eq!(
+      codegen.into_source_text(),
+      format!("wrap(42, {})", PLACEHOLDER)
+    );
+  }
+}
@@ -0,0 +1,615 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use oxc_allocator::{Allocator, Box as ArenaBox, CloneIn};
+use oxc_ast::ast::{
+  ArrowFunctionExpression, CallExpression, Declaration, Expression, Function, FunctionBody,
+  FunctionType, IdentifierReference, MethodDefinition, ObjectProperty, ObjectPropertyKind, Program,
+  Statement,
+};
+use oxc_ast::{AstBuilder, NONE};
+use oxc_ast_visit::{walk, walk_mut, Visit, VisitMut};
+use oxc_codegen::{Codegen, CodegenOptions};
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_semantic::{ScopeFlags, Scoping};
+use oxc_span::{GetSpan, Span, SPAN};
+
+use super::template::{
+  parse_expression_template, parse_template, unique_name, PlaceholderReplacer, PLACEHOLDER,
+};
+
+const WORKLET_DIRECTIVE: &str = "worklet";
+
+/// Functions whose arguments at the given indices run on the UI thread.
+const WORKLET_ARGUMENTS: &[(&str, &[usize])] = &[
+  ("useAnimatedStyle", &[0]),
+  ("useAnimatedProps", &[0]),
+  ("useDerivedValue", &[0]),
+  ("useAnimatedReaction", &[0, 1]),
+  ("useAnimatedScrollHandler", &[0]),
+  ("useAnimatedGestureHandler", &[0]),
+  ("useFrameCallback", &[0]),
+  ("runOnUI", &[0]),
+  ("executeOnUIRuntimeSync", &[0]),
+  ("createAnimatedPropAdapter", &[0]),
+  ("withTiming", &[2]),
+  ("withSpring", &[2]),
+  ("withDecay", &[1]),
+  ("withRepeat", &[3]),
+];
+
+/// Hooks that also accept an object whose function properties are worklets.
+const HANDLER_OBJECT_HOOKS: &[&str] = &["useAnimatedScrollHandler", "useAnimatedGestureHandler"];
+
+/// Callbacks of `react-native-gesture-handler` gestures built from `Gesture.*()`.
+const GESTURE_CALLBACKS: &[&str] = &[
+  "onBegin",
+  "onStart",
+  "onUpdate",
+  "onChange",
+  "onEnd",
+  "onFinalize",
+  "onTouchesDown",
+  "onTouchesMove",
+  "onTouchesUp",
+  "onTouchesCancelled",
+];
+
+/// Turns every worklet of the program into the factory `react-native-reanimated` expects:
+/// the function with its `__closure`, `__workletHash`, `__initData` and `__stackDetails`.
+///
+/// Must run after the other transforms so the worklet code is plain JavaScript.
+/// Returns a warning for each class method marked as a worklet, which Reanimated cannot
+/// recreate on the UI thread and which is left untouched.
+pub fn transform_worklets<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+  scoping: &Scoping,
+  filename: &str,
+  source_text: &str,
+) -> Vec<OxcDiagnostic> {
+  let mut collector = WorkletCollector {
+    scoping,
+    worklets: HashMap::new(),
+    warnings: Vec::new(),
+  };
+  collector.visit_program(program);
+
+  if collector.worklets.is_empty() {
+    return collector.warnings;
+  }
+
+  let mut transformer = WorkletTransformer {
+    allocator,
+    ast: AstBuilder::new(allocator),
+    filename,
+    source_text,
+    worklets: collector.worklets,
+    nested_init_data: Vec::new(),
+    init_data: Vec::new(),
+    hashes: HashSet::new(),
+  };
+  transformer.visit_program(program);
+
+  let index = program
+    .body
+    .iter()
+    .take_while(|stmt| matches!(stmt, Statement::ImportDeclaration(_)))
+    .count();
+  for (offset, stmt) in transformer.init_data.into_iter().enumerate() {
+    program.body.insert(index + offset, stmt);
+  }
+  collector.warnings
+}
+
+/// Finds worklet functions and the outer variables each of them captures.
+struct WorkletCollector<'s> {
+  scoping: &'s Scoping,
+  worklets: HashMap<Span, Vec<String>>,
+  warnings: Vec<OxcDiagnostic>,
+}
+
+impl WorkletCollector<'_> {
+  fn mark(&mut self, span: Span, visit: impl FnOnce(&mut CaptureCollector)) {
+    let mut captures = CaptureCollector {
+      scoping: self.scoping,
+      span,
+      names: BTreeSet::new(),
+    };
+    visit(&mut captures);
+    self
+      .worklets
+      .insert(span, captures.names.into_iter().collect());
+  }
+
+  fn mark_expression(&mut self, expr: &Expression) {
+    match expr {
+      Expression::FunctionExpression(func) => {
+        self.mark(func.span, |c| c.visit_function(func, ScopeFlags::Function))
+      }
+      Expression::ArrowFunctionExpression(arrow) => {
+        self.mark(arrow.span, |c| c.visit_arrow_function_expression(arrow))
+      }
+      Expression::ParenthesizedExpression(paren) => self.mark_expression(&paren.expression),
+      _ => {}
+    }
+  }
+}
+
+impl<'a> Visit<'a> for WorkletCollector<'_> {
+  fn visit_function(&mut self, func: &Function<'a>, flags: ScopeFlags) {
+    if func.body.as_deref().is_some_and(has_worklet_directive) {
+      self.mark(func.span, |c| c.visit_function(func, flags));
+    }
+    walk::walk_function(self, func, flags);
+  }
+
+  fn visit_arrow_function_expression(&mut self, arrow: &ArrowFunctionExpression<'a>) {
+    if has_worklet_directive(&arrow.body) {
+      self.mark(arrow.span, |c| c.visit_arrow_function_expression(arrow));
+    }
+    walk::walk_arrow_function_expression(self, arrow);
+  }
+
+  fn visit_method_definition(&mut self, method: &MethodDefinition<'a>) {
+    if !method
+      .value
+      .body
+      .as_deref()
+      .is_some_and(has_worklet_directive)
+    {
+      walk::walk_method_definition(self, method);
+      return;
+    }
+    self.warnings.push(
+      OxcDiagnostic::warn("Class methods cannot be worklets and are left as plain functions")
+        .with_help("Move the worklet to a function outside of the class")
+        .with_label(method.key.span()),
+    );
+    self.visit_property_key(&method.key);
+    walk::walk_function(self, &method.value, ScopeFlags::Function);
+  }
+
+  fn visit_call_expression(&mut self, call: &CallExpression<'a>) {
+    let callee = match &call.callee {
+      Expression::Identifier(ident) => Some(ident.name.as_str()),
+      Expression::StaticMemberExpression(member) => Some(member.property.name.as_str()),
+      _ => None,
+    };
+
+    if let Some((name, indices)) =
+      callee.and_then(|c| WORKLET_ARGUMENTS.iter().find(|(n, _)| *n == c))
+    {
+      for arg in indices.iter().filter_map(|&i| call.arguments.get(i)) {
+        let Some(expr) = arg.as_expression() else {
+          continue;
+        };
+        match expr {
+          Expression::ObjectExpression(object) if HANDLER_OBJECT_HOOKS.contains(name) => {
+            for property in object.properties.iter() {
+              if let ObjectPropertyKind::ObjectProperty(property) = property {
+                self.mark_expression(&property.value);
+              }
+            }
+          }
+          _ => self.mark_expression(expr),
+        }
+      }
+    }
+
+    if let Expression::StaticMemberExpression(member) = &call.callee {
+      if GESTURE_CALLBACKS.contains(&member.property.name.as_str()) && is_gesture(&member.object) {
+        if let Some(expr) = call.arguments.first().and_then(|a| a.as_expression()) {
+          self.mark_expression(expr);
+        }
+      }
+    }
+
+    walk::walk_call_expression(self, call);
+  }
+}
+
+/// Collects the names of the bindings a worklet references but does not declare.
+/// Unresolved references are globals available on the UI runtime and are not captured.
+struct CaptureCollector<'s> {
+  scoping: &'s Scoping,
+  span: Span,
+  names: BTreeSet<String>,
+}
+
+impl<'a> Visit<'a> for CaptureCollector<'_> {
+  fn visit_identifier_reference(&mut self, ident: &IdentifierReference<'a>) {
+    let symbol = ident
+      .reference_id
+      .get()
+      .and_then(|id| self.scoping.get_reference(id).symbol_id());
+    if let Some(symbol) = symbol {
+      let declared = self.scoping.symbol_span(symbol);
+      if declared.start < self.span.start || declared.end > self.span.end {
+        self.names.insert(ident.name.to_string());
+      }
+    }
+  }
+}
+
+/// Replaces worklets, innermost first, with their factories.
+struct WorkletTransformer<'a, 'ctx> {
+  allocator: &'a Allocator,
+  ast: AstBuilder<'a>,
+  filename: &'ctx str,
+  source_text: &'ctx str,
+  worklets: HashMap<Span, Vec<String>>,
+  /// Init data of the worklets nested in the ones being transformed, which the outer
+  /// worklet has to capture to recreate them on the UI thread.
+  nested_init_data: Vec<Vec<String>>,
+  init_data: Vec<Statement<'a>>,
+  /// Hashes of the emitted init data. Identical worklets on the same line share it.
+  hashes: HashSet<u64>,
+}
+
+impl<'a> WorkletTransformer<'a, '_> {
+  fn build_factory(
+    &mut self,
+    span: Span,
+    mut function: Expression<'a>,
+    captures: Vec<String>,
+  ) -> Expression<'a> {
+    remove_worklet_directive(&mut function);
+
+    let name = match &function {
+      Expression::FunctionExpression(func) => func.id.as_ref().map(|id| id.name.to_string()),
+      _ => None,
+    };
+    let code = self.worklet_code(&function, &captures, span, name.as_deref());
+    let hash = worklet_hash(&code);
+
+    let init_data = format!("_worklet_{}_init_data", hash);
+    if self.hashes.insert(hash) {
+      let init_data_source = format!(
+        "const {} = {{ code: {:?}, location: {:?} }};",
+        init_data, code, self.filename
+      );
+      self
+        .init_data
+        .extend(parse_template(self.allocator, &init_data_source).body);
+    }
+    if let Some(parent) = self.nested_init_data.last_mut() {
+      parent.push(init_data);
+    }
+
+    let local = unique_name(name.as_deref().unwrap_or("_worklet"), &captures);
+    let stack = unique_name("_e", &captures);
+    let factory_source = format!(
+      "(function () {{ const {stack} = [new global.Error(), 1, -27]; const {local} = {PLACEHOLDER}; \
+       {local}.__closure = {{ {closure} }}; {local}.__workletHash = {hash}; \
+       {local}.__initData = _worklet_{hash}_init_data; {local}.__stackDetails = {stack}; \
+       return {local}; }})()",
+      closure = captures.join(", "),
+    );
+    parse_expression_template(self.allocator, &factory_source, Some(function))
+  }
+
+  /// Generates the source Reanimated evaluates on the UI runtime: a named function
+  /// expression reading its captured variables from `this.__closure`.
+  fn worklet_code(
+    &self,
+    function: &Expression<'a>,
+    captures: &[String],
+    span: Span,
+    name: Option<&str>,
+  ) -> String {
+    let mut func = match function.clone_in(self.allocator) {
+      Expression::FunctionExpression(func) => func,
+      Expression::ArrowFunctionExpression(arrow) => {
+        let arrow = arrow.unbox();
+        let mut body = arrow.body;
+        if arrow.expression {
+          if let Some(Statement::ExpressionStatement(stmt)) = body.statements.pop() {
+            let argument = stmt.unbox().expression;
+            body
+              .statements
+              .push(self.ast.statement_return(SPAN, Some(argument)));
+          }
+        }
+        self.ast.alloc_function(
+          arrow.span,
+          FunctionType::FunctionExpression,
+          None,
+          false,
+          arrow.r#async,
+          false,
+          NONE,
+          NONE,
+          arrow.params,
+          NONE,
+          Some(body),
+        )
+      }
+      _ => unreachable!("worklets are functions"),
+    };
+
+    let line = self.source_text[..(span.start as usize).min(self.source_text.len())]
+      .matches('\n')
+      .count()
+      + 1;
+    let file = Path::new(self.filename)
+      .file_name()
+      .and_then(|f| f.to_str())
+      .unwrap_or_default()
+      .chars()
+      .filter(|c| c.is_ascii_alphanumeric())
+      .collect::<String>();
+    let worklet_name = format!("{}_{}{}", name.unwrap_or("anonymous"), file, line);
+    func.r#type = FunctionType::FunctionExpression;
+    func.id = Some(
+      self
+        .ast
+        .binding_identifier(SPAN, self.ast.atom(&worklet_name)),
+    );
+
+    if !captures.is_empty() {
+      let source = format!("const {{ {} }} = this.__closure;", captures.join(", "));
+      let closure = parse_template(self.allocator, &source).body;
+      if let Some(body) = func.body.as_mut() {
+        for (index, stmt) in closure.into_iter().enumerate() {
+          body.statements.insert(index, stmt);
+        }
+      }
+    }
+
+    let mut codegen = Codegen::new().with_options(CodegenOptions::minify());
+    codegen.print_expression(&Expression::FunctionExpression(func));
+    codegen.into_source_text()
+  }
+
+  fn take_worklet(&mut self, span: Span) -> Option<Vec<String>> {
+    let captures = self.worklets.remove(&span)?;
+    self.nested_init_data.push(Vec::new());
+    Some(captures)
+  }
+
+  fn nested_captures(&mut self, mut captures: Vec<String>) -> Vec<String> {
+    captures.extend(self.nested_init_data.pop().unwrap_or_default());
+    captures.sort();
+    captures.dedup();
+    captures
+  }
+}
+
+impl<'a> VisitMut<'a> for WorkletTransformer<'a, '_> {
+  fn visit_expression(&mut self, expr: &mut Expression<'a>) {
+    let span = match expr {
+      Expression::FunctionExpression(func) => func.span,
+      Expression::ArrowFunctionExpression(arrow) => arrow.span,
+      _ => SPAN,
+    };
+    let Some(captures) = self.take_worklet(span) else {
+      walk_mut::walk_expression(self, expr);
+      return;
+    };
+
+    walk_mut::walk_expression(self, expr);
+    let captures = self.nested_captures(captures);
+    let function = std::mem::replace(expr, self.ast.expression_null_literal(SPAN));
+    *expr = self.build_factory(span, function, captures);
+  }
+
+  fn visit_declaration(&mut self, decl: &mut Declaration<'a>) {
+    let span = match decl {
+      Declaration::FunctionDeclaration(func) => func.span,
+      _ => SPAN,
+    };
+    let Some(captures) = self.take_worklet(span) else {
+      walk_mut::walk_declaration(self, decl);
+      return;
+    };
+
+    walk_mut::walk_declaration(self, decl);
+    let captures = self.nested_captures(captures);
+    let Declaration::FunctionDeclaration(mut func) = std::mem::replace(
+      decl,
+      Declaration::VariableDeclaration(self.ast.alloc(self.ast.variable_declaration(
+        SPAN,
+        oxc_ast::ast::VariableDeclarationKind::Const,
+        self.ast.vec(),
+        false,
+      ))),
+    ) else {
+      unreachable!("the declaration was matched as a function");
+    };
+
+    // Like Reanimated, a worklet function declaration becomes a `const` binding.
+    let name = func
+      .id
+      .as_ref()
+      .map_or("_worklet".to_string(), |id| id.name.to_string());
+    func.r#type = FunctionType::FunctionExpression;
+    let factory = self.build_factory(span, Expression::FunctionExpression(func), captures);
+
+    let mut program = parse_template(
+      self.allocator,
+      &format!("const {} = {};", name, PLACEHOLDER),
+    );
+    let Some(Statement::VariableDeclaration(mut declaration)) = program.body.pop() else {
+      unreachable!("the binding template is a variable declaration");
+    };
+    declaration.span = span;
+    PlaceholderReplacer {
+      replacement: Some(factory),
+    }
+    .visit_variable_declaration(&mut declaration);
+    *decl = Declaration::VariableDeclaration(declaration);
+  }
+
+  fn visit_object_property(&mut self, property: &mut ObjectProperty<'a>) {
+    walk_mut::walk_object_property(self, property);
+    if property.method && !matches!(property.value, Expression::FunctionExpression(_)) {
+      property.method = false;
+    }
+  }
+}
+
+fn has_worklet_directive(body: &FunctionBody) -> bool {
+  body
+    .directives
+    .iter()
+    .any(|d| d.directive == WORKLET_DIRECTIVE)
+}
+
+fn remove_worklet_directive(function: &mut Expression) {
+  let body: Option<&mut ArenaBox<FunctionBody>> = match function {
+    Expression::FunctionExpression(func) => func.body.as_mut(),
+    Expression::ArrowFunctionExpression(arrow) => Some(&mut arrow.body),
+    _ => None,
+  };
+  if let Some(body) = body {
+    body.directives.retain(|d| d.directive != WORKLET_DIRECTIVE);
+  }
+}
+
+/// Returns `true` for a gesture builder chain such as `Gesture.Pan().minDistance(1)`.
+fn is_gesture(mut expr: &Expression) -> bool {
+  loop {
+    match expr {
+      Expression::CallExpression(call) => expr = &call.callee,
+      Expression::StaticMemberExpression(member) => expr = &member.object,
+      Expression::Identifier(ident) => return ident.name == "Gesture",
+      _ => return false,
+    }
+  }
+}
+
+/// FNV-1a hash of the worklet code, kept within the range of exact JavaScript integers.
+fn worklet_hash(code: &str) -> u64 {
+  let hash = code.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+    (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+  });
+  hash & 0xffff_ffff_ffff
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_parser::Parser;
+  use oxc_semantic::SemanticBuilder;
+  use oxc_span::SourceType;
+
+  fn transform(source: &str) -> String {
+    let allocator = Allocator::default();
+    let mut program = Parser::new(&allocator, source, SourceType::mjs())
+      .parse()
+      .program;
+    let scoping = SemanticBuilder::new()
+      .build(&program)
+      .semantic
+      .into_scoping();
+    transform_worklets(&allocator, &mut program, &scoping, "App.js", source);
+    Codegen::new().build(&program).code
+  }
+
+  #[test]
+  fn test_directive_worklet() {
+    let code = transform(
+      "const offset = 10;\nfunction move(x) {\n  'worklet';\n  return Math.max(x + offset, 0);\n}",
+    );
+
+    assert!(code.contains("const move = (function() {"));
+    assert!(code.contains("move.__closure = { offset };"));
+    assert!(code.contains("move.__workletHash = "));
+    assert!(code.contains("move.__initData = _worklet_"));
+    assert!(code.contains("move.__stackDetails = _e;"));
+    assert!(code.contains("const _e = ["));
+    assert!(code.contains("new global.Error()"));
+    assert!(!code.contains("'worklet'"));
+    assert!(code.contains(r#"function move_Appjs2(x){const{offset}=this.__closure;"#));
+  }
+
+  #[test]
+  fn test_hook_callbacks_are_worklets() {
+    let code = transform(
+      "import { useAnimatedStyle } from 'react-native-reanimated';\nconst style = useAnimatedStyle(() => ({ opacity: progress.value }));",
+    );
+
+    assert!(code.contains("_worklet.__closure = {};"));
+    assert!(code.contains("function anonymous_Appjs2(){return{opacity:progress.value}}"));
+
+    let imports_end = code.find("react-native-reanimated").unwrap();
+    assert!(code.find("_init_data").unwrap() > imports_end);
+  }
+
+  #[test]
+  fn test_handler_objects_and_gestures() {
+    let code = transform(
+      "const scroll = useAnimatedScrollHandler({ onScroll(event) { offset.value = event.contentOffset.y; } });\nconst pan = Gesture.Pan().minDistance(1).onUpdate((e) => { x.value = e.translationX; });\nconst other = list.onUpdate(() => {});",
+    );
+
+    assert!(code.contains("onScroll: (function"));
+    assert_eq!(code.matches("__workletHash").count(), 2);
+  }
+
+  #[test]
+  fn test_nested_worklets_capture_init_data() {
+    let code = transform(
+      "function outer() {\n  'worklet';\n  const inner = () => {\n    'worklet';\n    return 1;\n  };\n  return inner();\n}",
+    );
+
+    let inner_init_data = code
+      .split("const ")
+      .find_map(|s| s.strip_prefix("_worklet_"))
+      .and_then(|s| s.split('_').next())
+      .unwrap();
+    assert!(code.contains(&format!(
+      "outer.__closure = {{ _worklet_{}_init_data }};",
+      inner_init_data
+    )));
+  }
+
+  #[test]
+  fn test_identical_worklets_share_init_data() {
+    let code = transform(
+      "const a = useAnimatedStyle(() => ({ opacity: 1 })), b = useAnimatedStyle(() => ({ opacity: 1 }));",
+    );
+
+    assert_eq!(code.matches("__workletHash").count(), 2);
+    assert_eq!(code.matches("_init_data = {").count(), 1);
+  }
+
+  #[test]
+  fn test_method_worklets() {
+    let source = "const x = {};\nconst handlers = { onEnd(e) {\n  'worklet';\n  x.value = e.x;\n} };\nclass Tracker {\n  track() {\n    'worklet';\n    return 1;\n  }\n}";
+    let allocator = Allocator::default();
+    let mut program = Parser::new(&allocator, source, SourceType::mjs())
+      .parse()
+      .program;
+    let scoping = SemanticBuilder::new()
+      .build(&program)
+      .semantic
+      .into_scoping();
+    let warnings = transform_worklets(&allocator, &mut program, &scoping, "App.js", source);
+    let code = Codegen::new().build(&program).code;
+
+    assert!(code.contains("onEnd: (function"));
+    assert!(code.contains("function anonymous_Appjs2(e){const{x}=this.__closure;"));
+    assert_eq!(code.matches("__workletHash").count(), 1);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0]
+      .message
+      .contains("Class methods cannot be worklets"));
+  }
+
+  #[test]
+  fn test_no_worklets() {
+    let source = "const style = useStyle(() => ({ opacity: 1 }));";
+    assert_eq!(
+      transform(source).trim(),
+      Codegen::new()
+        .build(
+          &Parser::new(&Allocator::default(), source, SourceType::mjs())
+            .parse()
+            .program
+        )
+        .code
+        .trim()
+    );
+  }
+}
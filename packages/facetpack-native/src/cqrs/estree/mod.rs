@@ -0,0 +1,537 @@
+mod tokens;
+
+use oxc_ast::ast::{CommentKind, Program};
+use oxc_ast_visit::utf8_to_utf16::Utf8ToUtf16;
+use serde_json::{json, Map, Value};
+
+use crate::domain::line_index::LineIndex;
+use crate::domain::types::AstFormat;
+
+use tokens::collect_tokens;
+
+/// What to include in the serialized AST besides the nodes.
+pub struct EstreeOptions {
+  pub comments: bool,
+  pub tokens: bool,
+  /// Adds `range: [start, end]` to every node.
+  pub range: bool,
+  /// Adds `loc` with 1-based lines and 0-based columns to every node.
+  pub loc: bool,
+}
+
+impl Default for EstreeOptions {
+  fn default() -> Self {
+    Self {
+      comments: true,
+      tokens: false,
+      range: false,
+      loc: false,
+    }
+  }
+}
+
+/// Serializes a program to ESTree or Babel JSON.
+///
+/// Offsets are UTF-16 code units like in JavaScript.
+pub fn program_to_json(
+  program: &mut Program,
+  format: AstFormat,
+  options: &EstreeOptions,
+) -> String {
+  let source_text = program.source_text;
+  let tokens = if options.tokens {
+    collect_tokens(program)
+  } else {
+    Vec::new()
+  };
+
+  let utf16 = Utf8ToUtf16::new(source_text);
+  utf16.convert_program(program);
+
+  let json = if program.source_type.is_typescript() {
+    program.to_estree_ts_json(false)
+  } else {
+    program.to_estree_js_json(false)
+  };
+  let mut ast: Value = serde_json::from_str(&json).expect("oxc emits valid ESTree JSON");
+
+  let mut converter = utf16.converter();
+  let comments: Vec<(CommentKind, u32, u32, &str)> = program
+    .comments
+    .iter()
+    .filter(|_| options.comments)
+    .map(|comment| {
+      let text = comment.content_span().source_text(source_text);
+      let mut span = comment.span;
+      if let Some(converter) = converter.as_mut() {
+        converter.convert_span(&mut span);
+      }
+      (comment.kind, span.start, span.end, text)
+    })
+    .collect();
+
+  let lines = Locations::new(source_text, options);
+  let babel = format == AstFormat::Babel;
+  convert(&mut ast, &lines, babel);
+
+  let comments = comments
+    .into_iter()
+    .map(|(kind, start, end, text)| {
+      let kind = match (kind, babel) {
+        (CommentKind::Line, true) => "CommentLine",
+        (CommentKind::Line, false) => "Line",
+        (_, true) => "CommentBlock",
+        (_, false) => "Block",
+      };
+      let mut comment = json!({ "type": kind, "value": text, "start": start, "end": end });
+      lines.add_loc(comment.as_object_mut().unwrap(), babel);
+      comment
+    })
+    .collect::<Vec<_>>();
+
+  let mut converter = utf16.converter();
+  let tokens = tokens
+    .into_iter()
+    .map(|token| {
+      let mut span = token.span;
+      if let Some(converter) = converter.as_mut() {
+        converter.convert_span(&mut span);
+      }
+      let mut value = json!({
+        "type": token.kind,
+        "value": token.value,
+        "start": span.start,
+        "end": span.end,
+      });
+      if let Some((pattern, flags)) = token.regex {
+        value["regex"] = json!({ "pattern": pattern, "flags": flags });
+      }
+      lines.add_loc(value.as_object_mut().unwrap(), babel);
+      value
+    })
+    .collect::<Vec<_>>();
+
+  if babel {
+    let (start, end) = (ast["start"].clone(), ast["end"].clone());
+    let mut file = json!({
+      "type": "File",
+      "start": start,
+      "end": end,
+      "program": ast,
+    });
+    if options.comments {
+      file["comments"] = Value::Array(comments);
+    }
+    if options.tokens {
+      file["tokens"] = Value::Array(tokens);
+    }
+    lines.add_loc(file.as_object_mut().unwrap(), babel);
+    file.to_string()
+  } else {
+    if options.comments {
+      ast["comments"] = Value::Array(comments);
+    }
+    if options.tokens {
+      ast["tokens"] = Value::Array(tokens);
+    }
+    ast.to_string()
+  }
+}
+
+/// Computes `loc` and `range` from the UTF-16 offsets of nodes.
+struct Locations<'s> {
+  index: LineIndex<'s>,
+  loc: bool,
+  range: bool,
+}
+
+impl<'s> Locations<'s> {
+  fn new(source_text: &'s str, options: &EstreeOptions) -> Self {
+    Self {
+      index: LineIndex::new(source_text),
+      loc: options.loc,
+      range: options.range,
+    }
+  }
+
+  fn position(&self, offset: u32, babel: bool) -> Value {
+    let (line, column) = self.index.utf16_line_column(offset);
+    if babel {
+      json!({ "line": line, "column": column, "index": offset })
+    } else {
+      json!({ "line": line, "column": column })
+    }
+  }
+
+  /// Adds `loc` and `range` to a node with `start` and `end`, as enabled.
+  fn add_loc(&self, node: &mut Map<String, Value>, babel: bool) {
+    let (Some(start), Some(end)) = (
+      node.get("start").and_then(Value::as_u64),
+      node.get("end").and_then(Value::as_u64),
+    ) else {
+      return;
+    };
+    if self.loc {
+      let loc = json!({
+        "start": self.position(start as u32, babel),
+        "end": self.position(end as u32, babel),
+      });
+      node.insert("loc".to_string(), loc);
+    }
+    if self.range {
+      node.insert("range".to_string(), json!([start, end]));
+    }
+  }
+}
+
+fn convert(value: &mut Value, lines: &Locations, babel: bool) {
+  match value {
+    Value::Array(items) => {
+      for item in items {
+        convert(item, lines, babel);
+      }
+    }
+    Value::Object(node) => {
+      for child in node.values_mut() {
+        convert(child, lines, babel);
+      }
+      if babel {
+        if let Some(replacement) = to_babel(node, lines) {
+          *value = replacement;
+          return;
+        }
+      }
+      lines.add_loc(node, babel);
+    }
+    _ => {}
+  }
+}
+
+fn node_type(node: &Map<String, Value>) -> &str {
+  node.get("type").and_then(Value::as_str).unwrap_or_default()
+}
+
+fn set_type(node: &mut Map<String, Value>, kind: &str) {
+  node.insert("type".to_string(), Value::from(kind));
+}
+
+fn take(node: &mut Map<String, Value>, key: &str) -> Value {
+  node.remove(key).unwrap_or(Value::Null)
+}
+
+/// Reshapes an ESTree node, whose children are already converted, into its Babel
+/// equivalent. Returns a replacement when the node itself disappears.
+fn to_babel(node: &mut Map<String, Value>, lines: &Locations) -> Option<Value> {
+  match node_type(node) {
+    "Program" => {
+      move_directives(node, lines);
+      let hashbang = take(node, "hashbang");
+      let interpreter = match hashbang.get("value") {
+        Some(value) => json!({ "type": "InterpreterDirective", "value": value }),
+        None => Value::Null,
+      };
+      node.insert("interpreter".to_string(), interpreter);
+    }
+    "BlockStatement" => move_directives(node, lines),
+    "Literal" => literal_to_babel(node),
+    "Property" => {
+      let is_method = node.get("method").and_then(Value::as_bool) == Some(true)
+        || matches!(
+          node.get("kind").and_then(Value::as_str),
+          Some("get" | "set")
+        );
+      if is_method {
+        let kind = match node.get("kind").and_then(Value::as_str) {
+          Some(kind @ ("get" | "set")) => kind.to_string(),
+          _ => "method".to_string(),
+        };
+        set_type(node, "ObjectMethod");
+        node.insert("kind".to_string(), Value::from(kind));
+        hoist_function(node);
+      } else {
+        set_type(node, "ObjectProperty");
+        node.remove("kind");
+      }
+    }
+    "MethodDefinition" | "TSAbstractMethodDefinition" => {
+      let private = node_type_of(node, "key") == "PrivateName";
+      set_type(
+        node,
+        if private {
+          "ClassPrivateMethod"
+        } else {
+          "ClassMethod"
+        },
+      );
+      hoist_function(node);
+    }
+    "PropertyDefinition" | "TSAbstractPropertyDefinition" => {
+      let private = node_type_of(node, "key") == "PrivateName";
+      set_type(
+        node,
+        if private {
+          "ClassPrivateProperty"
+        } else {
+          "ClassProperty"
+        },
+      );
+    }
+    "AccessorProperty" => set_type(node, "ClassAccessorProperty"),
+    "PrivateIdentifier" => {
+      let start = node["start"].as_u64().unwrap_or_default() + 1;
+      let mut id = json!({ "type": "Identifier", "name": take(node, "name"), "start": start, "end": node["end"] });
+      lines.add_loc(id.as_object_mut().unwrap(), true);
+      set_type(node, "PrivateName");
+      node.insert("id".to_string(), id);
+    }
+    "ImportExpression" => {
+      let start = node["start"].as_u64().unwrap_or_default();
+      let mut callee = json!({ "type": "Import", "start": start, "end": start + 6 });
+      lines.add_loc(callee.as_object_mut().unwrap(), true);
+      let mut arguments = vec![take(node, "source")];
+      match take(node, "options") {
+        Value::Null => {}
+        options => arguments.push(options),
+      }
+      node.remove("phase");
+      set_type(node, "CallExpression");
+      node.insert("callee".to_string(), callee);
+      node.insert("arguments".to_string(), Value::Array(arguments));
+      node.insert("optional".to_string(), Value::Bool(false));
+    }
+    "ChainExpression" => {
+      let mut expression = take(node, "expression");
+      mark_optional_chain(&mut expression);
+      return Some(expression);
+    }
+    "ExportAllDeclaration" if !node.get("exported").is_none_or(Value::is_null) => {
+      let exported = take(node, "exported");
+      let mut specifier = json!({
+        "type": "ExportNamespaceSpecifier",
+        "start": node["start"],
+        "end": exported["end"],
+        "exported": exported,
+      });
+      lines.add_loc(specifier.as_object_mut().unwrap(), true);
+      set_type(node, "ExportNamedDeclaration");
+      node.insert("specifiers".to_string(), Value::Array(vec![specifier]));
+      node.insert("declaration".to_string(), Value::Null);
+    }
+    _ => {}
+  }
+  None
+}
+
+fn node_type_of<'n>(node: &'n Map<String, Value>, key: &str) -> &'n str {
+  node
+    .get(key)
+    .and_then(|child| child.get("type"))
+    .and_then(Value::as_str)
+    .unwrap_or_default()
+}
+
+fn literal_to_babel(node: &mut Map<String, Value>) {
+  let raw = take(node, "raw");
+  if let Some(regex) = node.remove("regex") {
+    set_type(node, "RegExpLiteral");
+    node.remove("value");
+    node.insert("pattern".to_string(), regex["pattern"].clone());
+    node.insert("flags".to_string(), regex["flags"].clone());
+    node.insert("extra".to_string(), json!({ "raw": raw }));
+    return;
+  }
+  if let Some(bigint) = node.remove("bigint") {
+    set_type(node, "BigIntLiteral");
+    node.insert("value".to_string(), bigint.clone());
+    node.insert(
+      "extra".to_string(),
+      json!({ "rawValue": bigint, "raw": raw }),
+    );
+    return;
+  }
+
+  let value = node.get("value").cloned().unwrap_or(Value::Null);
+  let kind = match value {
+    Value::String(_) => "StringLiteral",
+    Value::Number(_) => "NumericLiteral",
+    Value::Bool(_) => "BooleanLiteral",
+    _ => "NullLiteral",
+  };
+  set_type(node, kind);
+  match kind {
+    "StringLiteral" | "NumericLiteral" => {
+      node.insert(
+        "extra".to_string(),
+        json!({ "rawValue": value, "raw": raw }),
+      );
+    }
+    "NullLiteral" => {
+      node.remove("value");
+    }
+    _ => {}
+  }
+}
+
+/// Babel puts the parameters and body of methods on the method node itself.
+fn hoist_function(node: &mut Map<String, Value>) {
+  let Value::Object(mut function) = take(node, "value") else {
+    return;
+  };
+  for key in ["id", "params", "body", "generator", "async"] {
+    node.insert(key.to_string(), take(&mut function, key));
+  }
+  node.remove("method");
+  node.remove("shorthand");
+}
+
+/// Babel has no `Directive`-flagged expression statements: directives are separate nodes.
+fn move_directives(node: &mut Map<String, Value>, lines: &Locations) {
+  let mut directives = Vec::new();
+  if let Some(Value::Array(body)) = node.get_mut("body") {
+    let count = body
+      .iter()
+      .take_while(|stmt| stmt.get("directive").is_some_and(Value::is_string))
+      .count();
+    for stmt in body.drain(..count) {
+      let expression = &stmt["expression"];
+      let raw = expression["extra"]["raw"].clone();
+      let mut value = json!({
+        "type": "DirectiveLiteral",
+        "start": expression["start"],
+        "end": expression["end"],
+        "value": stmt["directive"],
+        "extra": { "raw": raw, "rawValue": stmt["directive"] },
+      });
+      lines.add_loc(value.as_object_mut().unwrap(), true);
+      let mut directive = json!({
+        "type": "Directive",
+        "start": stmt["start"],
+        "end": stmt["end"],
+        "value": value,
+      });
+      lines.add_loc(directive.as_object_mut().unwrap(), true);
+      directives.push(directive);
+    }
+  }
+  node.insert("directives".to_string(), Value::Array(directives));
+}
+
+/// Turns the members and calls of an optional chain into Babel's `Optional*` nodes, up to
+/// the outermost one that follows an `?.`. Returns whether `value` is part of the chain.
+fn mark_optional_chain(value: &mut Value) -> bool {
+  let Some(node) = value.as_object_mut() else {
+    return false;
+  };
+  let (child, optional_type) = match node_type(node) {
+    "MemberExpression" | "OptionalMemberExpression" => ("object", "OptionalMemberExpression"),
+    "CallExpression" | "OptionalCallExpression" => ("callee", "OptionalCallExpression"),
+    "TSNonNullExpression" => return node.get_mut("expression").is_some_and(mark_optional_chain),
+    _ => return false,
+  };
+
+  let child_optional = node.get_mut(child).is_some_and(mark_optional_chain);
+  let optional = node.get("optional").and_then(Value::as_bool) == Some(true);
+  if child_optional || optional {
+    set_type(node, optional_type);
+    node.insert("optional".to_string(), Value::Bool(optional));
+    true
+  } else {
+    false
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_allocator::Allocator;
+  use oxc_parser::{ParseOptions, Parser};
+  use oxc_span::SourceType;
+
+  fn to_json(source: &str, format: AstFormat) -> Value {
+    let allocator = Allocator::default();
+    let mut program = Parser::new(&allocator, source, SourceType::mjs())
+      .with_options(ParseOptions {
+        preserve_parens: false,
+        ..Default::default()
+      })
+      .parse()
+      .program;
+    serde_json::from_str(&program_to_json(
+      &mut program,
+      format,
+      &EstreeOptions {
+        loc: true,
+        ..Default::default()
+      },
+    ))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_estree_positions() {
+    let ast = to_json("// é\nconst s = 'ü';\nlet x = 1;", AstFormat::Estree);
+    let second = &ast["body"][1];
+
+    assert_eq!(ast["type"], "Program");
+    assert_eq!(second["start"], 20);
+    assert_eq!(second["loc"]["start"], json!({ "line": 3, "column": 0 }));
+    assert_eq!(ast["body"][0]["declarations"][0]["init"]["type"], "Literal");
+    assert_eq!(ast["comments"][0]["type"], "Line");
+    assert_eq!(ast["comments"][0]["value"], " é");
+  }
+
+  #[test]
+  fn test_babel_file_and_literals() {
+    let ast = to_json(
+      "'use strict';\nconst a = 'x', b = 1, c = null, d = /a/g, e = true;",
+      AstFormat::Babel,
+    );
+    let program = &ast["program"];
+    let inits: Vec<_> = program["body"][0]["declarations"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|d| d["init"]["type"].as_str().unwrap())
+      .collect();
+
+    assert_eq!(ast["type"], "File");
+    assert_eq!(program["directives"][0]["value"]["value"], "use strict");
+    assert_eq!(program["body"].as_array().unwrap().len(), 1);
+    assert_eq!(
+      inits,
+      [
+        "StringLiteral",
+        "NumericLiteral",
+        "NullLiteral",
+        "RegExpLiteral",
+        "BooleanLiteral"
+      ]
+    );
+    assert_eq!(
+      program["body"][0]["loc"]["start"],
+      json!({ "line": 2, "column": 0, "index": 14 })
+    );
+  }
+
+  #[test]
+  fn test_babel_objects_classes_and_chains() {
+    let ast = to_json(
+      "const o = { a, b() {}, get c() { return 1; } };\nclass K { #p = 1; m() {} }\na?.b.c();\nimport('./x');",
+      AstFormat::Babel,
+    );
+    let body = &ast["program"]["body"];
+    let properties = &body[0]["declarations"][0]["init"]["properties"];
+
+    assert_eq!(properties[0]["type"], "ObjectProperty");
+    assert_eq!(properties[1]["type"], "ObjectMethod");
+    assert_eq!(properties[1]["kind"], "method");
+    assert_eq!(properties[2]["kind"], "get");
+    assert_eq!(properties[2]["body"]["type"], "BlockStatement");
+    assert_eq!(body[1]["body"]["body"][0]["type"], "ClassPrivateProperty");
+    assert_eq!(body[1]["body"]["body"][0]["key"]["id"]["name"], "p");
+    assert_eq!(body[1]["body"]["body"][1]["type"], "ClassMethod");
+    assert_eq!(body[2]["expression"]["type"], "OptionalCallExpression");
+    assert_eq!(body[2]["expression"]["callee"]["object"]["optional"], true);
+    assert_eq!(body[3]["expression"]["callee"]["type"], "Import");
+  }
+}
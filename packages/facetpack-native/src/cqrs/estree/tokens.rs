@@ -0,0 +1,343 @@
+use oxc_ast::ast::{
+  BigIntLiteral, BindingIdentifier, BooleanLiteral, IdentifierName, IdentifierReference,
+  JSXIdentifier, JSXText, LabelIdentifier, NullLiteral, NumericLiteral, PrivateIdentifier, Program,
+  RegExpLiteral, StringLiteral, TemplateElement,
+};
+use oxc_ast_visit::Visit;
+use oxc_span::Span;
+
+/// A token in ESTree (espree) form. Spans are UTF-8 offsets into the source.
+pub struct Token<'a> {
+  pub kind: &'static str,
+  pub span: Span,
+  pub value: &'a str,
+  /// The pattern and flags of a `RegularExpression` token.
+  pub regex: Option<(&'a str, &'a str)>,
+}
+
+const KEYWORDS: &[&str] = &[
+  "await",
+  "break",
+  "case",
+  "catch",
+  "class",
+  "const",
+  "continue",
+  "debugger",
+  "default",
+  "delete",
+  "do",
+  "else",
+  "enum",
+  "export",
+  "extends",
+  "finally",
+  "for",
+  "function",
+  "if",
+  "import",
+  "in",
+  "instanceof",
+  "let",
+  "new",
+  "return",
+  "static",
+  "super",
+  "switch",
+  "this",
+  "throw",
+  "try",
+  "typeof",
+  "var",
+  "void",
+  "while",
+  "with",
+  "yield",
+];
+
+/// Punctuators, longest first so the scanner matches greedily.
+const PUNCTUATORS: &[&str] = &[
+  ">>>=", "...", "===", "!==", "**=", "<<=", ">>=", ">>>", "&&=", "||=", "??=", "=>", "==", "!=",
+  "<=", ">=", "&&", "||", "??", "?.", "++", "--", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=",
+  "<<", ">>", "**", "{", "}", "(", ")", "[", "]", ";", ",", "<", ">", "+", "-", "*", "/", "%", "&",
+  "|",
+];
+
+/// Tokenizes a program like espree. Tokens that depend on the parsing context (literals,
+/// templates, identifiers and JSX) come from the AST, and only keywords and punctuators in
+/// between are scanned from the source, skipping comments.
+pub fn collect_tokens<'a>(program: &Program<'a>) -> Vec<Token<'a>> {
+  let source_text = program.source_text;
+  let mut collector = AtomCollector {
+    source_text,
+    atoms: Vec::new(),
+  };
+  collector.visit_program(program);
+
+  let mut atoms = collector.atoms;
+  let skipped = program
+    .comments
+    .iter()
+    .map(|comment| comment.span)
+    .chain(program.hashbang.as_ref().map(|hashbang| hashbang.span));
+  atoms.extend(skipped.map(|span| Token {
+    kind: "",
+    span,
+    value: "",
+    regex: None,
+  }));
+  atoms.sort_by_key(|atom| (atom.span.start, std::cmp::Reverse(atom.span.end)));
+
+  let mut tokens = Vec::new();
+  let mut offset = 0;
+  for atom in atoms {
+    // Shorthand properties and specifiers have two nodes with the same span.
+    if atom.span.start < offset {
+      continue;
+    }
+    scan(source_text, offset, atom.span.start, &mut tokens);
+    offset = atom.span.end;
+    if !atom.kind.is_empty() {
+      tokens.push(atom);
+    }
+  }
+  scan(source_text, offset, source_text.len() as u32, &mut tokens);
+  tokens
+}
+
+struct AtomCollector<'a> {
+  source_text: &'a str,
+  atoms: Vec<Token<'a>>,
+}
+
+impl<'a> AtomCollector<'a> {
+  fn push(&mut self, kind: &'static str, span: Span) {
+    self.atoms.push(Token {
+      kind,
+      span,
+      value: span.source_text(self.source_text),
+      regex: None,
+    });
+  }
+
+  fn push_word(&mut self, span: Span) {
+    self.push(word_kind(span.source_text(self.source_text)), span);
+  }
+}
+
+impl<'a> Visit<'a> for AtomCollector<'a> {
+  fn visit_string_literal(&mut self, lit: &StringLiteral<'a>) {
+    self.push("String", lit.span);
+  }
+
+  fn visit_numeric_literal(&mut self, lit: &NumericLiteral<'a>) {
+    self.push("Numeric", lit.span);
+  }
+
+  fn visit_big_int_literal(&mut self, lit: &BigIntLiteral<'a>) {
+    self.push("Numeric", lit.span);
+  }
+
+  fn visit_boolean_literal(&mut self, lit: &BooleanLiteral) {
+    self.push("Boolean", lit.span);
+  }
+
+  fn visit_null_literal(&mut self, lit: &NullLiteral) {
+    self.push("Null", lit.span);
+  }
+
+  fn visit_reg_exp_literal(&mut self, lit: &RegExpLiteral<'a>) {
+    let value = lit.span.source_text(self.source_text);
+    let end = value.rfind('/').unwrap_or_default();
+    self.atoms.push(Token {
+      kind: "RegularExpression",
+      span: lit.span,
+      value,
+      regex: Some((&value[1.min(end)..end], &value[end + 1..])),
+    });
+  }
+
+  fn visit_template_element(&mut self, element: &TemplateElement<'a>) {
+    // The token includes the backtick or `}` before, and the backtick or `${` after.
+    let end = element.span.end + if element.tail { 1 } else { 2 };
+    self.push("Template", Span::new(element.span.start - 1, end));
+  }
+
+  fn visit_identifier_reference(&mut self, ident: &IdentifierReference<'a>) {
+    self.push_word(ident.span);
+  }
+
+  fn visit_binding_identifier(&mut self, ident: &BindingIdentifier<'a>) {
+    // The span of a binding includes its type annotation.
+    let word = &self.source_text[ident.span.start as usize..ident.span.end as usize];
+    let len = word.find(|c| !is_word_char(c)).unwrap_or(word.len());
+    self.push_word(Span::sized(ident.span.start, len as u32));
+  }
+
+  fn visit_identifier_name(&mut self, ident: &IdentifierName<'a>) {
+    self.push_word(ident.span);
+  }
+
+  fn visit_label_identifier(&mut self, ident: &LabelIdentifier<'a>) {
+    self.push_word(ident.span);
+  }
+
+  fn visit_private_identifier(&mut self, ident: &PrivateIdentifier<'a>) {
+    self.atoms.push(Token {
+      kind: "PrivateIdentifier",
+      span: ident.span,
+      value: ident.name.as_str(),
+      regex: None,
+    });
+  }
+
+  fn visit_jsx_identifier(&mut self, ident: &JSXIdentifier<'a>) {
+    self.push("JSXIdentifier", ident.span);
+  }
+
+  fn visit_jsx_text(&mut self, text: &JSXText<'a>) {
+    self.push("JSXText", text.span);
+  }
+}
+
+fn word_kind(word: &str) -> &'static str {
+  match word {
+    "true" | "false" => "Boolean",
+    "null" => "Null",
+    _ if KEYWORDS.contains(&word) => "Keyword",
+    _ => "Identifier",
+  }
+}
+
+fn is_word_char(c: char) -> bool {
+  c.is_alphanumeric() || c == '_' || c == '$' || c == '\\'
+}
+
+/// Scans the keywords, punctuators and words of `source_text[start..end]`, which has no
+/// literal, template or comment.
+fn scan<'a>(source_text: &'a str, start: u32, end: u32, tokens: &mut Vec<Token<'a>>) {
+  let gap = &source_text[start as usize..end as usize];
+  let mut chars = gap.char_indices().peekable();
+  while let Some((i, c)) = chars.next() {
+    if c.is_whitespace() || c == '\u{feff}' {
+      continue;
+    }
+
+    let len = if is_word_char(c) {
+      let mut len = c.len_utf8();
+      while let Some(&(j, next)) = chars.peek() {
+        if !is_word_char(next) {
+          break;
+        }
+        len = j + next.len_utf8() - i;
+        chars.next();
+      }
+      len
+    } else {
+      let rest = &gap[i..];
+      match PUNCTUATORS.iter().find(|p| rest.starts_with(*p)) {
+        Some(punctuator) => {
+          // Punctuators are ASCII, one char per byte.
+          for _ in 1..punctuator.len() {
+            chars.next();
+          }
+          punctuator.len()
+        }
+        None => c.len_utf8(),
+      }
+    };
+
+    let value = &gap[i..i + len];
+    let kind = match c {
+      _ if c.is_ascii_digit() => "Numeric",
+      _ if is_word_char(c) => word_kind(value),
+      _ => "Punctuator",
+    };
+    let token_start = start + i as u32;
+    tokens.push(Token {
+      kind,
+      span: Span::new(token_start, token_start + len as u32),
+      value,
+      regex: None,
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_allocator::Allocator;
+  use oxc_parser::Parser;
+  use oxc_span::SourceType;
+
+  fn tokens(source: &str, source_type: SourceType) -> Vec<(String, String)> {
+    let allocator = Allocator::default();
+    let program = Parser::new(&allocator, source, source_type).parse().program;
+    collect_tokens(&program)
+      .into_iter()
+      .map(|token| (token.kind.to_string(), token.value.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn test_tokens() {
+    let tokens = tokens(
+      "// x\nconst { a } = b?.c / 2, r = /=\\//g;\nlabel: for (;;) break label;\nf`t${a}u`;",
+      SourceType::mjs(),
+    );
+    let expected = [
+      ("Keyword", "const"),
+      ("Punctuator", "{"),
+      ("Identifier", "a"),
+      ("Punctuator", "}"),
+      ("Punctuator", "="),
+      ("Identifier", "b"),
+      ("Punctuator", "?."),
+      ("Identifier", "c"),
+      ("Punctuator", "/"),
+      ("Numeric", "2"),
+      ("Punctuator", ","),
+      ("Identifier", "r"),
+      ("Punctuator", "="),
+      ("RegularExpression", "/=\\//g"),
+      ("Punctuator", ";"),
+      ("Identifier", "label"),
+      ("Punctuator", ":"),
+      ("Keyword", "for"),
+      ("Punctuator", "("),
+      ("Punctuator", ";"),
+      ("Punctuator", ";"),
+      ("Punctuator", ")"),
+      ("Keyword", "break"),
+      ("Identifier", "label"),
+      ("Punctuator", ";"),
+      ("Identifier", "f"),
+      ("Template", "`t${"),
+      ("Identifier", "a"),
+      ("Template", "}u`"),
+      ("Punctuator", ";"),
+    ];
+
+    assert_eq!(
+      tokens,
+      expected
+        .iter()
+        .map(|(kind, value)| (kind.to_string(), value.to_string()))
+        .collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn test_jsx_and_private_tokens() {
+    let tokens = tokens(
+      "class K { #p = null; }\n<a-b c=\"d\">e</a-b>;",
+      SourceType::jsx(),
+    );
+
+    assert!(tokens.contains(&("PrivateIdentifier".to_string(), "p".to_string())));
+    assert!(tokens.contains(&("Null".to_string(), "null".to_string())));
+    assert!(tokens.contains(&("JSXIdentifier".to_string(), "a-b".to_string())));
+    assert!(tokens.contains(&("JSXText".to_string(), "e".to_string())));
+    assert!(tokens.contains(&("String".to_string(), "\"d\"".to_string())));
+  }
+}
@@ -1,3 +1,4 @@
 pub mod command;
+pub mod estree;
 pub mod query;
 pub mod traits;
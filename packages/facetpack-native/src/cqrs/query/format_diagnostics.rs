@@ -0,0 +1,416 @@
+use crate::cqrs::traits::Query;
+use crate::domain::error::FacetpackError;
+use crate::domain::types::{
+  Diagnostic, DiagnosticFormat, DiagnosticSeverity, Locale, RelatedLocation,
+};
+
+use serde_json::{json, Value};
+
+const TOOL_NAME: &str = "facetpack";
+const TOOL_URI: &str = "https://github.com/ecrindigital/facetpack";
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Renders diagnostics for CI logs, code scanning and editors.
+pub struct FormatDiagnosticsQuery {
+  pub diagnostics: Vec<Diagnostic>,
+  pub format: DiagnosticFormat,
+}
+
+impl FormatDiagnosticsQuery {
+  pub fn new(diagnostics: Vec<Diagnostic>, format: DiagnosticFormat) -> Self {
+    Self {
+      diagnostics,
+      format,
+    }
+  }
+
+  fn text(&self) -> String {
+    self
+      .diagnostics
+      .iter()
+      .map(|diag| {
+        // Diagnostics built in JavaScript may not have been formatted yet.
+        if diag.formatted.is_empty() {
+          Diagnostic::strip_ansi(&diag.format(Locale::from_env()))
+        } else {
+          Diagnostic::strip_ansi(&diag.formatted)
+        }
+      })
+      .collect()
+  }
+
+  /// One workflow command per diagnostic:
+  /// `::error file=app.tsx,line=1,col=5,endLine=1,endColumn=9,title=E0001::message`
+  fn github(&self) -> String {
+    let mut output = String::new();
+    for diag in &self.diagnostics {
+      let command = match diag.severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Info | DiagnosticSeverity::Hint => "notice",
+      };
+
+      let mut properties = vec![
+        format!("file={}", escape_github_property(&diag.filename)),
+        format!("line={}", diag.line),
+        format!("col={}", diag.column),
+      ];
+      if let Some(end_line) = diag.end_line {
+        properties.push(format!("endLine={}", end_line));
+      }
+      if let Some(end_column) = diag.end_column {
+        properties.push(format!("endColumn={}", end_column));
+      }
+      if let Some(code) = &diag.code {
+        properties.push(format!("title={}", escape_github_property(code)));
+      }
+
+      let message = [
+        Some(&diag.message),
+        diag.help.as_ref(),
+        diag.suggestion.as_ref(),
+      ]
+      .into_iter()
+      .flatten()
+      .map(String::as_str)
+      .collect::<Vec<_>>()
+      .join("\n");
+
+      output.push_str(&format!(
+        "::{} {}::{}\n",
+        command,
+        properties.join(","),
+        escape_github_data(&message)
+      ));
+    }
+    output
+  }
+
+  fn sarif(&self) -> Value {
+    let mut rules: Vec<&str> = Vec::new();
+    let results: Vec<Value> = self
+      .diagnostics
+      .iter()
+      .map(|diag| {
+        let level = match diag.severity {
+          DiagnosticSeverity::Error => "error",
+          DiagnosticSeverity::Warning => "warning",
+          DiagnosticSeverity::Info | DiagnosticSeverity::Hint => "note",
+        };
+        let mut result = json!({
+          "level": level,
+          "message": { "text": diag.message },
+          "locations": [{
+            "physicalLocation": sarif_physical_location(
+              &diag.filename,
+              diag.line,
+              diag.column,
+              diag.end_line,
+              diag.end_column,
+            ),
+          }],
+        });
+
+        if let Some(code) = &diag.code {
+          let index = rules
+            .iter()
+            .position(|rule| rule == code)
+            .unwrap_or_else(|| {
+              rules.push(code);
+              rules.len() - 1
+            });
+          result["ruleId"] = json!(code);
+          result["ruleIndex"] = json!(index);
+        }
+
+        if !diag.related.is_empty() {
+          result["relatedLocations"] = diag
+            .related
+            .iter()
+            .enumerate()
+            .map(|(id, related)| {
+              let mut location = json!({
+                "id": id,
+                "physicalLocation": sarif_physical_location(
+                  &related.filename,
+                  related.line,
+                  related.column,
+                  Some(related.end_line),
+                  Some(related.end_column),
+                ),
+              });
+              if let Some(message) = &related.message {
+                location["message"] = json!({ "text": message });
+              }
+              location
+            })
+            .collect();
+        }
+
+        let fix_text = [diag.help.as_ref(), diag.suggestion.as_ref()]
+          .into_iter()
+          .flatten()
+          .map(String::as_str)
+          .collect::<Vec<_>>();
+        if !fix_text.is_empty() {
+          result["properties"] = json!({ "help": fix_text.join("\n") });
+        }
+
+        result
+      })
+      .collect();
+
+    json!({
+      "$schema": SARIF_SCHEMA,
+      "version": "2.1.0",
+      "runs": [{
+        "tool": {
+          "driver": {
+            "name": TOOL_NAME,
+            "informationUri": TOOL_URI,
+            "version": env!("CARGO_PKG_VERSION"),
+            "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+          },
+        },
+        "columnKind": "utf16CodeUnits",
+        "results": results,
+      }],
+    })
+  }
+
+  fn lsp(&self) -> Value {
+    self
+      .diagnostics
+      .iter()
+      .map(|diag| {
+        let severity = match diag.severity {
+          DiagnosticSeverity::Error => 1,
+          DiagnosticSeverity::Warning => 2,
+          DiagnosticSeverity::Info => 3,
+          DiagnosticSeverity::Hint => 4,
+        };
+        let mut lsp_diag = json!({
+          "range": lsp_range(diag.line, diag.column, diag.end_line, diag.end_column),
+          "severity": severity,
+          "source": TOOL_NAME,
+          "message": diag.message,
+        });
+
+        if let Some(code) = &diag.code {
+          lsp_diag["code"] = json!(code);
+        }
+
+        if !diag.related.is_empty() {
+          lsp_diag["relatedInformation"] = diag.related.iter().map(lsp_related).collect();
+        }
+
+        if diag.help.is_some() || diag.suggestion.is_some() {
+          lsp_diag["data"] = json!({
+            "help": diag.help,
+            "suggestion": diag.suggestion,
+          });
+        }
+
+        lsp_diag
+      })
+      .collect()
+  }
+}
+
+impl Query for FormatDiagnosticsQuery {
+  type Result = String;
+
+  fn execute(&self) -> Result<Self::Result, FacetpackError> {
+    Ok(match self.format {
+      DiagnosticFormat::Text => self.text(),
+      DiagnosticFormat::Github => self.github(),
+      DiagnosticFormat::Sarif => self.sarif().to_string(),
+      DiagnosticFormat::Lsp => self.lsp().to_string(),
+    })
+  }
+}
+
+fn sarif_physical_location(
+  filename: &str,
+  line: u32,
+  column: u32,
+  end_line: Option<u32>,
+  end_column: Option<u32>,
+) -> Value {
+  let mut region = json!({ "startLine": line, "startColumn": column });
+  if let Some(end_line) = end_line {
+    region["endLine"] = json!(end_line);
+  }
+  if let Some(end_column) = end_column {
+    region["endColumn"] = json!(end_column);
+  }
+  json!({
+    "artifactLocation": { "uri": file_uri(filename) },
+    "region": region,
+  })
+}
+
+/// LSP positions are 0-based, while diagnostics are 1-based. Both count UTF-16 code units.
+fn lsp_range(line: u32, column: u32, end_line: Option<u32>, end_column: Option<u32>) -> Value {
+  let start = json!({
+    "line": line.saturating_sub(1),
+    "character": column.saturating_sub(1),
+  });
+  let end = json!({
+    "line": end_line.unwrap_or(line).saturating_sub(1),
+    "character": end_column.unwrap_or(column).saturating_sub(1),
+  });
+  json!({ "start": start, "end": end })
+}
+
+/// `relatedInformation` needs a message, so unlabeled locations get an empty one.
+fn lsp_related(related: &RelatedLocation) -> Value {
+  json!({
+    "location": {
+      "uri": file_uri(&related.filename),
+      "range": lsp_range(
+        related.line,
+        related.column,
+        Some(related.end_line),
+        Some(related.end_column),
+      ),
+    },
+    "message": related.message.as_deref().unwrap_or_default(),
+  })
+}
+
+/// Absolute paths become `file://` URIs. Relative paths stay relative references, which
+/// SARIF resolves against the repository root.
+fn file_uri(path: &str) -> String {
+  let path = path.replace('\\', "/");
+  let bytes = path.as_bytes();
+  let is_windows_absolute = bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+  let prefix = if path.starts_with('/') {
+    "file://"
+  } else if is_windows_absolute {
+    "file:///"
+  } else {
+    ""
+  };
+
+  let mut uri = String::from(prefix);
+  for byte in path.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' | b':' => {
+        uri.push(byte as char)
+      }
+      _ => uri.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+  uri
+}
+
+fn escape_github_data(text: &str) -> String {
+  text
+    .replace('%', "%25")
+    .replace('\r', "%0D")
+    .replace('\n', "%0A")
+}
+
+fn escape_github_property(text: &str) -> String {
+  escape_github_data(text)
+    .replace(':', "%3A")
+    .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn diagnostic() -> Diagnostic {
+    let mut diag = Diagnostic {
+      severity: DiagnosticSeverity::Error,
+      code: Some("E0001".to_string()),
+      message: "Invalid double operator".to_string(),
+      filename: "src/app, v2.ts".to_string(),
+      line: 2,
+      column: 11,
+      end_line: Some(2),
+      end_column: Some(12),
+      related: vec![RelatedLocation {
+        message: Some("first declared here".to_string()),
+        filename: "/home/dev/my app/a.ts".to_string(),
+        line: 1,
+        column: 7,
+        end_line: 1,
+        end_column: 8,
+      }],
+      help: Some("Did you mean '=='?".to_string()),
+      ..Default::default()
+    };
+    diag.formatted = diag.format(Locale::En);
+    diag
+  }
+
+  fn format(format: DiagnosticFormat) -> String {
+    FormatDiagnosticsQuery::new(vec![diagnostic()], format)
+      .execute()
+      .unwrap()
+  }
+
+  #[test]
+  fn test_text_has_no_escape_codes() {
+    let output = format(DiagnosticFormat::Text);
+
+    assert!(!output.contains('\x1b'));
+    assert!(output.contains("Invalid double operator"));
+    assert!(output.contains("Context: Did you mean '=='?"));
+  }
+
+  #[test]
+  fn test_github_annotations() {
+    assert_eq!(
+      format(DiagnosticFormat::Github),
+      "::error file=src/app%2C v2.ts,line=2,col=11,endLine=2,endColumn=12,title=E0001::Invalid double operator%0ADid you mean '=='?\n"
+    );
+  }
+
+  #[test]
+  fn test_sarif() {
+    let sarif: Value = serde_json::from_str(&format(DiagnosticFormat::Sarif)).unwrap();
+    let run = &sarif["runs"][0];
+    let result = &run["results"][0];
+
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "E0001");
+    assert_eq!(result["ruleId"], "E0001");
+    assert_eq!(result["level"], "error");
+    assert_eq!(
+      result["locations"][0]["physicalLocation"],
+      json!({
+        "artifactLocation": { "uri": "src/app%2C%20v2.ts" },
+        "region": { "startLine": 2, "startColumn": 11, "endLine": 2, "endColumn": 12 },
+      })
+    );
+    assert_eq!(
+      result["relatedLocations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+      "file:///home/dev/my%20app/a.ts"
+    );
+  }
+
+  #[test]
+  fn test_lsp() {
+    let lsp: Value = serde_json::from_str(&format(DiagnosticFormat::Lsp)).unwrap();
+    let diag = &lsp[0];
+
+    assert_eq!(
+      diag["range"],
+      json!({
+        "start": { "line": 1, "character": 10 },
+        "end": { "line": 1, "character": 11 },
+      })
+    );
+    assert_eq!(diag["severity"], 1);
+    assert_eq!(diag["code"], "E0001");
+    assert_eq!(diag["source"], "facetpack");
+    assert_eq!(
+      diag["relatedInformation"][0]["location"]["range"]["start"],
+      json!({ "line": 0, "character": 6 })
+    );
+  }
+}
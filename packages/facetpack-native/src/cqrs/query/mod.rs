@@ -1,7 +1,9 @@
 mod analyze;
+mod format_diagnostics;
 mod parse;
 mod resolve;
 
 pub use analyze::{AnalyzeBatchQuery, AnalyzeQuery};
-pub use parse::ParseQuery;
+pub use format_diagnostics::FormatDiagnosticsQuery;
+pub use parse::{DiagnosticReporter, ParseQuery};
 pub use resolve::{ResolveBatchQuery, ResolveQuery, ResolveResult, ResolverOptions};
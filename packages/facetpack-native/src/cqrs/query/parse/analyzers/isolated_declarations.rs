@@ -0,0 +1,54 @@
+use super::{ErrorAnalyzer, ErrorInfo, Locale, Message};
+
+pub struct IsolatedDeclarationsAnalyzer;
+
+impl ErrorAnalyzer for IsolatedDeclarationsAnalyzer {
+  fn can_analyze(&self, message: &str, _snippet: &str) -> bool {
+    message.contains("--isolatedDeclarations")
+  }
+
+  fn analyze(&self, message: &str, _snippet: &str, _column: u32, locale: Locale) -> ErrorInfo {
+    if message.contains("explicit return type") {
+      return ErrorInfo::localized("E0060", locale, Message::MissingReturnType, &[]);
+    }
+
+    if message.contains("explicit type annotation") {
+      return ErrorInfo::localized("E0061", locale, Message::MissingTypeAnnotation, &[]);
+    }
+
+    ErrorInfo::localized(
+      "E0062",
+      locale,
+      Message::IsolatedDeclaration,
+      &[("message", message)],
+    )
+  }
+
+  fn priority(&self) -> u8 {
+    95
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_missing_return_type() {
+    let analyzer = IsolatedDeclarationsAnalyzer;
+    let message =
+      "TS9007: Function must have an explicit return type annotation with --isolatedDeclarations.";
+    assert!(analyzer.can_analyze(message, ""));
+
+    assert_eq!(analyzer.analyze(message, "", 1, Locale::Fr).code, "E0060");
+  }
+
+  #[test]
+  fn test_missing_type_annotation() {
+    let analyzer = IsolatedDeclarationsAnalyzer;
+    let message =
+      "TS9010: Variable must have an explicit type annotation with --isolatedDeclarations.";
+
+    assert_eq!(analyzer.analyze(message, "", 1, Locale::Fr).code, "E0061");
+  }
+}
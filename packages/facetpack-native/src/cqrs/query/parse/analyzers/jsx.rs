@@ -1,4 +1,4 @@
-use super::{ErrorAnalyzer, ErrorInfo};
+use super::{ErrorAnalyzer, ErrorInfo, Locale, Message};
 
 pub struct JsxAnalyzer;
 
@@ -9,29 +9,24 @@ impl ErrorAnalyzer for JsxAnalyzer {
       || (snippet.contains("<") && (snippet.contains("/>") || snippet.contains("</")))
   }
 
-  fn analyze(&self, message: &str, snippet: &str, _column: u32) -> ErrorInfo {
-    if let Some(error) = self.check_style_syntax(snippet) {
+  fn analyze(&self, message: &str, snippet: &str, _column: u32, locale: Locale) -> ErrorInfo {
+    if let Some(error) = self.check_style_syntax(snippet, locale) {
       return error;
     }
 
-    if let Some(error) = self.check_class_attribute(snippet) {
+    if let Some(error) = self.check_class_attribute(snippet, locale) {
       return error;
     }
 
-    if let Some(error) = self.check_event_handlers(snippet) {
+    if let Some(error) = self.check_event_handlers(snippet, locale) {
       return error;
     }
 
-    if let Some(error) = self.check_for_attribute(snippet) {
+    if let Some(error) = self.check_for_attribute(snippet, locale) {
       return error;
     }
 
-    ErrorInfo::new(
-      "E0010",
-      message,
-      "Erreur de syntaxe JSX. Vérifiez les balises, attributs et expressions",
-      "Les expressions JS doivent être entre accolades: `{expression}`",
-    )
+    ErrorInfo::localized("E0010", locale, Message::JsxSyntax, &[("message", message)])
   }
 
   fn priority(&self) -> u8 {
@@ -40,60 +35,59 @@ impl ErrorAnalyzer for JsxAnalyzer {
 }
 
 impl JsxAnalyzer {
-  fn check_style_syntax(&self, snippet: &str) -> Option<ErrorInfo> {
+  fn check_style_syntax(&self, snippet: &str, locale: Locale) -> Option<ErrorInfo> {
     if snippet.contains("style=")
       && !snippet.contains("style={{")
       && !snippet.contains("style={")
     {
-      return Some(ErrorInfo::new(
+      return Some(ErrorInfo::localized(
         "E0010",
-        "Syntaxe de style JSX invalide",
-        "En JSX/React Native, les styles doivent être passés comme objet JavaScript",
-        "Utilisez: `style={{ prop: value }}` ou `style={styles.nomDuStyle}`",
+        locale,
+        Message::JsxStyle,
+        &[],
       ));
     }
     None
   }
 
-  fn check_class_attribute(&self, snippet: &str) -> Option<ErrorInfo> {
+  fn check_class_attribute(&self, snippet: &str, locale: Locale) -> Option<ErrorInfo> {
     if snippet.contains("class=") && !snippet.contains("className=") {
-      return Some(ErrorInfo::new(
+      return Some(ErrorInfo::localized(
         "E0011",
-        "Attribut 'class' invalide en JSX",
-        "En JSX, utilisez 'className' au lieu de 'class' pour les classes CSS",
-        "Remplacez `class=` par `className=`",
+        locale,
+        Message::JsxClassAttribute,
+        &[],
       ));
     }
     None
   }
 
-  fn check_event_handlers(&self, snippet: &str) -> Option<ErrorInfo> {
+  fn check_event_handlers(&self, snippet: &str, locale: Locale) -> Option<ErrorInfo> {
     let lowercase_handlers = ["onclick", "onchange", "onsubmit", "onfocus", "onblur", "onmouseover"];
 
     for handler in lowercase_handlers {
       if snippet.to_lowercase().contains(handler) && !snippet.contains(&self.to_camel_case(handler)) {
-        return Some(ErrorInfo::new(
+        return Some(ErrorInfo::localized(
           "E0012",
-          "Gestionnaire d'événement en minuscules",
-          "En JSX, les gestionnaires d'événements utilisent le camelCase",
-          &format!(
-            "Utilisez `{}` au lieu de `{}`. Pour React Native, utilisez `onPress`",
-            self.to_camel_case(handler),
-            handler
-          ),
+          locale,
+          Message::JsxEventHandler,
+          &[
+            ("camel_case", &self.to_camel_case(handler)),
+            ("handler", handler),
+          ],
         ));
       }
     }
     None
   }
 
-  fn check_for_attribute(&self, snippet: &str) -> Option<ErrorInfo> {
+  fn check_for_attribute(&self, snippet: &str, locale: Locale) -> Option<ErrorInfo> {
     if snippet.contains("for=") && !snippet.contains("htmlFor=") {
-      return Some(ErrorInfo::new(
+      return Some(ErrorInfo::localized(
         "E0013",
-        "Attribut 'for' invalide en JSX",
-        "En JSX, utilisez 'htmlFor' au lieu de 'for' pour les labels",
-        "Remplacez `for=` par `htmlFor=`",
+        locale,
+        Message::JsxForAttribute,
+        &[],
       ));
     }
     None
@@ -124,7 +118,7 @@ mod tests {
     let analyzer = JsxAnalyzer;
     assert!(analyzer.can_analyze("jsx error", "<div style="));
 
-    let result = analyzer.analyze("error", "<View style=>", 12);
+    let result = analyzer.analyze("error", "<View style=>", 12, Locale::Fr);
     assert_eq!(result.code, "E0010");
     assert!(result.suggestion.contains("style={{"));
   }
@@ -132,7 +126,7 @@ mod tests {
   #[test]
   fn test_class_attribute() {
     let analyzer = JsxAnalyzer;
-    let result = analyzer.analyze("error", "<div class=\"foo\">", 6);
+    let result = analyzer.analyze("error", "<div class=\"foo\">", 6, Locale::Fr);
     assert_eq!(result.code, "E0011");
     assert!(result.suggestion.contains("className"));
   }
@@ -140,7 +134,7 @@ mod tests {
   #[test]
   fn test_event_handler() {
     let analyzer = JsxAnalyzer;
-    let result = analyzer.analyze("error", "<button onclick={}>", 8);
+    let result = analyzer.analyze("error", "<button onclick={}>", 8, Locale::Fr);
     assert_eq!(result.code, "E0012");
     assert!(result.suggestion.contains("onClick"));
   }
@@ -148,7 +142,7 @@ mod tests {
   #[test]
   fn test_for_attribute() {
     let analyzer = JsxAnalyzer;
-    let result = analyzer.analyze("error", "<label for=\"input\">", 7);
+    let result = analyzer.analyze("error", "<label for=\"input\">", 7, Locale::Fr);
     assert_eq!(result.code, "E0013");
     assert!(result.suggestion.contains("htmlFor"));
   }
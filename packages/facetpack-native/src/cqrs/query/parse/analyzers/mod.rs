@@ -1,6 +1,8 @@
+mod isolated_declarations;
 mod jsx;
 mod module;
 mod reserved_word;
+mod semantic;
 mod statement;
 mod typescript;
 mod unclosed_bracket;
@@ -8,10 +10,14 @@ mod unexpected_token;
 mod unterminated;
 
 use super::error_info::ErrorInfo;
+use crate::domain::locale::Message;
+use crate::domain::types::Locale;
 
+pub use isolated_declarations::IsolatedDeclarationsAnalyzer;
 pub use jsx::JsxAnalyzer;
 pub use module::ModuleAnalyzer;
 pub use reserved_word::ReservedWordAnalyzer;
+pub use semantic::SemanticAnalyzer;
 pub use statement::StatementAnalyzer;
 pub use typescript::TypeScriptAnalyzer;
 pub use unclosed_bracket::UnclosedBracketAnalyzer;
@@ -20,7 +26,7 @@ pub use unterminated::UnterminatedAnalyzer;
 
 pub trait ErrorAnalyzer: Send + Sync {
   fn can_analyze(&self, message: &str, snippet: &str) -> bool;
-  fn analyze(&self, message: &str, snippet: &str, column: u32) -> ErrorInfo;
+  fn analyze(&self, message: &str, snippet: &str, column: u32, locale: Locale) -> ErrorInfo;
 
   fn priority(&self) -> u8 {
     50
@@ -49,21 +55,23 @@ impl AnalyzerRegistry {
     self.register(Box::new(TypeScriptAnalyzer));
     self.register(Box::new(ModuleAnalyzer));
     self.register(Box::new(StatementAnalyzer));
+    self.register(Box::new(SemanticAnalyzer));
+    self.register(Box::new(IsolatedDeclarationsAnalyzer));
   }
 
   pub fn register(&mut self, analyzer: Box<dyn ErrorAnalyzer>) {
     self.analyzers.push(analyzer);
-    self.analyzers.sort_by(|a, b| b.priority().cmp(&a.priority()));
+    self.analyzers.sort_by_key(|a| std::cmp::Reverse(a.priority()));
   }
 
-  pub fn analyze(&self, message: &str, snippet: &str, column: u32) -> ErrorInfo {
+  pub fn analyze(&self, message: &str, snippet: &str, column: u32, locale: Locale) -> ErrorInfo {
     for analyzer in &self.analyzers {
       if analyzer.can_analyze(message, snippet) {
-        return analyzer.analyze(message, snippet, column);
+        return analyzer.analyze(message, snippet, column, locale);
       }
     }
 
-    ErrorInfo::fallback(message)
+    ErrorInfo::fallback(message, locale)
   }
 }
 
@@ -81,10 +89,10 @@ mod tests {
   fn test_registry_finds_correct_analyzer() {
     let registry = AnalyzerRegistry::new();
 
-    let result = registry.analyze("Unexpected token", "const x = = 5;", 11);
+    let result = registry.analyze("Unexpected token", "const x = = 5;", 11, Locale::En);
     assert_eq!(result.code, "E0001");
 
-    let result = registry.analyze("Expected `}`", "const obj = {", 12);
+    let result = registry.analyze("Expected `}`", "const obj = {", 12, Locale::En);
     assert_eq!(result.code, "E0002");
   }
 
@@ -92,7 +100,7 @@ mod tests {
   fn test_registry_fallback() {
     let registry = AnalyzerRegistry::new();
 
-    let result = registry.analyze("Some unknown error", "code", 1);
+    let result = registry.analyze("Some unknown error", "code", 1, Locale::En);
     assert_eq!(result.code, "E0000");
   }
 }
@@ -1,4 +1,4 @@
-use super::{ErrorAnalyzer, ErrorInfo};
+use super::{ErrorAnalyzer, ErrorInfo, Locale, Message};
 
 pub struct ModuleAnalyzer;
 
@@ -8,24 +8,24 @@ impl ErrorAnalyzer for ModuleAnalyzer {
     msg_lower.contains("import") || msg_lower.contains("export")
   }
 
-  fn analyze(&self, message: &str, snippet: &str, _column: u32) -> ErrorInfo {
-    if let Some(error) = self.check_default_export_syntax(snippet) {
+  fn analyze(&self, message: &str, snippet: &str, _column: u32, locale: Locale) -> ErrorInfo {
+    if let Some(error) = self.check_default_export_syntax(snippet, locale) {
       return error;
     }
 
-    if let Some(error) = self.check_named_import_syntax(snippet) {
+    if let Some(error) = self.check_named_import_syntax(snippet, locale) {
       return error;
     }
 
-    if let Some(error) = self.check_import_assertion(message) {
+    if let Some(error) = self.check_import_assertion(message, locale) {
       return error;
     }
 
-    ErrorInfo::new(
+    ErrorInfo::localized(
       "E0030",
-      message,
-      "Les instructions import/export ne sont valides que dans les modules ES",
-      "Vérifiez que le fichier est traité comme un module (extension .mjs ou \"type\": \"module\" dans package.json)",
+      locale,
+      Message::ModuleSyntax,
+      &[("message", message)],
     )
   }
 
@@ -35,44 +35,43 @@ impl ErrorAnalyzer for ModuleAnalyzer {
 }
 
 impl ModuleAnalyzer {
-  fn check_default_export_syntax(&self, snippet: &str) -> Option<ErrorInfo> {
+  fn check_default_export_syntax(&self, snippet: &str, locale: Locale) -> Option<ErrorInfo> {
     if snippet.contains("export default =") {
-      return Some(ErrorInfo::new(
+      return Some(ErrorInfo::localized(
         "E0031",
-        "Syntaxe d'export default invalide",
-        "L'export default ne nécessite pas le signe '='",
-        "Utilisez: `export default value` ou `export default function() {}`",
+        locale,
+        Message::DefaultExportSyntax,
+        &[],
       ));
     }
     None
   }
 
-  fn check_named_import_syntax(&self, snippet: &str) -> Option<ErrorInfo> {
+  fn check_named_import_syntax(&self, snippet: &str, locale: Locale) -> Option<ErrorInfo> {
     if snippet.contains("import ")
       && snippet.contains(" from ")
       && !snippet.contains("{")
       && !snippet.contains("*")
       && !snippet.contains("import type")
+      && snippet.contains(",")
     {
-      if snippet.contains(",") {
-        return Some(ErrorInfo::new(
-          "E0032",
-          "Syntaxe d'import nommé invalide",
-          "Les imports nommés doivent être entre accolades",
-          "Utilisez: `import { name1, name2 } from 'module'`",
-        ));
-      }
+      return Some(ErrorInfo::localized(
+        "E0032",
+        locale,
+        Message::NamedImportSyntax,
+        &[],
+      ));
     }
     None
   }
 
-  fn check_import_assertion(&self, message: &str) -> Option<ErrorInfo> {
+  fn check_import_assertion(&self, message: &str, locale: Locale) -> Option<ErrorInfo> {
     if message.to_lowercase().contains("assertion") || message.to_lowercase().contains("assert") {
-      return Some(ErrorInfo::new(
+      return Some(ErrorInfo::localized(
         "E0033",
-        "Assertion d'import invalide",
-        "Les assertions d'import (import assertions) ont une syntaxe spécifique",
-        "Utilisez: `import data from './data.json' assert { type: 'json' }`",
+        locale,
+        Message::ImportAssertion,
+        &[],
       ));
     }
     None
@@ -94,14 +93,14 @@ mod tests {
   #[test]
   fn test_default_export_syntax() {
     let analyzer = ModuleAnalyzer;
-    let result = analyzer.analyze("error", "export default = foo", 16);
+    let result = analyzer.analyze("error", "export default = foo", 16, Locale::Fr);
     assert_eq!(result.code, "E0031");
   }
 
   #[test]
   fn test_generic_module_error() {
     let analyzer = ModuleAnalyzer;
-    let result = analyzer.analyze("import error", "import something", 1);
+    let result = analyzer.analyze("import error", "import something", 1, Locale::Fr);
     assert_eq!(result.code, "E0030");
   }
 }
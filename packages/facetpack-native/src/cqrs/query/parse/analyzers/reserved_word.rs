@@ -1,4 +1,4 @@
-use super::{ErrorAnalyzer, ErrorInfo};
+use super::{ErrorAnalyzer, ErrorInfo, Locale, Message};
 
 pub struct ReservedWordAnalyzer;
 
@@ -17,18 +17,16 @@ impl ErrorAnalyzer for ReservedWordAnalyzer {
     msg_lower.contains("reserved") || msg_lower.contains("keyword")
   }
 
-  fn analyze(&self, _message: &str, snippet: &str, _column: u32) -> ErrorInfo {
-    let found_word = self.find_reserved_word(snippet);
-
-    ErrorInfo::new(
-      "E0004",
-      "Utilisation d'un mot réservé comme identifiant",
-      &format!(
-        "Le mot '{}' est réservé par JavaScript et ne peut pas être utilisé comme nom de variable",
-        found_word.unwrap_or("(mot réservé)")
+  fn analyze(&self, _message: &str, snippet: &str, _column: u32, locale: Locale) -> ErrorInfo {
+    match self.find_reserved_word(snippet) {
+      Some(word) => ErrorInfo::localized(
+        "E0004",
+        locale,
+        Message::ReservedWord,
+        &[("word", word), ("capitalized", &self.capitalize(word))],
       ),
-      &self.generate_suggestion(found_word),
-    )
+      None => ErrorInfo::localized("E0004", locale, Message::ReservedWordUnknown, &[]),
+    }
   }
 
   fn priority(&self) -> u8 {
@@ -49,22 +47,13 @@ impl ReservedWordAnalyzer {
     None
   }
 
-  fn generate_suggestion(&self, word: Option<&str>) -> String {
-    match word {
-      Some(w) => {
-        let capitalized = w
-          .chars()
-          .next()
-          .map(|c| c.to_uppercase().to_string())
-          .unwrap_or_default()
-          + &w[1..];
-        format!(
-          "Choisissez un autre nom: `my{}` ou `{}_value` par exemple",
-          capitalized, w
-        )
-      }
-      None => "Choisissez un autre nom pour votre variable".to_string(),
-    }
+  fn capitalize(&self, word: &str) -> String {
+    word
+      .chars()
+      .next()
+      .map(|c| c.to_uppercase().to_string())
+      .unwrap_or_default()
+      + &word[1..]
   }
 }
 
@@ -77,7 +66,7 @@ mod tests {
     let analyzer = ReservedWordAnalyzer;
     assert!(analyzer.can_analyze("reserved word", ""));
 
-    let result = analyzer.analyze("reserved word", "const class = 5;", 7);
+    let result = analyzer.analyze("reserved word", "const class = 5;", 7, Locale::Fr);
     assert_eq!(result.code, "E0004");
     assert!(result.help.contains("class"));
     assert!(result.suggestion.contains("myClass"));
@@ -86,7 +75,7 @@ mod tests {
   #[test]
   fn test_reserved_function() {
     let analyzer = ReservedWordAnalyzer;
-    let result = analyzer.analyze("reserved word", "let function = test", 5);
+    let result = analyzer.analyze("reserved word", "let function = test", 5, Locale::Fr);
     assert!(result.help.contains("function"));
   }
 }
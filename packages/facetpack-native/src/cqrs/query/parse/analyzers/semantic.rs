@@ -0,0 +1,35 @@
+use super::{ErrorAnalyzer, ErrorInfo, Locale, Message};
+
+pub struct SemanticAnalyzer;
+
+impl ErrorAnalyzer for SemanticAnalyzer {
+  fn can_analyze(&self, message: &str, _snippet: &str) -> bool {
+    message.contains("has already been declared")
+  }
+
+  fn analyze(&self, message: &str, _snippet: &str, _column: u32, locale: Locale) -> ErrorInfo {
+    let name = message.split('`').nth(1).unwrap_or("identifier");
+
+    ErrorInfo::localized("E0050", locale, Message::Redeclaration, &[("name", name)])
+  }
+
+  fn priority(&self) -> u8 {
+    95
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_redeclaration() {
+    let analyzer = SemanticAnalyzer;
+    let message = "Identifier `count` has already been declared";
+    assert!(analyzer.can_analyze(message, ""));
+
+    let result = analyzer.analyze(message, "let count = 1;", 5, Locale::Fr);
+    assert_eq!(result.code, "E0050");
+    assert!(result.message.contains("count"));
+  }
+}
@@ -1,4 +1,4 @@
-use super::{ErrorAnalyzer, ErrorInfo};
+use super::{ErrorAnalyzer, ErrorInfo, Locale, Message};
 
 pub struct TypeScriptAnalyzer;
 
@@ -8,28 +8,28 @@ impl ErrorAnalyzer for TypeScriptAnalyzer {
     msg_lower.contains("type") || snippet.contains(": ")
   }
 
-  fn analyze(&self, message: &str, snippet: &str, _column: u32) -> ErrorInfo {
-    if let Some(error) = self.check_empty_type_annotation(snippet) {
+  fn analyze(&self, message: &str, snippet: &str, _column: u32, locale: Locale) -> ErrorInfo {
+    if let Some(error) = self.check_empty_type_annotation(snippet, locale) {
       return error;
     }
 
-    if let Some(error) = self.check_missing_parameter_type(snippet) {
+    if let Some(error) = self.check_missing_parameter_type(snippet, locale) {
       return error;
     }
 
-    if let Some(error) = self.check_invalid_generic(snippet) {
+    if let Some(error) = self.check_invalid_generic(snippet, locale) {
       return error;
     }
 
-    if let Some(error) = self.check_readonly_assignment(message) {
+    if let Some(error) = self.check_readonly_assignment(message, locale) {
       return error;
     }
 
-    ErrorInfo::new(
+    ErrorInfo::localized(
       "E0020",
-      message,
-      "Erreur de syntaxe TypeScript. Vérifiez les annotations de type",
-      "Consultez: https://www.typescriptlang.org/docs/handbook/2/everyday-types.html",
+      locale,
+      Message::TypeScriptSyntax,
+      &[("message", message)],
     )
   }
 
@@ -39,49 +39,49 @@ impl ErrorAnalyzer for TypeScriptAnalyzer {
 }
 
 impl TypeScriptAnalyzer {
-  fn check_empty_type_annotation(&self, snippet: &str) -> Option<ErrorInfo> {
+  fn check_empty_type_annotation(&self, snippet: &str, locale: Locale) -> Option<ErrorInfo> {
     if snippet.contains(": =") || snippet.contains(":=") || snippet.contains(": ;") {
-      return Some(ErrorInfo::new(
+      return Some(ErrorInfo::localized(
         "E0020",
-        "Annotation de type vide",
-        "Une annotation de type (:) doit être suivie d'un type valide",
-        "Ajoutez le type: `const x: number = 5` ou `const x: string = 'hello'`",
+        locale,
+        Message::EmptyTypeAnnotation,
+        &[],
       ));
     }
     None
   }
 
-  fn check_missing_parameter_type(&self, snippet: &str) -> Option<ErrorInfo> {
+  fn check_missing_parameter_type(&self, snippet: &str, locale: Locale) -> Option<ErrorInfo> {
     if snippet.contains("(") && snippet.contains(":)") {
-      return Some(ErrorInfo::new(
+      return Some(ErrorInfo::localized(
         "E0021",
-        "Type de paramètre manquant",
-        "Un paramètre avec ':' doit avoir un type spécifié",
-        "Exemple: `function foo(param: string)` ou utilisez `any` temporairement",
+        locale,
+        Message::MissingParameterType,
+        &[],
       ));
     }
     None
   }
 
-  fn check_invalid_generic(&self, snippet: &str) -> Option<ErrorInfo> {
+  fn check_invalid_generic(&self, snippet: &str, locale: Locale) -> Option<ErrorInfo> {
     if snippet.contains("<>") || snippet.contains("<,") {
-      return Some(ErrorInfo::new(
+      return Some(ErrorInfo::localized(
         "E0022",
-        "Générique invalide ou vide",
-        "Les génériques doivent contenir au moins un type: Array<T>",
-        "Spécifiez un type: `Array<string>` ou `Promise<void>`",
+        locale,
+        Message::InvalidGeneric,
+        &[],
       ));
     }
     None
   }
 
-  fn check_readonly_assignment(&self, message: &str) -> Option<ErrorInfo> {
+  fn check_readonly_assignment(&self, message: &str, locale: Locale) -> Option<ErrorInfo> {
     if message.to_lowercase().contains("readonly") {
-      return Some(ErrorInfo::new(
+      return Some(ErrorInfo::localized(
         "E0023",
-        "Tentative de modification d'une propriété readonly",
-        "Les propriétés marquées 'readonly' ne peuvent pas être modifiées après initialisation",
-        "Retirez 'readonly' si la modification est nécessaire, ou créez une nouvelle instance",
+        locale,
+        Message::ReadonlyAssignment,
+        &[],
       ));
     }
     None
@@ -97,7 +97,7 @@ mod tests {
     let analyzer = TypeScriptAnalyzer;
     assert!(analyzer.can_analyze("type error", "const x: "));
 
-    let result = analyzer.analyze("error", "const x: = 5;", 10);
+    let result = analyzer.analyze("error", "const x: = 5;", 10, Locale::Fr);
     assert_eq!(result.code, "E0020");
     assert!(result.message.contains("vide"));
   }
@@ -105,14 +105,14 @@ mod tests {
   #[test]
   fn test_missing_parameter_type() {
     let analyzer = TypeScriptAnalyzer;
-    let result = analyzer.analyze("error", "function foo(x:) {}", 14);
+    let result = analyzer.analyze("error", "function foo(x:) {}", 14, Locale::Fr);
     assert_eq!(result.code, "E0021");
   }
 
   #[test]
   fn test_invalid_generic() {
     let analyzer = TypeScriptAnalyzer;
-    let result = analyzer.analyze("error", "const arr: Array<> = []", 17);
+    let result = analyzer.analyze("error", "const arr: Array<> = []", 17, Locale::Fr);
     assert_eq!(result.code, "E0022");
   }
 }
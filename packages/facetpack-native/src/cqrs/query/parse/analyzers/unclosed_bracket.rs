@@ -1,4 +1,4 @@
-use super::{ErrorAnalyzer, ErrorInfo};
+use super::{ErrorAnalyzer, ErrorInfo, Locale, Message};
 
 pub struct UnclosedBracketAnalyzer;
 
@@ -10,23 +10,17 @@ impl ErrorAnalyzer for UnclosedBracketAnalyzer {
       || msg_lower.contains("expected `)`")
   }
 
-  fn analyze(&self, message: &str, snippet: &str, _column: u32) -> ErrorInfo {
-    let (bracket_name, open, close) = self.detect_bracket_type(message);
+  fn analyze(&self, message: &str, snippet: &str, _column: u32, locale: Locale) -> ErrorInfo {
+    let (bracket, open, close) = self.detect_bracket_type(message);
 
-    let open_count = snippet.matches(open).count();
-    let close_count = snippet.matches(close).count();
+    let open_count = snippet.matches(open).count().to_string();
+    let close_count = snippet.matches(close).count().to_string();
 
-    ErrorInfo::new(
+    ErrorInfo::localized(
       "E0002",
-      &format!("{} fermante '{}' manquante", bracket_name, close),
-      &format!(
-        "Il y a {} '{}' ouvrant(s) mais seulement {} '{}' fermant(s) dans ce bloc",
-        open_count, open, close_count, close
-      ),
-      &format!(
-        "Ajoutez '{}' pour fermer le bloc. Astuce: utilisez un éditeur avec coloration des parenthèses",
-        close
-      ),
+      locale,
+      bracket,
+      &[("open_count", &open_count), ("close_count", &close_count)],
     )
   }
 
@@ -36,13 +30,13 @@ impl ErrorAnalyzer for UnclosedBracketAnalyzer {
 }
 
 impl UnclosedBracketAnalyzer {
-  fn detect_bracket_type(&self, message: &str) -> (&'static str, &'static str, &'static str) {
+  fn detect_bracket_type(&self, message: &str) -> (Message, &'static str, &'static str) {
     if message.contains("}") {
-      ("accolade", "{", "}")
+      (Message::UnclosedBrace, "{", "}")
     } else if message.contains("]") {
-      ("crochet", "[", "]")
+      (Message::UnclosedBracket, "[", "]")
     } else {
-      ("parenthèse", "(", ")")
+      (Message::UnclosedParenthesis, "(", ")")
     }
   }
 }
@@ -56,7 +50,7 @@ mod tests {
     let analyzer = UnclosedBracketAnalyzer;
     assert!(analyzer.can_analyze("Expected `}`", ""));
 
-    let result = analyzer.analyze("Expected `}`", "const obj = { name: 'test'", 12);
+    let result = analyzer.analyze("Expected `}`", "const obj = { name: 'test'", 12, Locale::Fr);
     assert_eq!(result.code, "E0002");
     assert!(result.message.contains("accolade"));
   }
@@ -64,14 +58,14 @@ mod tests {
   #[test]
   fn test_missing_bracket() {
     let analyzer = UnclosedBracketAnalyzer;
-    let result = analyzer.analyze("Expected `]`", "const arr = [1, 2", 12);
+    let result = analyzer.analyze("Expected `]`", "const arr = [1, 2", 12, Locale::Fr);
     assert!(result.message.contains("crochet"));
   }
 
   #[test]
   fn test_missing_paren() {
     let analyzer = UnclosedBracketAnalyzer;
-    let result = analyzer.analyze("Expected `)`", "foo(1, 2", 4);
+    let result = analyzer.analyze("Expected `)`", "foo(1, 2", 4, Locale::Fr);
     assert!(result.message.contains("parenthèse"));
   }
 }
@@ -1,4 +1,4 @@
-use super::{ErrorAnalyzer, ErrorInfo};
+use super::{ErrorAnalyzer, ErrorInfo, Locale, Message};
 
 pub struct UnexpectedTokenAnalyzer;
 
@@ -8,45 +8,33 @@ impl ErrorAnalyzer for UnexpectedTokenAnalyzer {
     msg_lower.contains("unexpected token") || msg_lower.contains("expected expression")
   }
 
-  fn analyze(&self, message: &str, snippet: &str, column: u32) -> ErrorInfo {
+  fn analyze(&self, message: &str, snippet: &str, column: u32, locale: Locale) -> ErrorInfo {
     let col = column.saturating_sub(1) as usize;
     let line = snippet.lines().next().unwrap_or("");
 
     if line.contains("= =") || line.contains("= = =") {
-      return ErrorInfo::new(
+      let fixed = line.replace("= = =", "===").replace("= =", "==");
+      return ErrorInfo::localized(
         "E0001",
-        "Opérateur double invalide détecté",
-        "Deux opérateurs '=' consécutifs ne sont pas valides. Peut-être vouliez-vous '==' ou '==='?",
-        &format!(
-          "Remplacez '= =' par '==' pour une comparaison, ou retirez un '=' si c'est une affectation: `{}`",
-          line.replace("= = =", "===").replace("= =", "==")
-        ),
+        locale,
+        Message::DoubleOperator,
+        &[("line", &fixed)],
       );
     }
 
     if line.trim().ends_with('=') || (col > 0 && line.chars().nth(col) == Some('=')) {
-      return ErrorInfo::new(
-        "E0001",
-        "Expression manquante après l'opérateur d'affectation",
-        "L'opérateur '=' nécessite une valeur à droite",
-        "Ajoutez une valeur après '=': `const x = 5` ou `const x = getValue()`",
-      );
+      return ErrorInfo::localized("E0001", locale, Message::MissingAssignmentValue, &[]);
     }
 
     if snippet.contains("style=") && snippet.contains(">") {
-      return ErrorInfo::new(
-        "E0010",
-        "Attribut JSX incomplet",
-        "Un attribut JSX doit avoir une valeur. Pour les styles, utilisez un objet JavaScript",
-        "Exemple: `style={{ color: 'red' }}` ou `style={styles.container}`",
-      );
+      return ErrorInfo::localized("E0010", locale, Message::IncompleteJsxAttribute, &[]);
     }
 
-    ErrorInfo::new(
+    ErrorInfo::localized(
       "E0001",
-      message,
-      "Un token inattendu a été trouvé. Vérifiez les opérateurs, parenthèses et virgules",
-      "Vérifiez qu'il n'y a pas de caractère manquant ou en trop près de l'erreur",
+      locale,
+      Message::UnexpectedToken,
+      &[("message", message)],
     )
   }
 
@@ -64,7 +52,7 @@ mod tests {
     let analyzer = UnexpectedTokenAnalyzer;
     assert!(analyzer.can_analyze("Unexpected token", ""));
 
-    let result = analyzer.analyze("Unexpected token", "const x = = 5;", 11);
+    let result = analyzer.analyze("Unexpected token", "const x = = 5;", 11, Locale::Fr);
     assert_eq!(result.code, "E0001");
     assert!(result.message.contains("double"));
   }
@@ -72,7 +60,7 @@ mod tests {
   #[test]
   fn test_missing_value() {
     let analyzer = UnexpectedTokenAnalyzer;
-    let result = analyzer.analyze("Unexpected token", "const x =", 9);
+    let result = analyzer.analyze("Unexpected token", "const x =", 9, Locale::Fr);
     assert!(result.help.contains("valeur"));
   }
 }
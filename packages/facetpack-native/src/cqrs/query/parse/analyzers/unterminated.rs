@@ -1,4 +1,4 @@
-use super::{ErrorAnalyzer, ErrorInfo};
+use super::{ErrorAnalyzer, ErrorInfo, Locale, Message};
 
 pub struct UnterminatedAnalyzer;
 
@@ -7,15 +7,15 @@ impl ErrorAnalyzer for UnterminatedAnalyzer {
     message.to_lowercase().contains("unterminated")
   }
 
-  fn analyze(&self, message: &str, snippet: &str, _column: u32) -> ErrorInfo {
+  fn analyze(&self, message: &str, snippet: &str, _column: u32, locale: Locale) -> ErrorInfo {
     let msg_lower = message.to_lowercase();
 
     if msg_lower.contains("string") {
-      self.analyze_string(snippet)
+      self.analyze_string(snippet, locale)
     } else if msg_lower.contains("template") {
-      self.analyze_template()
+      self.analyze_template(locale)
     } else {
-      self.analyze_generic(message)
+      self.analyze_generic(message, locale)
     }
   }
 
@@ -25,39 +25,31 @@ impl ErrorAnalyzer for UnterminatedAnalyzer {
 }
 
 impl UnterminatedAnalyzer {
-  fn analyze_string(&self, snippet: &str) -> ErrorInfo {
+  fn analyze_string(&self, snippet: &str, locale: Locale) -> ErrorInfo {
     let quote_type = if snippet.contains("'") && !snippet.contains("\"") {
       "'"
     } else {
       "\""
     };
 
-    ErrorInfo::new(
+    ErrorInfo::localized(
       "E0003",
-      "Chaîne de caractères non terminée",
-      &format!(
-        "Une chaîne de caractères doit être fermée avec le même type de guillemet ({}) qu'à l'ouverture",
-        quote_type
-      ),
-      &format!("Ajoutez {} à la fin de la chaîne pour la fermer", quote_type),
+      locale,
+      Message::UnterminatedString,
+      &[("quote", quote_type)],
     )
   }
 
-  fn analyze_template(&self) -> ErrorInfo {
-    ErrorInfo::new(
-      "E0003",
-      "Template literal non terminé",
-      "Un template literal (`) doit être fermé avec un backtick (`)",
-      "Ajoutez ` à la fin du template literal",
-    )
+  fn analyze_template(&self, locale: Locale) -> ErrorInfo {
+    ErrorInfo::localized("E0003", locale, Message::UnterminatedTemplate, &[])
   }
 
-  fn analyze_generic(&self, message: &str) -> ErrorInfo {
-    ErrorInfo::new(
+  fn analyze_generic(&self, message: &str, locale: Locale) -> ErrorInfo {
+    ErrorInfo::localized(
       "E0003",
-      message,
-      "Un élément de syntaxe n'est pas correctement fermé",
-      "Vérifiez les guillemets, backticks et autres délimiteurs",
+      locale,
+      Message::Unterminated,
+      &[("message", message)],
     )
   }
 }
@@ -75,6 +67,7 @@ mod tests {
       "Unterminated string literal",
       "const msg = \"hello",
       12,
+      Locale::Fr,
     );
     assert_eq!(result.code, "E0003");
     assert!(result.message.contains("Chaîne"));
@@ -83,7 +76,12 @@ mod tests {
   #[test]
   fn test_unterminated_template() {
     let analyzer = UnterminatedAnalyzer;
-    let result = analyzer.analyze("Unterminated template literal", "const msg = `hello", 12);
+    let result = analyzer.analyze(
+      "Unterminated template literal",
+      "const msg = `hello",
+      12,
+      Locale::Fr,
+    );
     assert!(result.message.contains("Template"));
   }
 }
@@ -0,0 +1,173 @@
+use crate::domain::line_index::{LineIndex, Position};
+use crate::domain::locale;
+use crate::domain::types::{Diagnostic, DiagnosticSeverity, Locale, RelatedLocation};
+
+use super::analyzers::AnalyzerRegistry;
+use super::context::ComponentContextDetector;
+
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+
+/// Turns oxc diagnostics into positioned, coded and formatted `Diagnostic`s for a source
+/// file. Shared by parsing and transforming so both report errors the same way.
+pub struct DiagnosticReporter<'s> {
+  filename: &'s str,
+  source_text: &'s str,
+  line_index: LineIndex<'s>,
+  analyzer_registry: AnalyzerRegistry,
+  locale: Locale,
+}
+
+impl<'s> DiagnosticReporter<'s> {
+  /// Creates a reporter explaining errors in the language of the environment.
+  pub fn new(filename: &'s str, source_text: &'s str) -> Self {
+    Self {
+      filename,
+      source_text,
+      line_index: LineIndex::new(source_text),
+      analyzer_registry: AnalyzerRegistry::new(),
+      locale: Locale::from_env(),
+    }
+  }
+
+  pub fn with_locale(mut self, locale: Locale) -> Self {
+    self.locale = locale;
+    self
+  }
+
+  pub fn report(&self, error: &OxcDiagnostic, severity: DiagnosticSeverity) -> Diagnostic {
+    let message = error.message.to_string();
+    let labels = error.labels.as_deref().unwrap_or_default();
+    // oxc rarely marks a primary label, and lists the main span first otherwise.
+    let primary = labels.iter().position(LabeledSpan::primary).unwrap_or(0);
+    let span = labels.get(primary).map(|label| self.span(label));
+    let (line, column) = span.map_or((1, 1), |(start, _)| (start.line, start.utf16_column + 1));
+    let snippet = self.get_snippet_context(line, 2);
+
+    // Detect component context
+    let context_detector = ComponentContextDetector::new(self.source_text);
+    let component_context = context_detector.detect(line);
+
+    // Analyze the error using the registry
+    let error_info = self
+      .analyzer_registry
+      .analyze(&message, &snippet, column, self.locale);
+
+    // Build help text with component context if available
+    let help_text = match &component_context {
+      Some(comp) => locale::render(
+        locale::labels(self.locale).in_component,
+        &[("component", comp), ("help", &error_info.help)],
+      ),
+      None => error_info.help,
+    };
+
+    let mut diag = Diagnostic {
+      severity,
+      code: Some(error_info.code),
+      message: error_info.message,
+      filename: self.filename.to_string(),
+      line,
+      column,
+      end_line: span.map(|(_, end)| end.line),
+      end_column: span.map(|(_, end)| end.utf16_column + 1),
+      related: labels
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != primary)
+        .map(|(_, label)| self.related_location(label))
+        .collect(),
+      snippet: Some(snippet),
+      label: labels
+        .get(primary)
+        .and_then(|label| label.label())
+        .map(str::to_string),
+      help: Some(help_text),
+      suggestion: Some(error_info.suggestion),
+      formatted: String::new(),
+    };
+
+    diag.formatted = diag.format(self.locale);
+    diag
+  }
+
+  fn get_snippet_context(&self, line: u32, context_lines: u32) -> String {
+    let start = line.saturating_sub(context_lines).max(1);
+    let end = std::cmp::min(line + context_lines, self.line_index.line_count());
+
+    (start..=end)
+      .map(|line| self.line_index.line_text(line))
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+
+  fn span(&self, label: &LabeledSpan) -> (Position, Position) {
+    let start = self.line_index.position(label.offset() as u32);
+    let end = self
+      .line_index
+      .position((label.offset() + label.len()) as u32);
+    (start, end)
+  }
+
+  fn related_location(&self, label: &LabeledSpan) -> RelatedLocation {
+    let (start, end) = self.span(label);
+    RelatedLocation {
+      message: label.label().map(str::to_string),
+      filename: self.filename.to_string(),
+      line: start.line,
+      column: start.utf16_column + 1,
+      end_line: end.line,
+      end_column: end.utf16_column + 1,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use oxc_span::Span;
+
+  #[test]
+  fn test_report_utf16_span_and_related_locations() {
+    let source = "const titre = \"Été 🎉\"; let a = 1;\nlet a = 2;";
+    let first = source.find("a =").unwrap() as u32;
+    let second = source.rfind("a =").unwrap() as u32;
+    let error = OxcDiagnostic::error("Identifier `a` has already been declared").with_labels([
+      Span::sized(first, 1).label("`a` has already been declared here"),
+      Span::sized(second, 1).label("It can not be redeclared here"),
+    ]);
+
+    let diag = DiagnosticReporter::new("app.js", source).report(&error, DiagnosticSeverity::Error);
+
+    assert_eq!((diag.line, diag.column), (1, 29));
+    assert_eq!((diag.end_line, diag.end_column), (Some(1), Some(30)));
+    assert_eq!(
+      diag.label.as_deref(),
+      Some("`a` has already been declared here")
+    );
+    assert_eq!(diag.related.len(), 1);
+    let related = &diag.related[0];
+    assert_eq!(
+      (related.line, related.column, related.end_column),
+      (2, 5, 6)
+    );
+    assert_eq!(
+      related.message.as_deref(),
+      Some("It can not be redeclared here")
+    );
+  }
+
+  #[test]
+  fn test_snippet_follows_cr_and_line_separator_breaks() {
+    let source = "let a = 1;\rlet b = 2;\u{2028}let c = 3;\rlet d = 4;\r\nlet e = 5;\nlet f = 6;";
+    let offset = source.find("d =").unwrap() as u32;
+    let error = OxcDiagnostic::error("Unexpected token").with_label(Span::sized(offset, 1));
+
+    let diag = DiagnosticReporter::new("app.js", source).report(&error, DiagnosticSeverity::Error);
+
+    assert_eq!(diag.line, 4);
+    assert_eq!(
+      diag.snippet.as_deref(),
+      Some("let b = 2;\nlet c = 3;\nlet d = 4;\nlet e = 5;\nlet f = 6;")
+    );
+  }
+}
@@ -1,3 +1,6 @@
+use crate::domain::locale::{self, Message};
+use crate::domain::types::Locale;
+
 /// Represents enriched error information with context and suggestions
 #[derive(Debug, Clone)]
 pub struct ErrorInfo {
@@ -17,13 +20,20 @@ impl ErrorInfo {
     }
   }
 
+  /// Creates error info from the catalog of `locale`, filling the `{name}` placeholders
+  /// with `args`
+  pub fn localized(code: &str, locale: Locale, message: Message, args: &[(&str, &str)]) -> Self {
+    let text = locale::text(locale, message);
+    Self::new(
+      code,
+      &locale::render(text.message, args),
+      &locale::render(text.help, args),
+      &locale::render(text.suggestion, args),
+    )
+  }
+
   /// Creates a generic fallback error info
-  pub fn fallback(message: &str) -> Self {
-    Self {
-      code: "E0000".to_string(),
-      message: message.to_string(),
-      help: "Vérifiez la syntaxe autour de cette ligne".to_string(),
-      suggestion: "Consultez la documentation JavaScript/TypeScript".to_string(),
-    }
+  pub fn fallback(message: &str, locale: Locale) -> Self {
+    Self::localized("E0000", locale, Message::Fallback, &[("message", message)])
   }
 }
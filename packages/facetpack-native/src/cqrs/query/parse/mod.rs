@@ -1,18 +1,19 @@
 mod analyzers;
 mod context;
+mod diagnostic;
 mod error_info;
 
+use crate::cqrs::estree::{program_to_json, EstreeOptions};
 use crate::cqrs::traits::Query;
 use crate::domain::error::FacetpackError;
-use crate::domain::types::{Diagnostic, DiagnosticSeverity, ParseOptions, ParseResult, SourceType};
+use crate::domain::types::{
+  AstFormat, Diagnostic, DiagnosticSeverity, ModuleKind, ParseOptions, ParseResult, SourceType,
+};
 
-use analyzers::AnalyzerRegistry;
-use context::ComponentContextDetector;
+pub use diagnostic::DiagnosticReporter;
 
 use oxc_allocator::Allocator;
-use oxc_ast::ast::Program;
-use oxc_codegen::Codegen;
-use oxc_parser::Parser;
+use oxc_parser::{ParseOptions as OxcParseOptions, Parser};
 use oxc_span::SourceType as OxcSourceType;
 
 /// Query for parsing source code into an AST
@@ -20,7 +21,6 @@ pub struct ParseQuery {
   pub filename: String,
   pub source_text: String,
   pub options: ParseOptions,
-  analyzer_registry: AnalyzerRegistry,
 }
 
 impl ParseQuery {
@@ -29,23 +29,60 @@ impl ParseQuery {
       filename,
       source_text,
       options: options.unwrap_or_default(),
-      analyzer_registry: AnalyzerRegistry::new(),
     }
   }
 
   fn get_oxc_source_type(&self) -> OxcSourceType {
-    match self.options.source_type {
+    let source_type = match self.options.source_type {
       Some(SourceType::Module) => OxcSourceType::mjs(),
       Some(SourceType::Script) => OxcSourceType::cjs(),
       Some(SourceType::Jsx) => OxcSourceType::jsx(),
       Some(SourceType::Tsx) => OxcSourceType::tsx(),
       Some(SourceType::Typescript) => OxcSourceType::ts(),
       None => OxcSourceType::from_path(&self.filename).unwrap_or_default(),
+    };
+
+    // React Native packages commonly ship JSX in `.js` files.
+    let source_type = match self.options.jsx {
+      Some(jsx) => source_type.with_jsx(jsx),
+      None => source_type,
+    };
+
+    match self.options.module_kind {
+      Some(ModuleKind::Module) => source_type.with_module(true),
+      Some(ModuleKind::Script) => source_type.with_script(true),
+      Some(ModuleKind::Unambiguous) => source_type.with_unambiguous(true),
+      None => source_type,
+    }
+  }
+
+  fn get_oxc_parse_options(&self) -> OxcParseOptions {
+    let defaults = OxcParseOptions::default();
+    OxcParseOptions {
+      preserve_parens: self
+        .options
+        .preserve_parens
+        .unwrap_or(defaults.preserve_parens),
+      allow_return_outside_function: self
+        .options
+        .allow_return_outside_function
+        .unwrap_or(defaults.allow_return_outside_function),
+      allow_v8_intrinsics: self
+        .options
+        .allow_v8_intrinsics
+        .unwrap_or(defaults.allow_v8_intrinsics),
+      ..defaults
     }
   }
 
-  fn serialize_program(program: &Program) -> String {
-    Codegen::new().build(program).code
+  fn estree_options(&self) -> EstreeOptions {
+    let defaults = EstreeOptions::default();
+    EstreeOptions {
+      comments: self.options.comments.unwrap_or(defaults.comments),
+      tokens: self.options.tokens.unwrap_or(defaults.tokens),
+      range: self.options.range.unwrap_or(defaults.range),
+      loc: self.options.loc.unwrap_or(defaults.loc),
+    }
   }
 }
 
@@ -56,20 +93,43 @@ impl Query for ParseQuery {
     let allocator = Allocator::default();
     let source_type = self.get_oxc_source_type();
 
-    let parser_return = Parser::new(&allocator, &self.source_text, source_type).parse();
+    let parser_return = Parser::new(&allocator, &self.source_text, source_type)
+      .with_options(self.get_oxc_parse_options())
+      .parse();
 
     let errors: Vec<String> = parser_return.errors.iter().map(|e| e.to_string()).collect();
 
+    let mut reporter = DiagnosticReporter::new(&self.filename, &self.source_text);
+    if let Some(locale) = self.options.locale {
+      reporter = reporter.with_locale(locale);
+    }
     let diagnostics: Vec<Diagnostic> = parser_return
       .errors
       .iter()
-      .map(|e| self.create_diagnostic(e))
+      .map(|e| reporter.report(e, DiagnosticSeverity::Error))
       .collect();
 
-    let program = Self::serialize_program(&parser_return.program);
+    if !self.options.ast.unwrap_or(true) {
+      return Ok(ParseResult {
+        program: String::new(),
+        buffer: None,
+        errors,
+        diagnostics,
+        panicked: parser_return.panicked,
+      });
+    }
+
+    let mut program = parser_return.program;
+    let ast = program_to_json(&mut program, AstFormat::Estree, &self.estree_options());
+    let (program, buffer) = if self.options.buffer.unwrap_or(false) {
+      (String::new(), Some(ast.into_bytes().into()))
+    } else {
+      (ast, None)
+    };
 
     Ok(ParseResult {
       program,
+      buffer,
       errors,
       diagnostics,
       panicked: parser_return.panicked,
@@ -77,84 +137,10 @@ impl Query for ParseQuery {
   }
 }
 
-impl ParseQuery {
-  fn create_diagnostic(&self, error: &oxc_diagnostics::OxcDiagnostic) -> Diagnostic {
-    let message = error.message.to_string();
-    let (line, column) = self.extract_position(error);
-    let snippet = self.get_snippet_context(line, 2);
-
-    // Detect component context
-    let context_detector = ComponentContextDetector::new(&self.source_text);
-    let component_context = context_detector.detect(line);
-
-    // Analyze the error using the registry
-    let error_info = self.analyzer_registry.analyze(&message, &snippet, column);
-
-    // Build help text with component context if available
-    let help_text = match &component_context {
-      Some(comp) => format!("Dans {} → {}", comp, error_info.help),
-      None => error_info.help,
-    };
-
-    let mut diag = Diagnostic {
-      severity: DiagnosticSeverity::Error,
-      code: Some(error_info.code),
-      message: error_info.message,
-      filename: self.filename.clone(),
-      line,
-      column,
-      end_line: None,
-      end_column: None,
-      snippet: Some(snippet),
-      label: None,
-      help: Some(help_text),
-      suggestion: Some(error_info.suggestion),
-      formatted: String::new(),
-    };
-
-    diag.formatted = diag.format();
-    diag
-  }
-
-  fn get_snippet_context(&self, line: u32, context_lines: u32) -> String {
-    let lines: Vec<&str> = self.source_text.lines().collect();
-    let start = line.saturating_sub(context_lines + 1) as usize;
-    let end = std::cmp::min(line as usize + context_lines as usize, lines.len());
-
-    lines[start..end].join("\n")
-  }
-
-  fn extract_position(&self, error: &oxc_diagnostics::OxcDiagnostic) -> (u32, u32) {
-    if let Some(labels) = &error.labels {
-      if let Some(first_label) = labels.first() {
-        let offset = first_label.offset();
-        return self.offset_to_line_col(offset);
-      }
-    }
-    (1, 1)
-  }
-
-  fn offset_to_line_col(&self, offset: usize) -> (u32, u32) {
-    let mut line = 1u32;
-    let mut col = 1u32;
-    for (i, ch) in self.source_text.chars().enumerate() {
-      if i >= offset {
-        break;
-      }
-      if ch == '\n' {
-        line += 1;
-        col = 1;
-      } else {
-        col += 1;
-      }
-    }
-    (line, col)
-  }
-}
-
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::domain::types::Locale;
 
   #[test]
   fn test_parse_javascript() {
@@ -197,6 +183,7 @@ mod tests {
     let options = ParseOptions {
       source_type: Some(SourceType::Module),
       preserve_parens: None,
+      ..Default::default()
     };
     let query = ParseQuery::new(
       "test.txt".to_string(),
@@ -251,6 +238,7 @@ mod tests {
     let options = ParseOptions {
       source_type: Some(SourceType::Tsx),
       preserve_parens: None,
+      ..Default::default()
     };
     let query = ParseQuery::new("App.tsx".to_string(), code.to_string(), Some(options));
     let result = query.execute().unwrap();
@@ -267,6 +255,7 @@ mod tests {
     let options = ParseOptions {
       source_type: Some(SourceType::Typescript),
       preserve_parens: None,
+      ..Default::default()
     };
     let query = ParseQuery::new("types.ts".to_string(), code.to_string(), Some(options));
     let result = query.execute().unwrap();
@@ -319,6 +308,7 @@ return a + b"#;
     let options = ParseOptions {
       source_type: Some(SourceType::Script),
       preserve_parens: None,
+      ..Default::default()
     };
     let query = ParseQuery::new(
       "test.txt".to_string(),
@@ -330,6 +320,7 @@ return a + b"#;
     let options = ParseOptions {
       source_type: Some(SourceType::Jsx),
       preserve_parens: None,
+      ..Default::default()
     };
     let query = ParseQuery::new("test.txt".to_string(), "<div/>".to_string(), Some(options));
     assert!(query.execute().is_ok());
@@ -337,6 +328,7 @@ return a + b"#;
     let options = ParseOptions {
       source_type: Some(SourceType::Tsx),
       preserve_parens: None,
+      ..Default::default()
     };
     let query = ParseQuery::new("test.txt".to_string(), "<div/>".to_string(), Some(options));
     assert!(query.execute().is_ok());
@@ -344,6 +336,7 @@ return a + b"#;
     let options = ParseOptions {
       source_type: Some(SourceType::Typescript),
       preserve_parens: None,
+      ..Default::default()
     };
     let query = ParseQuery::new(
       "test.txt".to_string(),
@@ -352,4 +345,157 @@ return a + b"#;
     );
     assert!(query.execute().is_ok());
   }
+
+  #[test]
+  fn test_parse_returns_estree() {
+    let options = ParseOptions {
+      tokens: Some(true),
+      range: Some(true),
+      loc: Some(true),
+      ..Default::default()
+    };
+    let query = ParseQuery::new(
+      "test.ts".to_string(),
+      "// note\nconst x: number = 1;".to_string(),
+      Some(options),
+    );
+    let result = query.execute().unwrap();
+    let ast: serde_json::Value = serde_json::from_str(&result.program).unwrap();
+    let declaration = &ast["body"][0];
+
+    assert_eq!(ast["type"], "Program");
+    assert_eq!(declaration["type"], "VariableDeclaration");
+    assert_eq!(declaration["range"], serde_json::json!([8, 28]));
+    assert_eq!(declaration["loc"]["start"]["line"], 2);
+    assert_eq!(
+      declaration["declarations"][0]["id"]["typeAnnotation"]["type"],
+      "TSTypeAnnotation"
+    );
+    assert_eq!(declaration["declarations"][0]["init"]["type"], "Literal");
+    assert_eq!(ast["comments"][0]["value"], " note");
+    assert_eq!(ast["tokens"][0]["value"], "const");
+    assert_eq!(ast["tokens"][0]["range"], serde_json::json!([8, 13]));
+    assert_eq!(ast["tokens"][1]["value"], "x");
+    assert!(result.buffer.is_none());
+  }
+
+  #[test]
+  fn test_parse_to_buffer() {
+    let options = ParseOptions {
+      buffer: Some(true),
+      comments: Some(false),
+      ..Default::default()
+    };
+    let query = ParseQuery::new("test.js".to_string(), "a;".to_string(), Some(options));
+    let result = query.execute().unwrap();
+    let ast: serde_json::Value = serde_json::from_slice(&result.buffer.unwrap()).unwrap();
+
+    assert!(result.program.is_empty());
+    assert_eq!(ast["body"][0]["expression"]["name"], "a");
+    assert!(ast.get("comments").is_none());
+    assert!(ast.get("loc").is_none());
+  }
+
+  #[test]
+  fn test_parse_without_ast() {
+    let options = ParseOptions {
+      ast: Some(false),
+      buffer: Some(true),
+      ..Default::default()
+    };
+    let query = ParseQuery::new(
+      "test.js".to_string(),
+      "const x = ;".to_string(),
+      Some(options),
+    );
+    let result = query.execute().unwrap();
+
+    assert!(result.program.is_empty());
+    assert!(result.buffer.is_none());
+    assert_eq!(result.diagnostics.len(), 1);
+  }
+
+  #[test]
+  fn test_parse_options() {
+    let parse = |source: &str, options: ParseOptions| {
+      let query = ParseQuery::new("index.js".to_string(), source.to_string(), Some(options));
+      let result = query.execute().unwrap();
+      let ast: serde_json::Value = serde_json::from_str(&result.program).unwrap();
+      (result.errors.len(), ast)
+    };
+
+    let (errors, _) = parse("<View />;", ParseOptions::default());
+    assert_eq!(errors, 1);
+    let (errors, ast) = parse(
+      "<View />;",
+      ParseOptions {
+        jsx: Some(true),
+        ..Default::default()
+      },
+    );
+    assert_eq!(errors, 0);
+    assert_eq!(ast["body"][0]["expression"]["type"], "JSXElement");
+
+    let (_, ast) = parse(
+      "(a);",
+      ParseOptions {
+        preserve_parens: Some(false),
+        ..Default::default()
+      },
+    );
+    assert_eq!(ast["body"][0]["expression"]["type"], "Identifier");
+
+    let (errors, _) = parse(
+      "return %GetOptimizationStatus(f);",
+      ParseOptions {
+        module_kind: Some(ModuleKind::Script),
+        allow_return_outside_function: Some(true),
+        allow_v8_intrinsics: Some(true),
+        ..Default::default()
+      },
+    );
+    assert_eq!(errors, 0);
+
+    let (_, ast) = parse(
+      "var a = 1;",
+      ParseOptions {
+        module_kind: Some(ModuleKind::Unambiguous),
+        ..Default::default()
+      },
+    );
+    assert_eq!(ast["sourceType"], "script");
+    let (_, ast) = parse(
+      "export {};",
+      ParseOptions {
+        module_kind: Some(ModuleKind::Unambiguous),
+        ..Default::default()
+      },
+    );
+    assert_eq!(ast["sourceType"], "module");
+  }
+
+  #[test]
+  fn test_localized_diagnostics() {
+    let parse = |locale| {
+      let options = ParseOptions {
+        locale: Some(locale),
+        ..Default::default()
+      };
+      let query = ParseQuery::new(
+        "test.js".to_string(),
+        "const x = = 5;".to_string(),
+        Some(options),
+      );
+      query.execute().unwrap().diagnostics.remove(0)
+    };
+
+    let en = parse(Locale::En);
+    let fr = parse(Locale::Fr);
+
+    assert_eq!(en.code, fr.code);
+    assert_eq!(en.message, "Invalid double operator");
+    assert_eq!(fr.message, "Opérateur double invalide détecté");
+    assert!(en.formatted.contains("Context:"));
+    assert!(fr.formatted.contains("Contexte:"));
+  }
 }
@@ -1,7 +1,8 @@
 use crate::cqrs::traits::Query;
 use crate::domain::error::FacetpackError;
 use napi_derive::napi;
-use oxc_resolver::{ResolveOptions, Resolver};
+use oxc_resolver::{AliasValue, ResolveOptions, Resolver};
+use std::collections::HashMap;
 use std::path::Path;
 
 #[napi(object)]
@@ -17,6 +18,8 @@ pub struct ResolverOptions {
   pub extensions: Option<Vec<String>>,
   pub main_fields: Option<Vec<String>>,
   pub condition_names: Option<Vec<String>>,
+  /// Maps a specifier, or a prefix of it followed by `/`, to another path.
+  pub alias: Option<HashMap<String, String>>,
 }
 
 pub struct ResolveQuery {
@@ -49,6 +52,13 @@ impl ResolveQuery {
       opts.condition_names = condition_names.clone();
     }
 
+    if let Some(ref alias) = self.options.alias {
+      opts.alias = alias
+        .iter()
+        .map(|(key, value)| (key.clone(), vec![AliasValue::Path(value.clone())]))
+        .collect();
+    }
+
     opts
   }
 }
@@ -104,6 +114,13 @@ impl ResolveBatchQuery {
       opts.condition_names = condition_names.clone();
     }
 
+    if let Some(ref alias) = self.options.alias {
+      opts.alias = alias
+        .iter()
+        .map(|(key, value)| (key.clone(), vec![AliasValue::Path(value.clone())]))
+        .collect();
+    }
+
     opts
   }
 
@@ -11,6 +11,15 @@ pub enum FacetpackError {
   #[error("Invalid source type: {0}")]
   InvalidSourceType(String),
 
+  #[error("Invalid target: {0}")]
+  InvalidTarget(String),
+
+  #[error("Invalid define: {0}")]
+  InvalidDefine(String),
+
+  #[error("Invalid source map: {0}")]
+  InvalidSourceMap(String),
+
   #[error("Codegen error: {0}")]
   CodegenError(String),
 }
@@ -0,0 +1,144 @@
+/// A position in a source file. Lines are 1-based and columns 0-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+  /// Byte offset from the start of the file.
+  pub offset: u32,
+  /// Offset in UTF-16 code units, as JavaScript strings count them.
+  pub utf16_offset: u32,
+  pub line: u32,
+  /// Column in bytes (UTF-8 code units).
+  pub column: u32,
+  /// Column in UTF-16 code units, as editors and the LSP count them.
+  pub utf16_column: u32,
+}
+
+/// Maps offsets in a source file to lines and columns, in bytes and in UTF-16 code units.
+///
+/// Lines end at `\n`, `\r\n`, `\r`, U+2028 and U+2029 like in JavaScript.
+pub struct LineIndex<'s> {
+  source_text: &'s str,
+  /// Byte offset of the start of each line.
+  line_starts: Vec<u32>,
+  /// UTF-16 offset of the start of each line.
+  utf16_line_starts: Vec<u32>,
+}
+
+impl<'s> LineIndex<'s> {
+  pub fn new(source_text: &'s str) -> Self {
+    let mut line_starts = vec![0];
+    let mut utf16_line_starts = vec![0];
+    let mut utf16_offset = 0u32;
+    let mut chars = source_text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+      utf16_offset += c.len_utf16() as u32;
+      let is_line_break = match c {
+        '\r' => chars.peek().map(|&(_, next)| next) != Some('\n'),
+        '\n' | '\u{2028}' | '\u{2029}' => true,
+        _ => false,
+      };
+      if is_line_break {
+        line_starts.push((i + c.len_utf8()) as u32);
+        utf16_line_starts.push(utf16_offset);
+      }
+    }
+    Self {
+      source_text,
+      line_starts,
+      utf16_line_starts,
+    }
+  }
+
+  /// The position of a byte offset. Offsets past the end or inside a character are moved
+  /// back to the previous character boundary.
+  pub fn position(&self, offset: u32) -> Position {
+    let mut offset = (offset as usize).min(self.source_text.len());
+    while !self.source_text.is_char_boundary(offset) {
+      offset -= 1;
+    }
+    let offset = offset as u32;
+
+    let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+    let line_start = self.line_starts[line];
+    let utf16_column = self.source_text[line_start as usize..offset as usize]
+      .encode_utf16()
+      .count() as u32;
+    Position {
+      offset,
+      utf16_offset: self.utf16_line_starts[line] + utf16_column,
+      line: line as u32 + 1,
+      column: offset - line_start,
+      utf16_column,
+    }
+  }
+
+  pub fn line_count(&self) -> u32 {
+    self.line_starts.len() as u32
+  }
+
+  /// The text of a 1-based line, without its line break. Empty past the last line.
+  pub fn line_text(&self, line: u32) -> &'s str {
+    let Some(&start) = self.line_starts.get((line as usize).wrapping_sub(1)) else {
+      return "";
+    };
+    let end = self
+      .line_starts
+      .get(line as usize)
+      .map_or(self.source_text.len(), |&end| end as usize);
+    self.source_text[start as usize..end].trim_end_matches(['\n', '\r', '\u{2028}', '\u{2029}'])
+  }
+
+  /// The line and UTF-16 column of a UTF-16 offset, for ASTs whose spans were converted
+  /// to UTF-16.
+  pub fn utf16_line_column(&self, utf16_offset: u32) -> (u32, u32) {
+    let line = self
+      .utf16_line_starts
+      .partition_point(|&start| start <= utf16_offset)
+      - 1;
+    (line as u32 + 1, utf16_offset - self.utf16_line_starts[line])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_position_counts_utf16_columns() {
+    let index = LineIndex::new("const msg = \"Été 🎉\";\nfoo();");
+
+    let position = index.position(24);
+    assert_eq!(position.line, 1);
+    assert_eq!(position.column, 24);
+    assert_eq!(position.utf16_column, 20);
+
+    let position = index.position(26);
+    assert_eq!(position.line, 2);
+    assert_eq!(position.column, 0);
+    assert_eq!(position.utf16_offset, 22);
+    assert_eq!(index.utf16_line_column(22), (2, 0));
+  }
+
+  #[test]
+  fn test_line_breaks() {
+    let index = LineIndex::new("a\r\nb\rc\u{2028}d");
+
+    assert_eq!(index.position(3).line, 2);
+    assert_eq!(index.position(5).line, 3);
+    assert_eq!(index.position(9).line, 4);
+    // Inside the multi-byte line separator, and past the end.
+    assert_eq!(index.position(7).offset, 6);
+    assert_eq!(index.position(100).offset, 10);
+  }
+
+  #[test]
+  fn test_line_text() {
+    let index = LineIndex::new("a\r\nb\rc\u{2028}d");
+
+    assert_eq!(index.line_text(1), "a");
+    assert_eq!(index.line_text(2), "b");
+    assert_eq!(index.line_text(3), "c");
+    assert_eq!(index.line_text(4), "d");
+    assert_eq!(index.line_text(0), "");
+    assert_eq!(index.line_text(5), "");
+  }
+}
@@ -0,0 +1,204 @@
+use super::{Labels, Message, Text};
+
+pub const LABELS: Labels = Labels {
+  context: "Context",
+  solution: "Solution",
+  in_component: "In {component} → {help}",
+};
+
+const ISOLATED_DECLARATIONS_HELP: &str = "With isolatedDeclarations, the type of every export must be known without analyzing the rest of the program";
+
+pub fn text(message: Message) -> Text {
+  let (message, help, suggestion) = match message {
+    Message::Fallback => (
+      "{message}",
+      "Check the syntax around this line",
+      "See the JavaScript/TypeScript documentation",
+    ),
+    Message::DoubleOperator => (
+      "Invalid double operator",
+      "Two consecutive '=' operators are not valid. Did you mean '==' or '==='?",
+      "Replace '= =' with '==' for a comparison, or remove one '=' for an assignment: `{line}`",
+    ),
+    Message::MissingAssignmentValue => (
+      "Missing expression after the assignment operator",
+      "The '=' operator needs a value on its right",
+      "Add a value after '=': `const x = 5` or `const x = getValue()`",
+    ),
+    Message::IncompleteJsxAttribute => (
+      "Incomplete JSX attribute",
+      "A JSX attribute must have a value. For styles, use a JavaScript object",
+      "Example: `style={{ color: 'red' }}` or `style={styles.container}`",
+    ),
+    Message::UnexpectedToken => (
+      "{message}",
+      "An unexpected token was found. Check the operators, parentheses and commas",
+      "Check for a missing or extra character near the error",
+    ),
+    Message::UnclosedBrace => (
+      "missing closing brace '}'",
+      "There are {open_count} opening '{' but only {close_count} closing '}' in this block",
+      "Add '}' to close the block. Tip: use an editor with bracket pair colorization",
+    ),
+    Message::UnclosedBracket => (
+      "missing closing bracket ']'",
+      "There are {open_count} opening '[' but only {close_count} closing ']' in this block",
+      "Add ']' to close the block. Tip: use an editor with bracket pair colorization",
+    ),
+    Message::UnclosedParenthesis => (
+      "missing closing parenthesis ')'",
+      "There are {open_count} opening '(' but only {close_count} closing ')' in this block",
+      "Add ')' to close the block. Tip: use an editor with bracket pair colorization",
+    ),
+    Message::UnterminatedString => (
+      "Unterminated string",
+      "A string must be closed with the same kind of quote ({quote}) it was opened with",
+      "Add {quote} at the end of the string to close it",
+    ),
+    Message::UnterminatedTemplate => (
+      "Unterminated template literal",
+      "A template literal (`) must be closed with a backtick (`)",
+      "Add ` at the end of the template literal",
+    ),
+    Message::Unterminated => (
+      "{message}",
+      "A syntax element is not closed properly",
+      "Check the quotes, backticks and other delimiters",
+    ),
+    Message::ReservedWord => (
+      "Reserved word used as an identifier",
+      "The word '{word}' is reserved by JavaScript and cannot be used as a variable name",
+      "Choose another name, for example `my{capitalized}` or `{word}_value`",
+    ),
+    Message::ReservedWordUnknown => (
+      "Reserved word used as an identifier",
+      "This word is reserved by JavaScript and cannot be used as a variable name",
+      "Choose another name for your variable",
+    ),
+    Message::JsxSyntax => (
+      "{message}",
+      "JSX syntax error. Check the tags, attributes and expressions",
+      "JS expressions must be wrapped in braces: `{expression}`",
+    ),
+    Message::JsxStyle => (
+      "Invalid JSX style syntax",
+      "In JSX/React Native, styles must be passed as a JavaScript object",
+      "Use `style={{ prop: value }}` or `style={styles.styleName}`",
+    ),
+    Message::JsxClassAttribute => (
+      "Invalid 'class' attribute in JSX",
+      "In JSX, use 'className' instead of 'class' for CSS classes",
+      "Replace `class=` with `className=`",
+    ),
+    Message::JsxEventHandler => (
+      "Lowercase event handler",
+      "In JSX, event handlers are camelCase",
+      "Use `{camel_case}` instead of `{handler}`. In React Native, use `onPress`",
+    ),
+    Message::JsxForAttribute => (
+      "Invalid 'for' attribute in JSX",
+      "In JSX, use 'htmlFor' instead of 'for' on labels",
+      "Replace `for=` with `htmlFor=`",
+    ),
+    Message::TypeScriptSyntax => (
+      "{message}",
+      "TypeScript syntax error. Check the type annotations",
+      "See https://www.typescriptlang.org/docs/handbook/2/everyday-types.html",
+    ),
+    Message::EmptyTypeAnnotation => (
+      "Empty type annotation",
+      "A type annotation (:) must be followed by a valid type",
+      "Add the type: `const x: number = 5` or `const x: string = 'hello'`",
+    ),
+    Message::MissingParameterType => (
+      "Missing parameter type",
+      "A parameter with ':' must specify a type",
+      "Example: `function foo(param: string)`, or use `any` temporarily",
+    ),
+    Message::InvalidGeneric => (
+      "Invalid or empty generic",
+      "Generics must contain at least one type: Array<T>",
+      "Specify a type: `Array<string>` or `Promise<void>`",
+    ),
+    Message::ReadonlyAssignment => (
+      "Attempt to modify a readonly property",
+      "Properties marked 'readonly' cannot be modified after initialization",
+      "Remove 'readonly' if the property must change, or create a new instance",
+    ),
+    Message::ModuleSyntax => (
+      "{message}",
+      "import/export statements are only valid in ES modules",
+      "Check that the file is treated as a module (.mjs extension or \"type\": \"module\" in package.json)",
+    ),
+    Message::DefaultExportSyntax => (
+      "Invalid default export syntax",
+      "A default export does not take an '=' sign",
+      "Use `export default value` or `export default function() {}`",
+    ),
+    Message::NamedImportSyntax => (
+      "Invalid named import syntax",
+      "Named imports must be wrapped in braces",
+      "Use `import { name1, name2 } from 'module'`",
+    ),
+    Message::ImportAssertion => (
+      "Invalid import assertion",
+      "Import assertions have a specific syntax",
+      "Use `import data from './data.json' assert { type: 'json' }`",
+    ),
+    Message::ReturnOutsideFunctionJsx => (
+      "'return' used outside of a function",
+      "In React, JSX must be returned from inside a function or component",
+      "Move the return into a function: `function Component() { return <View />; }`",
+    ),
+    Message::ReturnOutsideFunction => (
+      "'return' used outside of a function",
+      "A 'return' statement can only be used inside a function body",
+      "Move the code into a function: `function myFunc() { return value; }`",
+    ),
+    Message::AwaitInNonAsyncFunction => (
+      "'await' used in a non-async function",
+      "The 'await' operator can only be used in a function marked 'async'",
+      "Add 'async' before 'function': `async function myFunc() { await promise; }`",
+    ),
+    Message::AwaitOutsideAsync => (
+      "'await' used outside of an async function",
+      "The 'await' operator can only be used in a function marked 'async'",
+      "Create an async function: `async function myFunc() { await promise; }` or use an IIFE: `(async () => { await promise; })()`",
+    ),
+    Message::YieldOutsideGenerator => (
+      "'yield' used outside of a generator",
+      "The 'yield' operator can only be used in a generator function (function*)",
+      "Create a generator: `function* myGenerator() { yield value; }`",
+    ),
+    Message::LoopControlOutsideLoop => (
+      "'{keyword}' used outside of a loop",
+      "A '{keyword}' statement can only be used inside a loop (for, while, do-while)",
+      "Move '{keyword}' into a loop: `for (let i = 0; i < 10; i++) { if (cond) {keyword} }`",
+    ),
+    Message::Redeclaration => (
+      "'{name}' is already declared",
+      "A variable declared with let, const or class cannot be redeclared in the same scope: '{name}' is declared more than once",
+      "Rename one of the declarations or reassign the existing variable: `{name} = value`",
+    ),
+    Message::MissingReturnType => (
+      "Missing explicit return type on an export",
+      ISOLATED_DECLARATIONS_HELP,
+      "Annotate the return type: `export function getName(): string { ... }`",
+    ),
+    Message::MissingTypeAnnotation => (
+      "Missing explicit type annotation on an export",
+      ISOLATED_DECLARATIONS_HELP,
+      "Annotate the type: `export const count: number = compute();`",
+    ),
+    Message::IsolatedDeclaration => (
+      "{message}",
+      ISOLATED_DECLARATIONS_HELP,
+      "Add an explicit type annotation to the exported value",
+    ),
+  };
+  Text {
+    message,
+    help,
+    suggestion,
+  }
+}
@@ -0,0 +1,204 @@
+use super::{Labels, Message, Text};
+
+pub const LABELS: Labels = Labels {
+  context: "Contexte",
+  solution: "Solution",
+  in_component: "Dans {component} → {help}",
+};
+
+const ISOLATED_DECLARATIONS_HELP: &str = "Avec isolatedDeclarations, le type de chaque export doit pouvoir être déterminé sans analyser le reste du programme";
+
+pub fn text(message: Message) -> Text {
+  let (message, help, suggestion) = match message {
+    Message::Fallback => (
+      "{message}",
+      "Vérifiez la syntaxe autour de cette ligne",
+      "Consultez la documentation JavaScript/TypeScript",
+    ),
+    Message::DoubleOperator => (
+      "Opérateur double invalide détecté",
+      "Deux opérateurs '=' consécutifs ne sont pas valides. Peut-être vouliez-vous '==' ou '==='?",
+      "Remplacez '= =' par '==' pour une comparaison, ou retirez un '=' si c'est une affectation: `{line}`",
+    ),
+    Message::MissingAssignmentValue => (
+      "Expression manquante après l'opérateur d'affectation",
+      "L'opérateur '=' nécessite une valeur à droite",
+      "Ajoutez une valeur après '=': `const x = 5` ou `const x = getValue()`",
+    ),
+    Message::IncompleteJsxAttribute => (
+      "Attribut JSX incomplet",
+      "Un attribut JSX doit avoir une valeur. Pour les styles, utilisez un objet JavaScript",
+      "Exemple: `style={{ color: 'red' }}` ou `style={styles.container}`",
+    ),
+    Message::UnexpectedToken => (
+      "{message}",
+      "Un token inattendu a été trouvé. Vérifiez les opérateurs, parenthèses et virgules",
+      "Vérifiez qu'il n'y a pas de caractère manquant ou en trop près de l'erreur",
+    ),
+    Message::UnclosedBrace => (
+      "accolade fermante '}' manquante",
+      "Il y a {open_count} '{' ouvrant(s) mais seulement {close_count} '}' fermant(s) dans ce bloc",
+      "Ajoutez '}' pour fermer le bloc. Astuce: utilisez un éditeur avec coloration des parenthèses",
+    ),
+    Message::UnclosedBracket => (
+      "crochet fermant ']' manquant",
+      "Il y a {open_count} '[' ouvrant(s) mais seulement {close_count} ']' fermant(s) dans ce bloc",
+      "Ajoutez ']' pour fermer le bloc. Astuce: utilisez un éditeur avec coloration des parenthèses",
+    ),
+    Message::UnclosedParenthesis => (
+      "parenthèse fermante ')' manquante",
+      "Il y a {open_count} '(' ouvrant(s) mais seulement {close_count} ')' fermant(s) dans ce bloc",
+      "Ajoutez ')' pour fermer le bloc. Astuce: utilisez un éditeur avec coloration des parenthèses",
+    ),
+    Message::UnterminatedString => (
+      "Chaîne de caractères non terminée",
+      "Une chaîne de caractères doit être fermée avec le même type de guillemet ({quote}) qu'à l'ouverture",
+      "Ajoutez {quote} à la fin de la chaîne pour la fermer",
+    ),
+    Message::UnterminatedTemplate => (
+      "Template literal non terminé",
+      "Un template literal (`) doit être fermé avec un backtick (`)",
+      "Ajoutez ` à la fin du template literal",
+    ),
+    Message::Unterminated => (
+      "{message}",
+      "Un élément de syntaxe n'est pas correctement fermé",
+      "Vérifiez les guillemets, backticks et autres délimiteurs",
+    ),
+    Message::ReservedWord => (
+      "Utilisation d'un mot réservé comme identifiant",
+      "Le mot '{word}' est réservé par JavaScript et ne peut pas être utilisé comme nom de variable",
+      "Choisissez un autre nom: `my{capitalized}` ou `{word}_value` par exemple",
+    ),
+    Message::ReservedWordUnknown => (
+      "Utilisation d'un mot réservé comme identifiant",
+      "Le mot '(mot réservé)' est réservé par JavaScript et ne peut pas être utilisé comme nom de variable",
+      "Choisissez un autre nom pour votre variable",
+    ),
+    Message::JsxSyntax => (
+      "{message}",
+      "Erreur de syntaxe JSX. Vérifiez les balises, attributs et expressions",
+      "Les expressions JS doivent être entre accolades: `{expression}`",
+    ),
+    Message::JsxStyle => (
+      "Syntaxe de style JSX invalide",
+      "En JSX/React Native, les styles doivent être passés comme objet JavaScript",
+      "Utilisez: `style={{ prop: value }}` ou `style={styles.nomDuStyle}`",
+    ),
+    Message::JsxClassAttribute => (
+      "Attribut 'class' invalide en JSX",
+      "En JSX, utilisez 'className' au lieu de 'class' pour les classes CSS",
+      "Remplacez `class=` par `className=`",
+    ),
+    Message::JsxEventHandler => (
+      "Gestionnaire d'événement en minuscules",
+      "En JSX, les gestionnaires d'événements utilisent le camelCase",
+      "Utilisez `{camel_case}` au lieu de `{handler}`. Pour React Native, utilisez `onPress`",
+    ),
+    Message::JsxForAttribute => (
+      "Attribut 'for' invalide en JSX",
+      "En JSX, utilisez 'htmlFor' au lieu de 'for' pour les labels",
+      "Remplacez `for=` par `htmlFor=`",
+    ),
+    Message::TypeScriptSyntax => (
+      "{message}",
+      "Erreur de syntaxe TypeScript. Vérifiez les annotations de type",
+      "Consultez: https://www.typescriptlang.org/docs/handbook/2/everyday-types.html",
+    ),
+    Message::EmptyTypeAnnotation => (
+      "Annotation de type vide",
+      "Une annotation de type (:) doit être suivie d'un type valide",
+      "Ajoutez le type: `const x: number = 5` ou `const x: string = 'hello'`",
+    ),
+    Message::MissingParameterType => (
+      "Type de paramètre manquant",
+      "Un paramètre avec ':' doit avoir un type spécifié",
+      "Exemple: `function foo(param: string)` ou utilisez `any` temporairement",
+    ),
+    Message::InvalidGeneric => (
+      "Générique invalide ou vide",
+      "Les génériques doivent contenir au moins un type: Array<T>",
+      "Spécifiez un type: `Array<string>` ou `Promise<void>`",
+    ),
+    Message::ReadonlyAssignment => (
+      "Tentative de modification d'une propriété readonly",
+      "Les propriétés marquées 'readonly' ne peuvent pas être modifiées après initialisation",
+      "Retirez 'readonly' si la modification est nécessaire, ou créez une nouvelle instance",
+    ),
+    Message::ModuleSyntax => (
+      "{message}",
+      "Les instructions import/export ne sont valides que dans les modules ES",
+      "Vérifiez que le fichier est traité comme un module (extension .mjs ou \"type\": \"module\" dans package.json)",
+    ),
+    Message::DefaultExportSyntax => (
+      "Syntaxe d'export default invalide",
+      "L'export default ne nécessite pas le signe '='",
+      "Utilisez: `export default value` ou `export default function() {}`",
+    ),
+    Message::NamedImportSyntax => (
+      "Syntaxe d'import nommé invalide",
+      "Les imports nommés doivent être entre accolades",
+      "Utilisez: `import { name1, name2 } from 'module'`",
+    ),
+    Message::ImportAssertion => (
+      "Assertion d'import invalide",
+      "Les assertions d'import (import assertions) ont une syntaxe spécifique",
+      "Utilisez: `import data from './data.json' assert { type: 'json' }`",
+    ),
+    Message::ReturnOutsideFunctionJsx => (
+      "'return' utilisé en dehors d'une fonction",
+      "En React, le JSX doit être retourné depuis l'intérieur d'une fonction ou composant",
+      "Placez le return dans une fonction: `function Component() { return <View />; }`",
+    ),
+    Message::ReturnOutsideFunction => (
+      "'return' utilisé en dehors d'une fonction",
+      "L'instruction 'return' ne peut être utilisée qu'à l'intérieur du corps d'une fonction",
+      "Placez le code dans une fonction: `function myFunc() { return value; }`",
+    ),
+    Message::AwaitInNonAsyncFunction => (
+      "'await' utilisé dans une fonction non-async",
+      "L'opérateur 'await' ne peut être utilisé que dans une fonction marquée 'async'",
+      "Ajoutez 'async' devant 'function': `async function myFunc() { await promise; }`",
+    ),
+    Message::AwaitOutsideAsync => (
+      "'await' utilisé en dehors d'une fonction async",
+      "L'opérateur 'await' ne peut être utilisé que dans une fonction marquée 'async'",
+      "Créez une fonction async: `async function myFunc() { await promise; }` ou utilisez une IIFE: `(async () => { await promise; })()`",
+    ),
+    Message::YieldOutsideGenerator => (
+      "'yield' utilisé en dehors d'un générateur",
+      "L'opérateur 'yield' ne peut être utilisé que dans une fonction génératrice (function*)",
+      "Créez un générateur: `function* myGenerator() { yield value; }`",
+    ),
+    Message::LoopControlOutsideLoop => (
+      "'{keyword}' utilisé en dehors d'une boucle",
+      "L'instruction '{keyword}' ne peut être utilisée qu'à l'intérieur d'une boucle (for, while, do-while)",
+      "Placez '{keyword}' dans une boucle: `for (let i = 0; i < 10; i++) { if (cond) {keyword} }`",
+    ),
+    Message::Redeclaration => (
+      "'{name}' est déjà déclaré",
+      "Une variable déclarée avec let, const ou class ne peut pas être redéclarée dans la même portée: '{name}' est déclaré plusieurs fois",
+      "Renommez l'une des déclarations ou réassignez la variable existante: `{name} = value`",
+    ),
+    Message::MissingReturnType => (
+      "Type de retour explicite manquant sur un export",
+      ISOLATED_DECLARATIONS_HELP,
+      "Annotez le type de retour: `export function getName(): string { ... }`",
+    ),
+    Message::MissingTypeAnnotation => (
+      "Annotation de type explicite manquante sur un export",
+      ISOLATED_DECLARATIONS_HELP,
+      "Annotez le type: `export const count: number = compute();`",
+    ),
+    Message::IsolatedDeclaration => (
+      "{message}",
+      ISOLATED_DECLARATIONS_HELP,
+      "Ajoutez une annotation de type explicite à la valeur exportée",
+    ),
+  };
+  Text {
+    message,
+    help,
+    suggestion,
+  }
+}
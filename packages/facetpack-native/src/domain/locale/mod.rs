@@ -0,0 +1,127 @@
+mod en;
+mod fr;
+
+use crate::domain::types::Locale;
+
+/// A diagnostic explanation in a catalog. Codes are not part of it, so they stay the
+/// same in every language.
+///
+/// Texts are templates where `{name}` is replaced by the argument of that name.
+pub struct Text {
+  pub message: &'static str,
+  pub help: &'static str,
+  pub suggestion: &'static str,
+}
+
+/// The words around diagnostics in formatted output.
+pub struct Labels {
+  pub context: &'static str,
+  pub solution: &'static str,
+  /// Template with `{component}` and `{help}`.
+  pub in_component: &'static str,
+}
+
+/// Every explanation the analyzers can give. Each catalog matches on all of them, so a
+/// missing translation does not compile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+  Fallback,
+  DoubleOperator,
+  MissingAssignmentValue,
+  IncompleteJsxAttribute,
+  UnexpectedToken,
+  UnclosedBrace,
+  UnclosedBracket,
+  UnclosedParenthesis,
+  UnterminatedString,
+  UnterminatedTemplate,
+  Unterminated,
+  ReservedWord,
+  ReservedWordUnknown,
+  JsxSyntax,
+  JsxStyle,
+  JsxClassAttribute,
+  JsxEventHandler,
+  JsxForAttribute,
+  TypeScriptSyntax,
+  EmptyTypeAnnotation,
+  MissingParameterType,
+  InvalidGeneric,
+  ReadonlyAssignment,
+  ModuleSyntax,
+  DefaultExportSyntax,
+  NamedImportSyntax,
+  ImportAssertion,
+  ReturnOutsideFunctionJsx,
+  ReturnOutsideFunction,
+  AwaitInNonAsyncFunction,
+  AwaitOutsideAsync,
+  YieldOutsideGenerator,
+  LoopControlOutsideLoop,
+  Redeclaration,
+  MissingReturnType,
+  MissingTypeAnnotation,
+  IsolatedDeclaration,
+}
+
+pub fn text(locale: Locale, message: Message) -> Text {
+  match locale {
+    Locale::En => en::text(message),
+    Locale::Fr => fr::text(message),
+  }
+}
+
+pub fn labels(locale: Locale) -> Labels {
+  match locale {
+    Locale::En => en::LABELS,
+    Locale::Fr => fr::LABELS,
+  }
+}
+
+/// Replaces the `{name}` placeholders of a template. Other braces are left as they are,
+/// so templates can show code like `style={{ color: 'red' }}`.
+pub fn render(template: &str, args: &[(&str, &str)]) -> String {
+  args
+    .iter()
+    .fold(template.to_string(), |text, (name, value)| {
+      text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_render_keeps_literal_braces() {
+    let text = render(
+      "Place '{keyword}' in a loop: `for (;;) { if (cond) {keyword} }`",
+      &[("keyword", "break")],
+    );
+
+    assert_eq!(
+      text,
+      "Place 'break' in a loop: `for (;;) { if (cond) break }`"
+    );
+  }
+
+  #[test]
+  fn test_catalogs() {
+    let en = text(Locale::En, Message::Redeclaration);
+    let fr = text(Locale::Fr, Message::Redeclaration);
+
+    assert_eq!(
+      render(en.message, &[("name", "x")]),
+      "'x' is already declared"
+    );
+    assert_eq!(render(fr.message, &[("name", "x")]), "'x' est déjà déclaré");
+    assert_eq!(labels(Locale::Fr).solution, "Solution");
+  }
+
+  #[test]
+  fn test_locale_from_tag() {
+    assert_eq!(Locale::from_tag("fr_FR.UTF-8"), Some(Locale::Fr));
+    assert_eq!(Locale::from_tag("en-GB"), Some(Locale::En));
+    assert_eq!(Locale::from_tag("C"), None);
+  }
+}
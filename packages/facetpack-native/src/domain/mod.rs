@@ -1,2 +1,4 @@
 pub mod error;
+pub mod line_index;
+pub mod locale;
 pub mod types;
@@ -1,5 +1,10 @@
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::Buffer;
 use napi_derive::napi;
 
+use crate::domain::locale;
+
 #[napi(string_enum)]
 #[derive(Debug, Clone, Copy, Default)]
 pub enum DiagnosticSeverity {
@@ -10,6 +15,69 @@ pub enum DiagnosticSeverity {
   Hint,
 }
 
+/// Output formats of `formatDiagnostics`.
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiagnosticFormat {
+  /// The `formatted` layout without colors.
+  #[default]
+  Text,
+  /// GitHub Actions workflow commands, shown as annotations on pull requests.
+  Github,
+  /// SARIF 2.1.0 log, for code scanning.
+  Sarif,
+  /// JSON array of Language Server Protocol diagnostics.
+  Lsp,
+}
+
+/// The language of diagnostic explanations. Error codes are the same in every language.
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+  #[default]
+  #[napi(value = "en")]
+  En,
+  #[napi(value = "fr")]
+  Fr,
+}
+
+impl Locale {
+  /// Parses a language tag or POSIX locale such as `fr`, `fr-CA` or `fr_FR.UTF-8`.
+  pub fn from_tag(tag: &str) -> Option<Self> {
+    let language = tag.split(['_', '-', '.', '@']).next()?;
+    match language.to_ascii_lowercase().as_str() {
+      "en" => Some(Locale::En),
+      "fr" => Some(Locale::Fr),
+      _ => None,
+    }
+  }
+
+  /// The locale of the environment from `LC_ALL`, `LC_MESSAGES` or `LANG`, English if
+  /// none is set or supported.
+  pub fn from_env() -> Self {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+      .iter()
+      .filter_map(|name| std::env::var(name).ok())
+      .find(|value| !value.is_empty())
+      .and_then(|value| Self::from_tag(&value))
+      .unwrap_or_default()
+  }
+}
+
+/// Another span a diagnostic refers to, such as the first declaration of a redeclared name.
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct RelatedLocation {
+  pub message: Option<String>,
+  pub filename: String,
+  pub line: u32,
+  pub column: u32,
+  pub end_line: u32,
+  pub end_column: u32,
+}
+
+/// Lines and columns are 1-based, and columns count UTF-16 code units like JavaScript
+/// strings and editors do.
 #[napi(object)]
 #[derive(Debug, Clone, Default)]
 pub struct Diagnostic {
@@ -21,6 +89,7 @@ pub struct Diagnostic {
   pub column: u32,
   pub end_line: Option<u32>,
   pub end_column: Option<u32>,
+  pub related: Vec<RelatedLocation>,
   pub snippet: Option<String>,
   pub label: Option<String>,
   pub help: Option<String>,
@@ -40,7 +109,8 @@ impl Diagnostic {
   const UNDERLINE: &'static str = "\x1b[4m";
   const RESET: &'static str = "\x1b[0m";
 
-  pub fn format(&self) -> String {
+  pub fn format(&self, locale: Locale) -> String {
+    let labels = locale::labels(locale);
     let mut output = String::new();
 
     let (severity_text, icon, color) = match self.severity {
@@ -133,8 +203,9 @@ impl Diagnostic {
 
     if let Some(help) = &self.help {
       output.push_str(&format!(
-        "\n  {}💡 Contexte:{} {}\n",
+        "\n  {}💡 {}:{} {}\n",
         Self::CYAN,
+        labels.context,
         Self::RESET,
         help
       ));
@@ -142,18 +213,47 @@ impl Diagnostic {
 
     if let Some(suggestion) = &self.suggestion {
       output.push_str(&format!(
-        "\n  {}✨ Solution:{} {}\n",
+        "\n  {}✨ {}:{} {}\n",
         Self::GREEN,
+        labels.solution,
         Self::RESET,
         suggestion
       ));
     }
 
     output.push('\n');
+    if no_color() {
+      Self::strip_ansi(&output)
+    } else {
+      output
+    }
+  }
+
+  /// Removes the ANSI escape codes of `format` output, for logs and plain text files.
+  pub fn strip_ansi(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+      if c == '\x1b' && chars.next_if_eq(&'[').is_some() {
+        // Skip the parameters up to the final byte of the sequence, such as `m`.
+        for c in chars.by_ref() {
+          if ('@'..='~').contains(&c) {
+            break;
+          }
+        }
+      } else {
+        output.push(c);
+      }
+    }
     output
   }
 }
 
+/// Colors are left out when `NO_COLOR` is set to a non-empty value (https://no-color.org).
+fn no_color() -> bool {
+  std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
 #[napi(string_enum)]
 #[derive(Debug, Clone, Copy, Default)]
 pub enum SourceType {
@@ -165,17 +265,46 @@ pub enum SourceType {
   Typescript,
 }
 
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct FacetPackOptions {
+  /// Language of diagnostic explanations, from `LANG` when not set.
+  pub locale: Option<Locale>,
+}
+
+/// How a file is parsed with respect to ES module syntax, regardless of its extension.
+/// `Unambiguous` parses it as a module only if it has `import` or `export` statements.
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleKind {
+  Module,
+  Script,
+  Unambiguous,
+}
+
 #[napi(object)]
 #[derive(Debug, Clone, Default)]
 pub struct ParseOptions {
+  pub locale: Option<Locale>,
   pub source_type: Option<SourceType>,
+  pub module_kind: Option<ModuleKind>,
+  pub jsx: Option<bool>,
   pub preserve_parens: Option<bool>,
+  pub allow_return_outside_function: Option<bool>,
+  pub allow_v8_intrinsics: Option<bool>,
+  pub comments: Option<bool>,
+  pub tokens: Option<bool>,
+  pub range: Option<bool>,
+  pub loc: Option<bool>,
+  /// Set to `false` to only check for errors, leaving `program` empty. Defaults to `true`.
+  pub ast: Option<bool>,
+  pub buffer: Option<bool>,
 }
 
 #[napi(object)]
-#[derive(Debug, Clone)]
 pub struct ParseResult {
   pub program: String,
+  pub buffer: Option<Buffer>,
   pub errors: Vec<String>,
   pub diagnostics: Vec<Diagnostic>,
   pub panicked: bool,
@@ -189,18 +318,129 @@ pub enum JsxRuntime {
   Classic,
 }
 
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HelperMode {
+  #[default]
+  #[napi(value = "runtime")]
+  Runtime,
+  #[napi(value = "inline")]
+  Inline,
+  #[napi(value = "external")]
+  External,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AstFormat {
+  #[napi(value = "estree")]
+  Estree,
+  #[napi(value = "babel")]
+  Babel,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ModuleFormat {
+  #[default]
+  #[napi(value = "esm")]
+  Esm,
+  #[napi(value = "metro-cjs")]
+  MetroCjs,
+  #[napi(value = "cjs")]
+  Cjs,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct HelperOptions {
+  pub mode: Option<HelperMode>,
+  pub module: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct InlineRequiresOptions {
+  pub block_list: Option<Vec<String>>,
+  pub non_inlined_requires: Option<Vec<String>>,
+}
+
 #[napi(object)]
 #[derive(Debug, Clone, Default)]
 pub struct TransformOptions {
+  pub locale: Option<Locale>,
   pub source_type: Option<SourceType>,
   pub jsx: Option<bool>,
   pub jsx_runtime: Option<JsxRuntime>,
   pub jsx_pragma: Option<String>,
   pub jsx_pragma_frag: Option<String>,
   pub jsx_import_source: Option<String>,
+  pub jsx_development: Option<bool>,
+  pub project_root: Option<String>,
   pub typescript: Option<bool>,
+  pub only_remove_type_imports: Option<bool>,
+  pub allow_declare_fields: Option<bool>,
+  pub allow_namespaces: Option<bool>,
+  pub optimize_const_enums: Option<bool>,
+  pub experimental_decorators: Option<bool>,
+  pub emit_decorator_metadata: Option<bool>,
   pub flow: Option<bool>,
   pub sourcemap: Option<bool>,
+  pub target: Option<String>,
+  pub helpers: Option<HelperOptions>,
+  pub input_source_map: Option<String>,
+  pub worklets: Option<bool>,
+  pub require_context: Option<bool>,
+  /// Extensions, without the dot, of the files `require.context()` can match. Defaults to
+  /// Metro's `js`, `jsx`, `json`, `ts` and `tsx`.
+  pub source_exts: Option<Vec<String>>,
+  /// Aliases of the resolver, applied to `require.context()` directories.
+  pub alias: Option<HashMap<String, String>>,
+  /// Also returns the transformed program as JSON in this format. It is parsed from the
+  /// returned `code`, so its positions (`start`, `end`, `loc`) refer to the generated code,
+  /// not to the source.
+  pub ast: Option<AstFormat>,
+  pub module_format: Option<ModuleFormat>,
+  /// Inlines the top-level `require()` of the module where they are used. With the `esm`
+  /// module format, imports are not lowered and only the `require()` calls of the source
+  /// are inlined.
+  pub inline_requires: Option<InlineRequiresOptions>,
+  pub define: Option<HashMap<String, String>>,
+  pub platform: Option<String>,
+  pub refresh: Option<bool>,
+  pub refresh_reg: Option<String>,
+  pub refresh_sig: Option<String>,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+  Import,
+  ReExport,
+  Require,
+  DynamicImport,
+  RequireResolve,
+  /// The directory of a `require.context()` call. Its file list changes when files are
+  /// added or removed, so caches of the file depend on it.
+  Context,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, Copy)]
+pub struct SourceSpan {
+  pub start: u32,
+  pub end: u32,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Dependency {
+  pub specifier: String,
+  pub kind: DependencyKind,
+  pub is_type_only: bool,
+  pub is_async: bool,
+  pub is_optional: bool,
+  pub span: SourceSpan,
 }
 
 #[napi(object)]
@@ -210,6 +450,42 @@ pub struct TransformResult {
   pub map: Option<String>,
   pub errors: Vec<String>,
   pub diagnostics: Vec<Diagnostic>,
+  pub helpers_mode: HelperMode,
+  pub dependencies: Vec<Dependency>,
+  pub ast: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct TransformInput {
+  pub filename: String,
+  pub source: String,
+  pub options: Option<TransformOptions>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct TransformBatchResult {
+  pub filename: String,
+  pub result: Option<TransformResult>,
+  pub error: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct DeclarationOptions {
+  pub locale: Option<Locale>,
+  pub strip_internal: Option<bool>,
+  pub sourcemap: Option<bool>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct DeclarationResult {
+  pub code: String,
+  pub map: Option<String>,
+  pub errors: Vec<String>,
+  pub diagnostics: Vec<Diagnostic>,
 }
 
 #[napi(object)]
@@ -221,6 +497,7 @@ pub struct MinifyOptions {
   pub drop_console: Option<bool>,
   pub drop_debugger: Option<bool>,
   pub sourcemap: Option<bool>,
+  pub input_source_map: Option<String>,
 }
 
 #[napi(object)]
@@ -2,38 +2,51 @@
 
 mod cqrs;
 mod domain;
+mod task;
 
+use napi::bindgen_prelude::{AbortSignal, AsyncTask};
 use napi_derive::napi;
 
 pub use domain::types::*;
 
-use cqrs::command::{MinifyCommand, ShakeCommand, TransformCommand};
+use cqrs::command::{
+  DeclarationCommand, MinifyCommand, ShakeCommand, TransformBatchCommand, TransformCommand,
+};
 use cqrs::query::{
-  AnalyzeBatchQuery, AnalyzeQuery, ParseQuery, ResolveBatchQuery, ResolveQuery, ResolveResult,
-  ResolverOptions,
+  AnalyzeBatchQuery, AnalyzeQuery, FormatDiagnosticsQuery, ParseQuery, ResolveBatchQuery,
+  ResolveQuery, ResolveResult, ResolverOptions,
 };
 use cqrs::traits::{Command, Query};
+use task::{
+  AnalyzeTask, DeclarationTask, MinifyTask, ParseTask, ResolveTask, ShakeTask, TransformBatchTask,
+  TransformTask,
+};
 
 #[napi]
 pub struct FacetPack {
-  _config: FacetPackConfig,
+  config: FacetPackConfig,
 }
 
 #[derive(Default)]
-struct FacetPackConfig {}
+struct FacetPackConfig {
+  locale: Option<Locale>,
+}
 
 impl Default for FacetPack {
   fn default() -> Self {
-    Self::new()
+    Self::new(None)
   }
 }
 
 #[napi]
 impl FacetPack {
   #[napi(constructor)]
-  pub fn new() -> Self {
+  pub fn new(options: Option<FacetPackOptions>) -> Self {
+    let options = options.unwrap_or_default();
     Self {
-      _config: FacetPackConfig::default(),
+      config: FacetPackConfig {
+        locale: options.locale,
+      },
     }
   }
 
@@ -44,10 +57,22 @@ impl FacetPack {
     source_text: String,
     options: Option<ParseOptions>,
   ) -> napi::Result<ParseResult> {
-    let query = ParseQuery::new(filename, source_text, options);
+    let query = ParseQuery::new(filename, source_text, Some(self.parse_options(options)));
     query.execute().map_err(Into::into)
   }
 
+  #[napi]
+  pub fn parse_async(
+    &self,
+    filename: String,
+    source_text: String,
+    options: Option<ParseOptions>,
+    signal: Option<AbortSignal>,
+  ) -> AsyncTask<ParseTask> {
+    let query = ParseQuery::new(filename, source_text, Some(self.parse_options(options)));
+    AsyncTask::with_optional_signal(ParseTask(query), signal)
+  }
+
   #[napi]
   pub fn transform(
     &self,
@@ -55,10 +80,24 @@ impl FacetPack {
     source_text: String,
     options: Option<TransformOptions>,
   ) -> napi::Result<TransformResult> {
-    let command = TransformCommand::new(filename, source_text, options);
+    let command =
+      TransformCommand::new(filename, source_text, Some(self.transform_options(options)));
     command.execute().map_err(Into::into)
   }
 
+  #[napi]
+  pub fn transform_async(
+    &self,
+    filename: String,
+    source_text: String,
+    options: Option<TransformOptions>,
+    signal: Option<AbortSignal>,
+  ) -> AsyncTask<TransformTask> {
+    let command =
+      TransformCommand::new(filename, source_text, Some(self.transform_options(options)));
+    AsyncTask::with_optional_signal(TransformTask(command), signal)
+  }
+
   #[napi]
   pub fn resolve(
     &self,
@@ -69,6 +108,33 @@ impl FacetPack {
     let query = ResolveQuery::new(directory, specifier, options);
     query.execute().map_err(Into::into)
   }
+
+  #[napi]
+  pub fn resolve_async(
+    &self,
+    directory: String,
+    specifier: String,
+    options: Option<ResolverOptions>,
+    signal: Option<AbortSignal>,
+  ) -> AsyncTask<ResolveTask> {
+    let query = ResolveQuery::new(directory, specifier, options);
+    AsyncTask::with_optional_signal(ResolveTask(query), signal)
+  }
+}
+
+impl FacetPack {
+  /// The options of a call, with the instance settings as defaults.
+  fn parse_options(&self, options: Option<ParseOptions>) -> ParseOptions {
+    let mut options = options.unwrap_or_default();
+    options.locale = options.locale.or(self.config.locale);
+    options
+  }
+
+  fn transform_options(&self, options: Option<TransformOptions>) -> TransformOptions {
+    let mut options = options.unwrap_or_default();
+    options.locale = options.locale.or(self.config.locale);
+    options
+  }
 }
 
 #[napi]
@@ -81,6 +147,27 @@ pub fn parse_sync(
   query.execute().map_err(Into::into)
 }
 
+#[napi]
+pub fn parse(
+  filename: String,
+  source_text: String,
+  options: Option<ParseOptions>,
+  signal: Option<AbortSignal>,
+) -> AsyncTask<ParseTask> {
+  let query = ParseQuery::new(filename, source_text, options);
+  AsyncTask::with_optional_signal(ParseTask(query), signal)
+}
+
+/// Renders diagnostics as plain text, GitHub Actions annotations, SARIF or LSP JSON.
+#[napi]
+pub fn format_diagnostics(
+  diagnostics: Vec<Diagnostic>,
+  format: DiagnosticFormat,
+) -> napi::Result<String> {
+  let query = FormatDiagnosticsQuery::new(diagnostics, format);
+  query.execute().map_err(Into::into)
+}
+
 #[napi]
 pub fn transform_sync(
   filename: String,
@@ -91,6 +178,32 @@ pub fn transform_sync(
   command.execute().map_err(Into::into)
 }
 
+#[napi]
+pub fn transform(
+  filename: String,
+  source_text: String,
+  options: Option<TransformOptions>,
+  signal: Option<AbortSignal>,
+) -> AsyncTask<TransformTask> {
+  let command = TransformCommand::new(filename, source_text, options);
+  AsyncTask::with_optional_signal(TransformTask(command), signal)
+}
+
+#[napi]
+pub fn transform_batch_sync(files: Vec<TransformInput>) -> napi::Result<Vec<TransformBatchResult>> {
+  let command = TransformBatchCommand::new(files);
+  command.execute().map_err(Into::into)
+}
+
+#[napi]
+pub fn transform_batch(
+  files: Vec<TransformInput>,
+  signal: Option<AbortSignal>,
+) -> AsyncTask<TransformBatchTask> {
+  let command = TransformBatchCommand::new(files);
+  AsyncTask::with_optional_signal(TransformBatchTask(command), signal)
+}
+
 #[napi]
 pub fn resolve_sync(
   directory: String,
@@ -101,6 +214,17 @@ pub fn resolve_sync(
   query.execute().map_err(Into::into)
 }
 
+#[napi]
+pub fn resolve(
+  directory: String,
+  specifier: String,
+  options: Option<ResolverOptions>,
+  signal: Option<AbortSignal>,
+) -> AsyncTask<ResolveTask> {
+  let query = ResolveQuery::new(directory, specifier, options);
+  AsyncTask::with_optional_signal(ResolveTask(query), signal)
+}
+
 #[napi]
 pub fn resolve_batch_sync(
   directory: String,
@@ -121,12 +245,33 @@ pub fn minify_sync(
   command.execute().map_err(Into::into)
 }
 
+#[napi]
+pub fn minify(
+  code: String,
+  filename: String,
+  options: Option<MinifyOptions>,
+  signal: Option<AbortSignal>,
+) -> AsyncTask<MinifyTask> {
+  let command = MinifyCommand::new(code, filename, options);
+  AsyncTask::with_optional_signal(MinifyTask(command), signal)
+}
+
 #[napi]
 pub fn analyze_sync(filename: String, source_text: String) -> napi::Result<ModuleAnalysis> {
   let query = AnalyzeQuery::new(filename, source_text);
   query.execute().map_err(Into::into)
 }
 
+#[napi]
+pub fn analyze(
+  filename: String,
+  source_text: String,
+  signal: Option<AbortSignal>,
+) -> AsyncTask<AnalyzeTask> {
+  let query = AnalyzeQuery::new(filename, source_text);
+  AsyncTask::with_optional_signal(AnalyzeTask(query), signal)
+}
+
 #[napi]
 pub fn analyze_batch_sync(modules: Vec<ModuleInput>) -> napi::Result<Vec<ModuleAnalysis>> {
   let query = AnalyzeBatchQuery::new(modules);
@@ -142,3 +287,35 @@ pub fn shake_sync(
   let command = ShakeCommand::new(filename, source_text, used_exports);
   command.execute().map_err(Into::into)
 }
+
+#[napi]
+pub fn shake(
+  filename: String,
+  source_text: String,
+  used_exports: Vec<String>,
+  signal: Option<AbortSignal>,
+) -> AsyncTask<ShakeTask> {
+  let command = ShakeCommand::new(filename, source_text, used_exports);
+  AsyncTask::with_optional_signal(ShakeTask(command), signal)
+}
+
+#[napi]
+pub fn isolated_declaration_sync(
+  filename: String,
+  source_text: String,
+  options: Option<DeclarationOptions>,
+) -> napi::Result<DeclarationResult> {
+  let command = DeclarationCommand::new(filename, source_text, options);
+  command.execute().map_err(Into::into)
+}
+
+#[napi]
+pub fn isolated_declaration(
+  filename: String,
+  source_text: String,
+  options: Option<DeclarationOptions>,
+  signal: Option<AbortSignal>,
+) -> AsyncTask<DeclarationTask> {
+  let command = DeclarationCommand::new(filename, source_text, options);
+  AsyncTask::with_optional_signal(DeclarationTask(command), signal)
+}
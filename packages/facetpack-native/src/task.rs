@@ -0,0 +1,44 @@
+use napi::{Env, Task};
+
+use crate::cqrs::command::{
+  DeclarationCommand, MinifyCommand, ShakeCommand, TransformBatchCommand, TransformCommand,
+};
+use crate::cqrs::query::{AnalyzeQuery, ParseQuery, ResolveQuery, ResolveResult};
+use crate::cqrs::traits::{Command, Query};
+use crate::domain::types::{
+  DeclarationResult, MinifyResult, ModuleAnalysis, ParseResult, ShakeResult, TransformBatchResult,
+  TransformResult,
+};
+
+/// Declares a task running a command or query on the libuv thread pool, off the JS thread.
+macro_rules! task {
+  ($(#[$attr:meta])* $name:ident($inner:ty) -> $output:ty) => {
+    $(#[$attr])*
+    pub struct $name(pub $inner);
+
+    impl Task for $name {
+      type Output = $output;
+      type JsValue = $output;
+
+      fn compute(&mut self) -> napi::Result<Self::Output> {
+        self.0.execute().map_err(Into::into)
+      }
+
+      fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output)
+      }
+    }
+  };
+}
+
+task!(ParseTask(ParseQuery) -> ParseResult);
+task!(TransformTask(TransformCommand) -> TransformResult);
+task!(
+  /// Transforms the files across the Rayon thread pool from a single libuv thread.
+  TransformBatchTask(TransformBatchCommand) -> Vec<TransformBatchResult>
+);
+task!(ResolveTask(ResolveQuery) -> ResolveResult);
+task!(MinifyTask(MinifyCommand) -> MinifyResult);
+task!(AnalyzeTask(AnalyzeQuery) -> ModuleAnalysis);
+task!(ShakeTask(ShakeCommand) -> ShakeResult);
+task!(DeclarationTask(DeclarationCommand) -> DeclarationResult);
@@ -30,6 +30,7 @@ export function minify(input: MetroMinifyInput): MetroMinifyOutput {
     dropConsole: input.config.drop_console ?? false,
     dropDebugger: input.config.drop_debugger ?? true,
     sourcemap: input.map !== undefined,
+    inputSourceMap: input.map,
   }
 
   const originalSize = Buffer.byteLength(input.code, 'utf8')
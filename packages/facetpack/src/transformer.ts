@@ -1,6 +1,5 @@
-import { transformSync, JsxRuntime, resolveBatchSync, parseSync } from '@ecrindigital/facetpack-native'
-import { parse } from '@babel/parser'
-import type { TransformParams, TransformResult, FacetpackOptions } from './types'
+import { transformSync, AstFormat, DependencyKind, JsxRuntime, resolveBatchSync, parseSync, type Dependency } from '@ecrindigital/facetpack-native'
+import type { TransformParams, TransformResult, TransformOptions, FacetpackOptions } from './types'
 import { setCachedResolutions } from './cache'
 import { globalStats } from './stats'
 
@@ -18,24 +17,22 @@ const ANSI = {
   reset: '\x1b[0m',
 } as const
 
-const IMPORT_REGEX = /(?:import|export)\s+(?:[\s\S]*?\s+from\s+)?['"]([^'"]+)['"]/g
-const REQUIRE_REGEX = /require\s*\(\s*['"]([^'"]+)['"]\s*\)/g
 
-const BABEL_REQUIRED_PATTERNS: readonly RegExp[] = [
+const REQUIRE_CONTEXT_REGEX = /require\.context\s*\(/
+
+const WORKLET_PATTERNS: readonly RegExp[] = [
   /'worklet'/,
   /"worklet"/,
-  /require\.context\s*\(/,
   /useAnimatedStyle/,
   /useAnimatedProps/,
   /useAnimatedScrollHandler/,
   /useAnimatedGestureHandler/,
   /useAnimatedReaction/,
   /useDerivedValue/,
-  /useAnimatedSensor/,
   /useFrameCallback/,
-  /useScrollViewOffset/,
   /runOnUI/,
-  /runOnJS/,
+  /Gesture\./,
+  /with(?:Timing|Spring|Decay|Repeat)/,
 ]
 
 const DEFAULT_OPTIONS: Required<FacetpackOptions> = {
@@ -128,22 +125,10 @@ const fallback = new FallbackTransformerManager()
 
 logger.logStartup()
 
-function extractSpecifiers(code: string): string[] {
-  const specifiers = new Set<string>()
-  let match: RegExpExecArray | null
-
-  while ((match = IMPORT_REGEX.exec(code)) !== null) {
-    if (match[1]) specifiers.add(match[1])
-  }
-  while ((match = REQUIRE_REGEX.exec(code)) !== null) {
-    if (match[1]) specifiers.add(match[1])
-  }
-
-  return Array.from(specifiers)
-}
-
-function preResolveImports(filename: string, code: string, sourceExts: string[]): void {
-  const specifiers = extractSpecifiers(code)
+function preResolveImports(filename: string, dependencies: Dependency[], sourceExts: string[]): void {
+  const specifiers = Array.from(
+    new Set(dependencies.filter(d => !d.isTypeOnly && d.kind !== DependencyKind.Context).map(d => d.specifier))
+  )
   if (specifiers.length === 0) return
 
   const directory = filename.substring(0, filename.lastIndexOf('/'))
@@ -167,8 +152,8 @@ function preResolveImports(filename: string, code: string, sourceExts: string[])
   setCachedResolutions(filename, resolutions)
 }
 
-function requiresBabelTransform(src: string): boolean {
-  return BABEL_REQUIRED_PATTERNS.some(pattern => pattern.test(src))
+function containsWorklets(src: string): boolean {
+  return WORKLET_PATTERNS.some(pattern => pattern.test(src))
 }
 
 function isNodeModules(filename: string): boolean {
@@ -181,10 +166,8 @@ function getFileExtension(filename: string): string | undefined {
 
 function getTransformDecision(
   filename: string,
-  src: string,
   opts: Required<FacetpackOptions>
 ): TransformDecision {
-  if (requiresBabelTransform(src)) return 'babel'
   if (isNodeModules(filename)) return 'babel'
 
   const ext = getFileExtension(filename)
@@ -223,9 +206,9 @@ function transformWithOxc(
   filename: string,
   src: string,
   opts: Required<FacetpackOptions>,
-  isDev: boolean
+  metroOptions: TransformOptions
 ): TransformResult {
-  const parseResult = parseSync(filename, src)
+  const parseResult = parseSync(filename, src, { ast: false })
 
   if (parseResult.errors.length > 0) {
     const error = parseResult.diagnostics.length > 0
@@ -242,24 +225,27 @@ function transformWithOxc(
     ...(isClassic
       ? { jsxPragma: opts.jsxPragma, jsxPragmaFrag: opts.jsxPragmaFrag }
       : { jsxImportSource: opts.jsxImportSource }),
+    jsxDevelopment: metroOptions.dev,
+    projectRoot: metroOptions.projectRoot,
     typescript: opts.typescript,
-    sourcemap: isDev,
+    sourcemap: metroOptions.dev,
+    worklets: containsWorklets(src),
+    requireContext: REQUIRE_CONTEXT_REGEX.test(src),
+    sourceExts: opts.sourceExts,
+    platform: metroOptions.platform,
+    refresh: metroOptions.dev && metroOptions.hot,
+    ast: AstFormat.Babel,
   })
 
   if (result.errors.length > 0) {
     throw new Error(`Facetpack transform error in ${filename}:\n${result.errors.join('\n')}`)
   }
 
-  preResolveImports(filename, result.code, opts.sourceExts)
+  preResolveImports(filename, result.dependencies, opts.sourceExts)
   globalStats.flush()
 
-  const ast = parse(result.code, {
-    sourceType: 'unambiguous',
-    plugins: ['jsx'],
-  })
-
   return {
-    ast,
+    ast: JSON.parse(result.ast!),
     code: result.code,
     map: result.map ? JSON.parse(result.map) : null,
   }
@@ -275,7 +261,7 @@ export function transform(params: TransformParams): TransformResult {
 
   globalStats.registerExitHandler()
 
-  const decision = getTransformDecision(filename, src, opts)
+  const decision = getTransformDecision(filename, opts)
   globalStats.recordTransform(decision)
   logger.logTransform(decision, filename)
 
@@ -284,7 +270,7 @@ export function transform(params: TransformParams): TransformResult {
   }
 
   try {
-    return transformWithOxc(filename, src, opts, metroOptions.dev)
+    return transformWithOxc(filename, src, opts, metroOptions)
   } catch (error) {
     if ((error as any).isParseError) {
       throw error
@@ -301,7 +287,7 @@ export function createTransformer(customOptions: FacetpackOptions = {}): Transfo
   return {
     transform(params: TransformParams): TransformResult {
       const { filename, src, options: metroOptions } = params
-      const decision = getTransformDecision(filename, src, opts)
+      const decision = getTransformDecision(filename, opts)
 
       globalStats.recordTransform(decision)
 
@@ -309,7 +295,7 @@ export function createTransformer(customOptions: FacetpackOptions = {}): Transfo
         return fallback.get().transform(params)
       }
 
-      return transformWithOxc(filename, src, opts, metroOptions.dev)
+      return transformWithOxc(filename, src, opts, metroOptions)
     },
   }
 }